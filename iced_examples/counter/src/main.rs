@@ -118,7 +118,7 @@ impl Application for Counter {
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         let center = column![
             button("Increment").on_press(Message::IncrementPressed),
             text(self.value).size(50),
@@ -76,7 +76,7 @@ impl MultiApplication for Counter {
         }
     }
 
-    fn view(&self, _id: iced::window::Id) -> Element<Message> {
+    fn view(&self, _id: iced::window::Id) -> Element<'_, Message> {
         column![
             Space::with_height(Length::Fill),
             button("Increment").on_press(Message::IncrementPressed),
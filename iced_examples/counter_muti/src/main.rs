@@ -37,6 +37,7 @@ enum WindowInfo {
     Left,
     Right,
     PopUp,
+    Lock,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -103,6 +104,9 @@ impl MultiApplication for Counter {
     fn remove_id(&mut self, id: iced::window::Id) {
         self.ids.remove(&id);
     }
+    fn lock_surface_added(&mut self, id: iced::window::Id) {
+        self.ids.insert(id, WindowInfo::Lock);
+    }
     fn update(&mut self, message: Message) -> Command<Message> {
         use iced::keyboard;
         use iced::keyboard::key::Named;
@@ -208,13 +212,19 @@ impl MultiApplication for Counter {
         }
     }
 
-    fn view(&self, id: iced::window::Id) -> Element<Message> {
+    fn view(&self, id: iced::window::Id) -> Element<'_, Message> {
         if let Some(WindowInfo::Left) = self.id_info(id) {
             return button("close left").on_press(Message::Close(id)).into();
         }
         if let Some(WindowInfo::Right) = self.id_info(id) {
             return button("close right").on_press(Message::Close(id)).into();
         }
+        if let Some(WindowInfo::Lock) = self.id_info(id) {
+            return container(button("unlock").on_press(Message::UnlockSession))
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .into();
+        }
         if let Some(WindowInfo::PopUp) = self.id_info(id) {
             return container(button("close PopUp").on_press(Message::Close(id)))
                 .center_x(Length::Fill)
@@ -233,6 +243,7 @@ impl MultiApplication for Counter {
             text(self.value).size(50),
             button("newwindowLeft").on_press(Message::NewWindowLeft),
             button("newwindowRight").on_press(Message::NewWindowRight),
+            button("lock").on_press(Message::LockSession),
         ]
         .align_x(Alignment::Center)
         .padding(20)
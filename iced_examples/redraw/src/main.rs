@@ -51,7 +51,7 @@ impl Panel {
         Task::none()
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         container(LoadingBar::default()).into()
     }
 }
@@ -58,7 +58,7 @@ impl MultiApplication for Counter {
     fn namespace(&self) -> String {
         String::from("Counter - Iced")
     }
-    fn view(&self, id: iced::window::Id) -> Element<Message> {
+    fn view(&self, id: iced::window::Id) -> Element<'_, Message> {
         container(
             column![
                 container(button(text("hello")).on_press(Message::CloseWindow(id)))
@@ -1,6 +1,6 @@
 use iced::widget::{button, row};
-use iced::{Color, Element, Length, Task as Command, Theme};
-use iced_layershell::actions::ActionCallback;
+use iced::{Color, Element, Length, Point, Rectangle, Size, Task as Command, Theme};
+use iced_layershell::region::Region;
 use iced_layershell::settings::{LayerShellSettings, Settings};
 use iced_layershell::to_layer_message;
 use iced_layershell::Application;
@@ -43,24 +43,20 @@ impl Application for InputRegionExample {
         match message {
             Message::SetRegion => {
                 self.0 = !self.0;
-                let val = self.0;
-                Command::done(Message::SetInputRegion(ActionCallback::new(
-                    move |region| {
-                        if val {
-                            // Only the button
-                            region.add(0, 0, 400, 70);
-                        } else {
-                            // Entire Screen
-                            region.add(0, 0, 400, 400);
-                        }
-                    },
-                )))
+                let region = if self.0 {
+                    // Only the button
+                    Region::from(Rectangle::new(Point::ORIGIN, Size::new(400., 70.)))
+                } else {
+                    // Entire Screen
+                    Region::full()
+                };
+                Command::done(Message::InputRegionChange(region))
             }
             _ => unreachable!(),
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         row![
             button(if self.0 { "Set region" } else { "Reset region" })
             .on_press(Message::SetRegion)
@@ -1,10 +1,13 @@
-use crate::reexport::{Anchor, Layer, WlRegion};
+use crate::animation::Easing;
+use crate::reexport::{Anchor, KeyboardInteractivity, Layer, WlRegion};
+use crate::region::Region;
 use iced::window::Id as IcedId;
 use iced_core::mouse::Interaction;
 use layershellev::id::Id as LayerId;
-use layershellev::NewLayerShellSettings;
+use layershellev::{CustomCursor, NewLayerShellSettings};
 
 use std::sync::Arc;
+use std::time::Duration;
 
 pub(crate) type LayerShellActionVec = Vec<LayerShellAction>;
 
@@ -15,6 +18,14 @@ pub(crate) enum LayerShellAction {
     CustomActionsWithId(LayershellCustomActionsWithIdInner),
     RedrawAll,
     RedrawWindow(LayerId), // maybe one day it is useful, but now useless
+    /// mark the whole surface as opaque or not, None is the main window
+    OpaqueRegion(Option<LayerId>, bool),
+    /// the scale factor of the application changed, the regions are in its logical
+    /// coordinates. None is the main window
+    ScaleFactor(Option<LayerId>, f64),
+    /// let the pointer events pass through the surface or not, the declared input region is
+    /// restored after. None is the main window
+    MousePassthrough(Option<LayerId>, bool),
     NewMenu((IcedNewPopupSettings, iced_core::window::Id)),
 }
 
@@ -83,6 +94,32 @@ pub enum LayershellCustomActions {
     AnchorSizeChange(Anchor, (u32, u32)),
     MarginChange((i32, i32, i32, i32)),
     SizeChange((u32, u32)),
+    ExclusiveZoneChange(i32),
+    /// change the keyboard interactivity, OnDemand needs layer shell v4
+    KeyboardInteractivityChange(KeyboardInteractivity),
+    /// set the edge of the exclusive zone when anchored to a corner, needs layer shell v5
+    ExclusiveEdgeChange(Anchor),
+    /// slide the margin to the new one, in the order of top, right, bottom, left
+    AnimatedMarginChange {
+        margin: (i32, i32, i32, i32),
+        duration: Duration,
+        easing: Easing,
+    },
+    /// resize the layershell step by step
+    AnimatedSizeChange {
+        size: (u32, u32),
+        duration: Duration,
+        easing: Easing,
+    },
+    /// set the opacity of the layershell, from 0.0 to 1.0, applied by the compositor without
+    /// redrawing. It needs wp_alpha_modifier_v1
+    OpacityChange(f64),
+    /// fade the layershell to the new opacity, it needs wp_alpha_modifier_v1
+    AnimatedOpacityChange {
+        opacity: f64,
+        duration: Duration,
+        easing: Easing,
+    },
     VirtualKeyboardPressed {
         time: u32,
         key: u32,
@@ -93,6 +130,8 @@ pub enum LayershellCustomActions {
         id: IcedId,
     },
     SetInputRegion(ActionCallback),
+    /// set the input region to the union of rectangles in logical coordinates, see [Region]
+    InputRegionChange(Region),
     NewPopUp {
         settings: IcedNewPopupSettings,
         id: IcedId,
@@ -104,6 +143,14 @@ pub enum LayershellCustomActions {
     /// is same with WindowAction::Close(id)
     RemoveWindow(IcedId),
     ForgetLastOutput,
+    /// show the image as the pointer cursor, instead of the cursor of the mouse interaction.
+    /// None goes back to the cursor of the mouse interaction
+    SetCustomCursor(Option<CustomCursor>),
+    /// lock the session with ext-session-lock, a lock surface is created on every output. Only
+    /// works in the multi window mode.
+    LockSession,
+    /// unlock the session, the lock surfaces are removed and the layershells are shown again
+    UnlockSession,
 }
 
 /// Please do not use this struct directly
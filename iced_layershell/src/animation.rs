@@ -0,0 +1,312 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use layershellev::{id::Id as LayerId, ReturnData, WindowState};
+
+/// The easing curve of an animated action
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// map the progress of the animation, from 0 to 1, to the progress of the value
+    pub fn apply(&self, progress: f32) -> f32 {
+        let progress = progress.clamp(0., 1.);
+        match self {
+            Self::Linear => progress,
+            Self::EaseIn => progress.powi(3),
+            Self::EaseOut => 1. - (1. - progress).powi(3),
+            Self::EaseInOut => {
+                if progress < 0.5 {
+                    4. * progress.powi(3)
+                } else {
+                    1. - (-2. * progress + 2.).powi(3) / 2.
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn progress(start: Instant, duration: Duration, now: Instant) -> f32 {
+    let duration = duration.as_secs_f32();
+    if duration <= 0. {
+        return 1.;
+    }
+    (now.duration_since(start).as_secs_f32() / duration).min(1.)
+}
+
+fn lerp(from: i32, to: i32, progress: f32) -> i32 {
+    (from as f32 + (to as f32 - from as f32) * progress).round() as i32
+}
+
+/// the animated property of a layershell
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Property {
+    Margin((i32, i32, i32, i32)),
+    Size((u32, u32)),
+    Opacity(f64),
+}
+
+impl Property {
+    fn is_same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    fn interpolate(&self, to: &Self, progress: f32) -> Self {
+        match (*self, *to) {
+            (Self::Margin(from), Self::Margin(to)) => Self::Margin((
+                lerp(from.0, to.0, progress),
+                lerp(from.1, to.1, progress),
+                lerp(from.2, to.2, progress),
+                lerp(from.3, to.3, progress),
+            )),
+            // NOTE: 0 keeps the axis stretched between the two anchors, so it is not animated
+            (Self::Size(from), Self::Size(to)) => Self::Size((
+                if to.0 == 0 {
+                    0
+                } else {
+                    lerp(from.0 as i32, to.0 as i32, progress) as u32
+                },
+                if to.1 == 0 {
+                    0
+                } else {
+                    lerp(from.1 as i32, to.1 as i32, progress) as u32
+                },
+            )),
+            (Self::Opacity(from), Self::Opacity(to)) => {
+                Self::Opacity(from + (to - from) * progress as f64)
+            }
+            _ => *to,
+        }
+    }
+
+    fn apply<T>(&self, ev: &mut WindowState<T>, id: LayerId) {
+        match *self {
+            Self::Margin(margin) => {
+                if let Some(unit) = ev.get_unit_with_id(id) {
+                    unit.set_margin(margin);
+                }
+            }
+            Self::Size(size) => {
+                if let Some(unit) = ev.get_unit_with_id(id) {
+                    unit.set_size(size);
+                }
+            }
+            Self::Opacity(opacity) => ev.append_return_data(ReturnData::RequestSetOpacity((
+                id,
+                layershellev::alpha_multiplier(opacity),
+            ))),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Animation {
+    id: LayerId,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+    from: Property,
+    to: Property,
+    last: Property,
+}
+
+/// The running animations of the layershells. They are stepped when the compositor is ready for
+/// a new frame of the layershell, see [ReturnData::RequestFrame].
+#[derive(Debug, Default)]
+pub(crate) struct Animations {
+    running: Vec<Animation>,
+    /// the layershells waiting for a frame callback
+    pending_frames: HashSet<LayerId>,
+    /// the last opacity set on the layershells, they start fully opaque
+    opacities: HashMap<LayerId, f64>,
+}
+
+impl Animations {
+    pub fn animate_margin<T>(
+        &mut self,
+        ev: &mut WindowState<T>,
+        id: LayerId,
+        margin: (i32, i32, i32, i32),
+        duration: Duration,
+        easing: Easing,
+    ) {
+        let Some(unit) = ev.get_unit_with_id(id) else {
+            return;
+        };
+        let from = Property::Margin(unit.get_margin());
+        self.start(ev, id, from, Property::Margin(margin), duration, easing);
+    }
+
+    pub fn animate_size<T>(
+        &mut self,
+        ev: &mut WindowState<T>,
+        id: LayerId,
+        size: (u32, u32),
+        duration: Duration,
+        easing: Easing,
+    ) {
+        let Some(unit) = ev.get_unit_with_id(id) else {
+            return;
+        };
+        let from = Property::Size(unit.get_size());
+        self.start(ev, id, from, Property::Size(size), duration, easing);
+    }
+
+    pub fn animate_opacity<T: 'static>(
+        &mut self,
+        ev: &mut WindowState<T>,
+        id: LayerId,
+        opacity: f64,
+        duration: Duration,
+        easing: Easing,
+    ) {
+        if !ev.is_alpha_modifier_supported() {
+            tracing::warn!("the opacity cannot be animated without wp_alpha_modifier_v1");
+            return;
+        }
+        let from = Property::Opacity(self.opacities.get(&id).copied().unwrap_or(1.));
+        let to = Property::Opacity(opacity.clamp(0., 1.));
+        self.start(ev, id, from, to, duration, easing);
+    }
+
+    /// a plain margin change stops the running margin animation, which would override it
+    pub fn stop_margin(&mut self, id: LayerId) {
+        self.stop(id, &Property::Margin(Default::default()));
+    }
+
+    /// a plain size change stops the running size animation, which would override it
+    pub fn stop_size(&mut self, id: LayerId) {
+        self.stop(id, &Property::Size(Default::default()));
+    }
+
+    /// a plain opacity change stops the running opacity animation, and the next one starts from
+    /// it
+    pub fn opacity_changed(&mut self, id: LayerId, opacity: f64) {
+        self.stop(id, &Property::Opacity(opacity));
+        self.opacities.insert(id, opacity.clamp(0., 1.));
+    }
+
+    fn stop(&mut self, id: LayerId, kind: &Property) {
+        self.running
+            .retain(|animation| animation.id != id || !animation.to.is_same_kind(kind));
+    }
+
+    /// a new animation replaces the running one of the same kind, and starts from where it is
+    fn start<T>(
+        &mut self,
+        ev: &mut WindowState<T>,
+        id: LayerId,
+        from: Property,
+        to: Property,
+        duration: Duration,
+        easing: Easing,
+    ) {
+        self.running
+            .retain(|animation| ev.get_unit_with_id(animation.id).is_some());
+        self.stop(id, &to);
+        self.running.push(Animation {
+            id,
+            start: Instant::now(),
+            duration,
+            easing,
+            from,
+            to,
+            last: from,
+        });
+        self.request_frame(ev, id);
+    }
+
+    /// request a frame callback of the layershell, once until it is done. The auto hide slides
+    /// are stepped on these frames too.
+    pub fn request_frame<T>(&mut self, ev: &mut WindowState<T>, id: LayerId) {
+        if self.pending_frames.insert(id) {
+            ev.append_return_data(ReturnData::RequestFrame(id));
+        }
+    }
+
+    /// step the animations of the layershell when the compositor is ready for its new frame,
+    /// and request the next frame while they are running. It returns false if the frame was not
+    /// requested with [Animations::request_frame], or the layershell is gone.
+    pub fn frame_done<T>(&mut self, ev: &mut WindowState<T>, id: LayerId) -> bool {
+        if !self.pending_frames.remove(&id) {
+            return false;
+        }
+        if ev.get_unit_with_id(id).is_none() {
+            self.running.retain(|animation| animation.id != id);
+            self.opacities.remove(&id);
+            return false;
+        }
+        let now = Instant::now();
+        let mut running = false;
+        for animation in self
+            .running
+            .iter_mut()
+            .filter(|animation| animation.id == id)
+        {
+            let progress = progress(animation.start, animation.duration, now);
+            let current = if progress < 1. {
+                animation
+                    .from
+                    .interpolate(&animation.to, animation.easing.apply(progress))
+            } else {
+                animation.to
+            };
+            if current != animation.last {
+                animation.last = current;
+                current.apply(ev, id);
+                if let Property::Opacity(opacity) = current {
+                    self.opacities.insert(id, opacity);
+                }
+            }
+            running |= progress < 1.;
+        }
+        self.running.retain(|animation| {
+            animation.id != id || progress(animation.start, animation.duration, now) < 1.
+        });
+        if running {
+            self.request_frame(ev, id);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: [Easing; 4] = [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ];
+
+    #[test]
+    fn test_easing_endpoints() {
+        for easing in EASINGS {
+            assert_eq!(easing.apply(0.), 0., "{easing:?}");
+            assert_eq!(easing.apply(1.), 1., "{easing:?}");
+            // NOTE: the progress is clamped
+            assert_eq!(easing.apply(-1.), 0., "{easing:?}");
+            assert_eq!(easing.apply(2.), 1., "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn test_easing_monotonic() {
+        for easing in EASINGS {
+            let mut last = easing.apply(0.);
+            for step in 1..=100 {
+                let value = easing.apply(step as f32 / 100.);
+                assert!(value >= last, "{easing:?} decreases at {step}%");
+                last = value;
+            }
+        }
+    }
+}
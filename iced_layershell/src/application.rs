@@ -4,9 +4,12 @@ use std::{borrow::Cow, mem::ManuallyDrop, os::fd::AsFd, sync::Arc, time::Duratio
 
 use crate::{
     actions::{LayerShellAction, LayerShellActionVec, LayershellCustomActions},
-    clipboard::LayerShellClipboard,
-    conversion,
+    animation::Animations,
+    auto_hide::AutoHide,
+    auto_size::AutoSizeState,
     error::Error,
+    loop_sources::LoopSources,
+    region::{self, Region},
     settings::VirtualKeyboardSettings,
 };
 
@@ -24,12 +27,13 @@ use layershellev::{
     calloop::timer::{TimeoutAction, Timer},
     reexport::wayland_client::{WlCompositor, WlRegion},
     reexport::zwp_virtual_keyboard_v1,
-    LayerEvent, ReturnData, StartMode, WindowWrapper,
+    CustomCursor, LayerEvent, ReturnData, SeatHandler, SeatId, StartMode, WindowWrapper,
 };
 
 use futures::{channel::mpsc, StreamExt};
 
-use crate::{actions::ActionCallback, event::IcedLayerEvent, proxy::IcedProxy, settings::Settings};
+use crate::{actions::ActionCallback, event::IcedLayerEvent, settings::Settings};
+use iced_shellcore::{clipboard::ShellClipboard, conversion, proxy::IcedProxy};
 
 /// An interactive, native cross-platform application.
 ///
@@ -102,6 +106,27 @@ where
         1.0
     }
 
+    /// Inserts calloop sources, like timers or fd watches, into the event loop of the
+    /// [`Application`], see [`LoopSources`]. It is called once, after [`new`](#tymethod.new).
+    ///
+    /// By default, it inserts nothing.
+    fn loop_sources(&self, _sources: &LoopSources<Self::Message>) {}
+
+    /// Called once at startup with the protocols supported by the compositor, before the first
+    /// view, so the [`Application`] can hide the features the compositor cannot do.
+    ///
+    /// By default, it does nothing.
+    fn capabilities_reported(&mut self, _capabilities: layershellev::Capabilities) {}
+
+    /// Called with every pointer, keyboard and touch event forwarded to the [`Application`],
+    /// together with the seat which produced it, so the input of several seats can be told
+    /// apart. The returned message is handled by [`update`](#tymethod.update).
+    ///
+    /// By default, it returns `None`.
+    fn seat_input(&self, _seat: SeatId, _event: &iced::Event) -> Option<Self::Message> {
+        None
+    }
+
     /// Defines whether or not to use natural scrolling
     fn natural_scroll(&self) -> bool {
         false
@@ -137,24 +162,29 @@ where
 
     let (message_sender, message_receiver) = std::sync::mpsc::channel::<Action<A::Message>>();
 
-    let proxy = IcedProxy::new(message_sender);
+    let proxy = IcedProxy::new(message_sender.clone());
     let mut runtime: SingleRuntime<E, A::Message> = {
         let executor = E::new().map_err(Error::ExecutorCreationFailed)?;
 
         Runtime::new(executor, proxy)
     };
 
-    let (application, task) = {
+    let (mut application, task) = {
         let flags = settings.flags;
 
         runtime.enter(|| A::new(flags))
     };
 
+    let loop_sources = LoopSources::new();
+    application.loop_sources(&loop_sources);
+    let mut loop_sources = Some(loop_sources);
+
     assert!(!matches!(
         settings.layer_settings.start_mode,
         StartMode::AllScreens | StartMode::Background
     ));
 
+    let declared_input_region = settings.layer_settings.declared_input_region();
     let ev = layershellev::WindowStateSimple::new(&application.namespace())
         .with_use_display_handle(true)
         .with_option_size(settings.layer_settings.size)
@@ -162,12 +192,17 @@ where
         .with_events_transparent(settings.layer_settings.events_transparent)
         .with_anchor(settings.layer_settings.anchor)
         .with_exclusize_zone(settings.layer_settings.exclusive_zone)
+        .with_auto_exclusive_zone(settings.layer_settings.auto_exclusive_zone)
         .with_margin(settings.layer_settings.margin)
         .with_keyboard_interacivity(settings.layer_settings.keyboard_interactivity)
         .with_start_mode(settings.layer_settings.start_mode)
+        .with_cursor_theme(settings.cursor_theme.clone())
+        .with_cursor_size(settings.cursor_size)
         .build()
         .expect("Cannot create layershell");
 
+    application.capabilities_reported(ev.capabilities());
+
     let window = Arc::new(ev.gen_main_wrapper());
 
     if let Some(stream) = iced_runtime::task::into_stream(task) {
@@ -179,6 +214,11 @@ where
     ));
 
     let state = State::new(&application, &ev);
+    let mut scale_factor = application.scale_factor();
+    let auto_size = AutoSizeState::new(
+        settings.layer_settings.auto_size,
+        settings.layer_settings.anchor,
+    );
 
     let (mut event_sender, event_receiver) =
         mpsc::unbounded::<IcedLayerEvent<Action<A::Message>>>();
@@ -192,15 +232,35 @@ where
         event_receiver,
         control_sender,
         state,
+        auto_size,
         window,
         settings.fonts,
     ));
 
     let mut context = task::Context::from_waker(task::noop_waker_ref());
     let mut wl_input_region: Option<WlRegion> = None;
+    let mut wl_opaque_region: Option<WlRegion> = None;
     let mut pointer_serial: u32 = 0;
+    // NOTE: the serial is only valid for the pointer of the seat which sent it
+    let mut pointer_seat: Option<SeatId> = None;
+    let mut custom_cursor: Option<CustomCursor> = None;
+    let mut last_interaction = iced_core::mouse::Interaction::default();
+    let mut animations = Animations::default();
+    let mut pending_input_region = settings.layer_settings.input_region.clone();
+    // NOTE: the input region which is set, applied again when the scale factor changes
+    let mut input_region = pending_input_region.clone();
+    // NOTE: the input region is empty then, input_region is restored after
+    let mut mouse_passthrough = false;
+    let mut auto_hide = settings.layer_settings.auto_hide.and_then(|auto_hide| {
+        AutoHide::new(
+            auto_hide,
+            settings.layer_settings.anchor,
+            settings.layer_settings.exclusive_zone,
+            declared_input_region,
+        )
+    });
 
-    let _ = ev.running_with_proxy(message_receiver, move |event, ev, _| {
+    let _ = ev.running_with_proxy(message_receiver, move |event, ev, index| {
         use layershellev::DispatchMessage;
         let mut def_returndata = ReturnData::None;
         match event {
@@ -212,30 +272,85 @@ where
                     .bind::<WlCompositor, _, _>(qh, 1..=1, ())
                     .expect("could not bind wl_compositor");
                 wl_input_region = Some(wl_compositor.create_region(qh, ()));
+                wl_opaque_region = Some(wl_compositor.create_region(qh, ()));
 
-                if settings.virtual_keyboard_support.is_some() {
-                    let virtual_keyboard_manager = globals
-                        .bind::<zwp_virtual_keyboard_v1::ZwpVirtualKeyboardManagerV1, _, _>(
+                if let Some(VirtualKeyboardSettings {
+                    file,
+                    keymap_size,
+                    keymap_format,
+                }) = settings.virtual_keyboard_support.as_ref()
+                {
+                    match (
+                        globals.bind::<zwp_virtual_keyboard_v1::ZwpVirtualKeyboardManagerV1, _, _>(
                             qh,
                             1..=1,
                             (),
-                        )
-                        .expect("no support virtual_keyboard");
-                    let VirtualKeyboardSettings {
-                        file,
-                        keymap_size,
-                        keymap_format,
-                    } = settings.virtual_keyboard_support.as_ref().unwrap();
-                    let seat = ev.get_seat();
-                    let virtual_keyboard_in =
-                        virtual_keyboard_manager.create_virtual_keyboard(seat, qh, ());
-                    virtual_keyboard_in.keymap((*keymap_format).into(), file.as_fd(), *keymap_size);
-                    ev.set_virtual_keyboard(virtual_keyboard_in);
+                        ),
+                        ev.get_seat(),
+                    ) {
+                        (Ok(virtual_keyboard_manager), Some(seat)) => {
+                            let virtual_keyboard_in =
+                                virtual_keyboard_manager.create_virtual_keyboard(seat, qh, ());
+                            virtual_keyboard_in.keymap(
+                                (*keymap_format).into(),
+                                file.as_fd(),
+                                *keymap_size,
+                            );
+                            ev.set_virtual_keyboard(virtual_keyboard_in);
+                        }
+                        (Err(_), _) => tracing::error!(
+                            "the compositor does not support zwp_virtual_keyboard_v1, \
+                            the virtual keyboard is disabled"
+                        ),
+                        (Ok(_), None) => tracing::error!(
+                            "the compositor has no wl_seat, the virtual keyboard is disabled"
+                        ),
+                    }
                 }
             }
             LayerEvent::RequestMessages(message) => {
-                if let DispatchMessage::MouseEnter { serial, .. } = message {
+                if let DispatchMessage::MouseEnter {
+                    pointer,
+                    serial,
+                    seat,
+                    ..
+                } = message
+                {
                     pointer_serial = *serial;
+                    pointer_seat = *seat;
+                    if let Some(cursor) = custom_cursor.as_ref() {
+                        ev.append_return_data(ReturnData::RequestSetCustomCursor((
+                            cursor.clone(),
+                            pointer.clone(),
+                            pointer_serial,
+                        )));
+                    }
+                }
+                if let (DispatchMessage::FrameDone { .. }, Some(id)) = (message, index) {
+                    if animations.frame_done(ev, id) {
+                        if let (Some(auto_hide), Some(unit)) =
+                            (auto_hide.as_mut(), ev.get_unit_with_id(id))
+                        {
+                            auto_hide.frame(unit, wl_input_region.as_ref(), scale_factor);
+                            if auto_hide.is_sliding() {
+                                animations.request_frame(ev, id);
+                            }
+                        }
+                    }
+                }
+                if let DispatchMessage::RequestRefresh { .. } = message {
+                    if let (Some(region), Some(wl_region)) =
+                        (pending_input_region.take(), wl_input_region.as_ref())
+                    {
+                        region.set_input_region(ev.main_window(), wl_region, scale_factor);
+                    }
+                }
+                if let Some(auto_hide) = auto_hide.as_mut() {
+                    match message {
+                        DispatchMessage::MouseEnter { .. } => auto_hide.pointer_entered(),
+                        DispatchMessage::MouseLeave { .. } => auto_hide.pointer_left(),
+                        _ => {}
+                    }
                 }
 
                 event_sender
@@ -243,6 +358,19 @@ where
                     .expect("Cannot send");
             }
             LayerEvent::NormalDispatch => {
+                if let Some(loop_handle) = ev.get_loop_handler() {
+                    if let Some(loop_sources) = loop_sources.take() {
+                        loop_sources.insert_into_single(loop_handle, &message_sender);
+                    }
+                }
+                if let (Some(auto_hide), Some(unit)) = (auto_hide.as_mut(), ev.windows().first()) {
+                    auto_hide.tick(unit);
+                    // NOTE: the slide is stepped on the frame callbacks, like the animations
+                    if auto_hide.is_sliding() {
+                        let id = unit.id();
+                        animations.request_frame(ev, id);
+                    }
+                }
                 event_sender
                     .start_send(IcedLayerEvent::NormalUpdate)
                     .expect("Cannot send");
@@ -270,6 +398,7 @@ where
                         ev.main_window().set_anchor(anchor);
                     }
                     LayershellCustomActions::AnchorSizeChange(anchor, size) => {
+                        animations.stop_size(ev.main_window().id());
                         ev.main_window().set_anchor_with_size(anchor, size);
                     }
                     LayershellCustomActions::LayerChange(layer) => {
@@ -287,25 +416,110 @@ where
                         set_region(region);
 
                         window.get_wlsurface().set_input_region(Some(region));
+                        input_region = None;
+                    }
+                    LayershellCustomActions::InputRegionChange(region) => {
+                        input_region = Some(region.clone());
+                        if mouse_passthrough {
+                            continue;
+                        }
+                        if let Some(auto_hide) = auto_hide.as_mut() {
+                            if !auto_hide.input_region_changed(Some(region.clone())) {
+                                continue;
+                            }
+                        }
+                        let wl_region = wl_input_region.as_ref().expect("region not found");
+                        region.set_input_region(ev.main_window(), wl_region, scale_factor);
                     }
                     LayershellCustomActions::MarginChange(margin) => {
+                        animations.stop_margin(ev.main_window().id());
                         ev.main_window().set_margin(margin);
                     }
                     LayershellCustomActions::SizeChange((width, height)) => {
+                        animations.stop_size(ev.main_window().id());
                         ev.main_window().set_size((width, height));
                     }
+                    LayershellCustomActions::ExclusiveZoneChange(zone_size) => {
+                        ev.main_window().set_exclusive_zone(zone_size);
+                    }
+                    LayershellCustomActions::KeyboardInteractivityChange(
+                        keyboard_interactivity,
+                    ) => {
+                        ev.main_window()
+                            .set_keyboard_interactivity(keyboard_interactivity);
+                    }
+                    LayershellCustomActions::ExclusiveEdgeChange(edge) => {
+                        ev.main_window().set_exclusive_edge(edge);
+                    }
+                    LayershellCustomActions::AnimatedMarginChange {
+                        margin,
+                        duration,
+                        easing,
+                    } => {
+                        let id = ev.main_window().id();
+                        animations.animate_margin(ev, id, margin, duration, easing);
+                    }
+                    LayershellCustomActions::AnimatedSizeChange {
+                        size,
+                        duration,
+                        easing,
+                    } => {
+                        let id = ev.main_window().id();
+                        animations.animate_size(ev, id, size, duration, easing);
+                    }
+                    LayershellCustomActions::AnimatedOpacityChange {
+                        opacity,
+                        duration,
+                        easing,
+                    } => {
+                        let id = ev.main_window().id();
+                        animations.animate_opacity(ev, id, opacity, duration, easing);
+                    }
+                    LayershellCustomActions::OpacityChange(opacity) => {
+                        let id = ev.main_window().id();
+                        animations.opacity_changed(id, opacity);
+                        ev.append_return_data(ReturnData::RequestSetOpacity((
+                            id,
+                            layershellev::alpha_multiplier(opacity),
+                        )));
+                    }
+                    LayershellCustomActions::SetCustomCursor(cursor) => {
+                        custom_cursor = cursor;
+                        let Some(pointer) = pointer_seat
+                            .and_then(|seat| ev.get_pointer_with_seat(seat))
+                            .cloned()
+                        else {
+                            continue;
+                        };
+                        let data = match custom_cursor.as_ref() {
+                            Some(cursor) => ReturnData::RequestSetCustomCursor((
+                                cursor.clone(),
+                                pointer,
+                                pointer_serial,
+                            )),
+                            None => ReturnData::RequestSetCursorShape((
+                                conversion::mouse_interaction(last_interaction),
+                                pointer,
+                                pointer_serial,
+                            )),
+                        };
+                        ev.append_return_data(data);
+                    }
                     LayershellCustomActions::VirtualKeyboardPressed { time, key } => {
                         use layershellev::reexport::wayland_client::KeyState;
-                        let ky = ev.get_virtual_keyboard().unwrap();
+                        let Some(ky) = ev.get_virtual_keyboard() else {
+                            tracing::error!("no virtual keyboard, the key {key} is not pressed");
+                            continue;
+                        };
                         ky.key(time, key, KeyState::Pressed.into());
 
                         let eh = ev.get_loop_handler().unwrap();
                         eh.insert_source(
                             Timer::from_duration(Duration::from_micros(100)),
                             move |_, _, state| {
-                                let ky = state.get_virtual_keyboard().unwrap();
-
-                                ky.key(time, key, KeyState::Released.into());
+                                if let Some(ky) = state.get_virtual_keyboard() {
+                                    ky.key(time, key, KeyState::Released.into());
+                                }
                                 TimeoutAction::Drop
                             },
                         )
@@ -314,7 +528,14 @@ where
                     _ => {}
                 },
                 LayerShellAction::Mouse(mouse) => {
-                    let Some(pointer) = ev.get_pointer() else {
+                    last_interaction = mouse;
+                    // NOTE: the custom cursor stays until it is unset
+                    if custom_cursor.is_some() {
+                        continue;
+                    }
+                    let Some(pointer) =
+                        pointer_seat.and_then(|seat| ev.get_pointer_with_seat(seat))
+                    else {
                         return ReturnData::None;
                     };
 
@@ -330,6 +551,59 @@ where
                 LayerShellAction::RedrawWindow(index) => {
                     ev.append_return_data(ReturnData::RedrawIndexRequest(index));
                 }
+                LayerShellAction::OpaqueRegion(_, opaque) => {
+                    let window = ev.main_window();
+                    if !opaque {
+                        window.set_opaque_region(None);
+                        continue;
+                    }
+                    let Some(wl_region) = &wl_opaque_region else {
+                        continue;
+                    };
+                    Region::full().set_opaque_region(window, wl_region, scale_factor);
+                }
+                LayerShellAction::MousePassthrough(_, passthrough) => {
+                    if mouse_passthrough == passthrough {
+                        continue;
+                    }
+                    mouse_passthrough = passthrough;
+                    pending_input_region = None;
+                    // NOTE: an empty input region lets every pointer event pass through
+                    let region = if passthrough {
+                        Some(Region::new())
+                    } else {
+                        input_region.clone()
+                    };
+                    if let Some(auto_hide) = auto_hide.as_mut() {
+                        if !auto_hide.input_region_changed(region.clone()) {
+                            continue;
+                        }
+                    }
+                    region::set_input_region(
+                        region.as_ref(),
+                        ev.main_window(),
+                        wl_input_region.as_ref(),
+                        scale_factor,
+                    );
+                }
+                LayerShellAction::ScaleFactor(_, new_scale_factor) => {
+                    scale_factor = new_scale_factor;
+                    // NOTE: the pending input region is set on the first refresh
+                    if pending_input_region.is_some() || mouse_passthrough {
+                        continue;
+                    }
+                    let (Some(region), Some(wl_region)) =
+                        (input_region.as_ref(), wl_input_region.as_ref())
+                    else {
+                        continue;
+                    };
+                    if let Some(auto_hide) = auto_hide.as_mut() {
+                        if !auto_hide.input_region_changed(Some(region.clone())) {
+                            continue;
+                        }
+                    }
+                    region.set_input_region(ev.main_window(), wl_region, scale_factor);
+                }
                 _ => {}
             }
         }
@@ -347,6 +621,7 @@ async fn run_instance<A, E, C>(
     mut event_receiver: mpsc::UnboundedReceiver<IcedLayerEvent<Action<A::Message>>>,
     mut control_sender: mpsc::UnboundedSender<LayerShellActionVec>,
     mut state: State<A>,
+    mut auto_size: AutoSizeState,
     window: Arc<WindowWrapper>,
     fonts: Vec<Cow<'static, [u8]>>,
 ) where
@@ -377,9 +652,11 @@ async fn run_instance<A, E, C>(
 
     let mut should_exit = false;
 
-    let mut clipboard = LayerShellClipboard::connect(&window);
+    let mut clipboard = ShellClipboard::connect(&window);
 
     let mut mouse_interaction = mouse::Interaction::default();
+    let mut opaque = false;
+    let mut scale_factor = application.scale_factor();
     let mut messages = Vec::new();
     let mut events: Vec<Event> = Vec::new();
     let mut custom_actions = Vec::new();
@@ -405,6 +682,7 @@ async fn run_instance<A, E, C>(
             } => {
                 state.update_view_port(width, height, fractal_scale);
                 let logical_size = state.logical_size();
+                auto_size.mark_dirty();
 
                 debug.layout_started();
                 user_interface = ManuallyDrop::new(
@@ -412,6 +690,13 @@ async fn run_instance<A, E, C>(
                 );
                 debug.layout_finished();
 
+                // NOTE: the compositor does not need to draw what is below a fully opaque background
+                let is_opaque = region::is_opaque(state.background_color());
+                if is_opaque != opaque {
+                    opaque = is_opaque;
+                    custom_actions.push(LayerShellAction::OpaqueRegion(None, opaque));
+                }
+
                 let physical_size = state.physical_size();
                 compositor.configure_surface(
                     &mut surface,
@@ -422,7 +707,7 @@ async fn run_instance<A, E, C>(
                     IcedCoreEvent::Window(IcedCoreWindow::Event::RedrawRequested(Instant::now()));
 
                 user_interface.update(
-                    &[redraw_event.clone()],
+                    std::slice::from_ref(&redraw_event),
                     state.cursor(),
                     &mut renderer,
                     &mut clipboard,
@@ -497,7 +782,7 @@ async fn run_instance<A, E, C>(
                     },
                 }
             }
-            IcedLayerEvent::Window(event) => {
+            IcedLayerEvent::Window(event, seat) => {
                 state.update(&event);
 
                 if let Some(event) = conversion::window_event(
@@ -505,6 +790,11 @@ async fn run_instance<A, E, C>(
                     state.application_scale_factor(),
                     state.modifiers(),
                 ) {
+                    if let Some(message) =
+                        seat.and_then(|seat| application.seat_input(seat, &event))
+                    {
+                        messages.push(message);
+                    }
                     events.push(event);
                 }
             }
@@ -516,6 +806,7 @@ async fn run_instance<A, E, C>(
                     &mut surface,
                     &mut cache,
                     &state,
+                    main_id,
                     &mut renderer,
                     event,
                     &mut messages,
@@ -549,7 +840,7 @@ async fn run_instance<A, E, C>(
                 );
                 debug.event_processing_finished();
 
-                for (event, status) in events.drain(..).zip(statuses.into_iter()) {
+                for (event, status) in events.drain(..).zip(statuses) {
                     runtime.broadcast(iced_futures::subscription::Event::Interaction {
                         window: main_id,
                         event,
@@ -576,11 +867,33 @@ async fn run_instance<A, E, C>(
                         state.logical_size(),
                         &mut debug,
                     ));
+                    auto_size.mark_dirty();
                 }
                 custom_actions.push(LayerShellAction::RedrawAll);
             }
             _ => unreachable!(),
         }
+        if application.scale_factor() != scale_factor {
+            scale_factor = application.scale_factor();
+            custom_actions.push(LayerShellAction::ScaleFactor(None, scale_factor));
+        }
+        if auto_size.is_enabled() {
+            for action in custom_actions.iter().filter_map(|action| match action {
+                LayerShellAction::CustomActions(action) => Some(action),
+                _ => None,
+            }) {
+                auto_size.update_anchor(action);
+            }
+            if auto_size.is_dirty() {
+                let view = application.view();
+                custom_actions.extend(
+                    auto_size
+                        .measure(&view, &renderer, state.logical_size())
+                        .into_iter()
+                        .map(LayerShellAction::CustomActions),
+                );
+            }
+        }
         let mut copyactions = vec![];
         std::mem::swap(&mut copyactions, &mut custom_actions);
         control_sender.start_send(copyactions).ok();
@@ -643,6 +956,10 @@ pub(crate) fn update<A: Application, E: Executor>(
     ));
 }
 
+fn unsupported_window_action(name: &'static str) {
+    tracing::error!("{}", Error::UnsupportedWindowAction(name));
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn run_action<A, C>(
     application: &A,
@@ -650,10 +967,11 @@ pub(crate) fn run_action<A, C>(
     surface: &mut C::Surface,
     cache: &mut user_interface::Cache,
     state: &State<A>,
+    window_id: IcedCoreWindow::Id,
     renderer: &mut A::Renderer,
     event: Action<A::Message>,
     messages: &mut Vec<A::Message>,
-    clipboard: &mut LayerShellClipboard,
+    clipboard: &mut ShellClipboard,
     custom_actions: &mut Vec<LayerShellAction>,
     should_exit: &mut bool,
     debug: &mut Debug,
@@ -731,7 +1049,60 @@ pub(crate) fn run_action<A, C>(
                     state.viewport().scale_factor(),
                 ));
             }
-            _ => {}
+            WinowAction::Resize(_id, size) => {
+                custom_actions.push(LayerShellAction::CustomActions(
+                    LayershellCustomActions::SizeChange((
+                        size.width.ceil() as u32,
+                        size.height.ceil() as u32,
+                    )),
+                ));
+            }
+            WinowAction::EnableMousePassthrough(_id) => {
+                custom_actions.push(LayerShellAction::MousePassthrough(None, true));
+            }
+            WinowAction::DisableMousePassthrough(_id) => {
+                custom_actions.push(LayerShellAction::MousePassthrough(None, false));
+            }
+            WinowAction::GetSize(_id, channel) => {
+                let _ = channel.send(state.logical_size());
+            }
+            WinowAction::GetScaleFactor(_id, channel) => {
+                let _ = channel.send(state.viewport().scale_factor() as f32);
+            }
+            WinowAction::GetOldest(channel) | WinowAction::GetLatest(channel) => {
+                let _ = channel.send(Some(window_id));
+            }
+            // NOTE: a layershell is always shown as it is, it cannot be maximized, minimized or
+            // moved, and it does not know its position
+            WinowAction::GetMode(_id, channel) => {
+                let _ = channel.send(IcedCoreWindow::Mode::Windowed);
+            }
+            WinowAction::GetMaximized(_id, channel) => {
+                let _ = channel.send(false);
+            }
+            WinowAction::GetMinimized(_id, channel) => {
+                let _ = channel.send(None);
+            }
+            WinowAction::GetPosition(_id, channel) => {
+                let _ = channel.send(None);
+            }
+            WinowAction::Open(..) => unsupported_window_action("open"),
+            WinowAction::Drag(_) => unsupported_window_action("drag"),
+            WinowAction::Maximize(..) => unsupported_window_action("maximize"),
+            WinowAction::Minimize(..) => unsupported_window_action("minimize"),
+            WinowAction::Move(..) => unsupported_window_action("move"),
+            WinowAction::ChangeMode(..) => unsupported_window_action("change_mode"),
+            WinowAction::ToggleMaximize(_) => unsupported_window_action("toggle_maximize"),
+            WinowAction::ToggleDecorations(_) => unsupported_window_action("toggle_decorations"),
+            WinowAction::RequestUserAttention(..) => {
+                unsupported_window_action("request_user_attention")
+            }
+            WinowAction::GainFocus(_) => unsupported_window_action("gain_focus"),
+            WinowAction::ChangeLevel(..) => unsupported_window_action("change_level"),
+            WinowAction::ShowSystemMenu(_) => unsupported_window_action("show_system_menu"),
+            WinowAction::GetRawId(..) => unsupported_window_action("get_raw_id"),
+            WinowAction::ChangeIcon(..) => unsupported_window_action("change_icon"),
+            WinowAction::RunWithHandle(..) => unsupported_window_action("run_with_handle"),
         },
         Action::Exit => {
             *should_exit = true;
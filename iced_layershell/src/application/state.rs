@@ -20,7 +20,8 @@ where
     appearance: Appearance,
     mouse_position: Option<Point>,
     modifiers: ModifiersState,
-    wpviewport: WpViewport,
+    /// without wp_viewporter the surface is not scaled, so the scale stays 1
+    wpviewport: Option<WpViewport>,
 }
 
 impl<A: Application> State<A>
@@ -47,10 +48,7 @@ where
             appearance,
             mouse_position: None,
             modifiers: ModifiersState::default(),
-            wpviewport: window
-                .gen_main_wrapper()
-                .viewport
-                .expect("iced_layershell need viewport support to better wayland dpi"),
+            wpviewport: window.gen_main_wrapper().viewport,
         }
     }
 
@@ -63,6 +61,7 @@ where
     }
 
     pub fn update_view_port(&mut self, width: u32, height: u32, scale: f64) {
+        let scale = self.supported_scale(scale);
         let real_window_size = Size::new(width, height);
         if self.real_window_size == real_window_size && self.wayland_scale_factor == scale {
             return;
@@ -73,13 +72,27 @@ where
             self.adjusted_physical_size(),
             self.current_wayland_scale() * self.application_scale_factor,
         );
-        let logical_size = self.viewport.logical_size();
+        self.set_destination();
+        self.viewport_version = self.viewport_version.wrapping_add(1);
+    }
+
+    fn supported_scale(&self, scale: f64) -> f64 {
+        if self.wpviewport.is_some() {
+            scale
+        } else {
+            1.
+        }
+    }
 
-        self.wpviewport.set_destination(
+    fn set_destination(&self) {
+        let Some(wpviewport) = self.wpviewport.as_ref() else {
+            return;
+        };
+        let logical_size = self.viewport.logical_size();
+        wpviewport.set_destination(
             logical_size.width.ceil() as i32,
             logical_size.height.ceil() as i32,
         );
-        self.viewport_version = self.viewport_version.wrapping_add(1);
     }
 
     fn adjusted_physical_size(&self) -> Size<u32> {
@@ -140,19 +153,14 @@ where
                 scale_float,
                 scale_u32: _,
             } => {
-                self.wayland_scale_factor = *scale_float;
+                self.wayland_scale_factor = self.supported_scale(*scale_float);
                 self.viewport = Viewport::with_physical_size(
                     self.adjusted_physical_size(),
-                    self.application_scale_factor * scale_float,
+                    self.application_scale_factor * self.wayland_scale_factor,
                 );
 
                 self.viewport_version = self.viewport_version.wrapping_add(1);
-                let logical_size = self.viewport.logical_size();
-
-                self.wpviewport.set_destination(
-                    logical_size.width.ceil() as i32,
-                    logical_size.height.ceil() as i32,
-                );
+                self.set_destination();
             }
             _ => {}
         }
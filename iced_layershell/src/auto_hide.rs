@@ -0,0 +1,343 @@
+use std::time::Instant;
+
+use layershellev::reexport::wayland_client::WlRegion;
+use layershellev::reexport::Anchor;
+use layershellev::WindowStateUnit;
+
+use crate::animation::{self, Easing};
+use crate::region::{self, Region};
+use crate::settings::AutoHideSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Shown,
+    /// the pointer left, hide the layershell at the deadline
+    WaitingToHide(Instant),
+    /// slide the length on the anchored axis
+    Sliding {
+        start: Instant,
+        from: u32,
+        to: u32,
+    },
+    Hidden,
+}
+
+/// Hide a layershell to a strip on its anchored edge, and reveal it when the pointer enters it.
+#[derive(Debug, Clone)]
+pub(crate) struct AutoHide {
+    settings: AutoHideSettings,
+    anchor: Anchor,
+    /// if the layershell is anchored to the top or the bottom edge
+    vertical: bool,
+    exclusive_zone: i32,
+    /// the input region of the shown layershell, None is the whole layershell. It is replaced by
+    /// the whole strip while the layershell is hidden.
+    input_region: Option<Region>,
+    shown_size: Option<(u32, u32)>,
+    hovered: bool,
+    phase: Phase,
+    last_length: Option<u32>,
+}
+
+impl AutoHide {
+    pub fn new(
+        settings: AutoHideSettings,
+        anchor: Anchor,
+        exclusive_zone: i32,
+        input_region: Option<Region>,
+    ) -> Option<Self> {
+        let anchored_vertically = anchor.contains(Anchor::Top) != anchor.contains(Anchor::Bottom);
+        let anchored_horizontally = anchor.contains(Anchor::Left) != anchor.contains(Anchor::Right);
+        let vertical = match (anchored_vertically, anchored_horizontally) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => {
+                tracing::warn!("auto hide needs the layershell to be anchored to one edge");
+                return None;
+            }
+        };
+        Some(Self {
+            settings,
+            anchor,
+            vertical,
+            exclusive_zone,
+            input_region,
+            shown_size: None,
+            hovered: false,
+            phase: Phase::WaitingToHide(Instant::now() + settings.hide_delay),
+            last_length: None,
+        })
+    }
+
+    fn length_of(&self, (width, height): (u32, u32)) -> u32 {
+        if self.vertical {
+            height
+        } else {
+            width
+        }
+    }
+
+    fn current_length(&self, now: Instant) -> u32 {
+        match self.phase {
+            Phase::Sliding { start, from, to } => {
+                let progress = self.progress(start, now);
+                (from as f32 + (to as f32 - from as f32) * Easing::EaseOut.apply(progress)).round()
+                    as u32
+            }
+            Phase::Hidden => self.settings.trigger_size,
+            _ => self.shown_length(),
+        }
+    }
+
+    fn shown_length(&self) -> u32 {
+        self.shown_size
+            .map(|size| self.length_of(size))
+            .unwrap_or(self.settings.trigger_size)
+    }
+
+    fn progress(&self, start: Instant, now: Instant) -> f32 {
+        animation::progress(start, self.settings.animation_duration, now)
+    }
+
+    /// keep the new input region to restore it when the layershell is shown. It returns false
+    /// while the layershell is hidden or sliding, the region must not be applied then.
+    pub fn input_region_changed(&mut self, input_region: Option<Region>) -> bool {
+        self.input_region = input_region;
+        matches!(self.phase, Phase::Shown | Phase::WaitingToHide(_))
+    }
+
+    pub fn pointer_entered(&mut self) {
+        self.hovered = true;
+        let now = Instant::now();
+        match self.phase {
+            Phase::WaitingToHide(_) => self.phase = Phase::Shown,
+            Phase::Hidden => {
+                self.phase = Phase::Sliding {
+                    start: now,
+                    from: self.settings.trigger_size,
+                    to: self.shown_length(),
+                }
+            }
+            Phase::Sliding { to, .. } if to == self.settings.trigger_size => {
+                self.phase = Phase::Sliding {
+                    start: now,
+                    from: self.current_length(now),
+                    to: self.shown_length(),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn pointer_left(&mut self) {
+        self.hovered = false;
+        if self.phase == Phase::Shown {
+            self.phase = Phase::WaitingToHide(Instant::now() + self.settings.hide_delay);
+        }
+    }
+
+    /// if the layershell slides, its frames are stepped with [AutoHide::frame]
+    pub fn is_sliding(&self) -> bool {
+        matches!(self.phase, Phase::Sliding { .. })
+    }
+
+    /// start to hide the layershell once the pointer has left it for the hide delay
+    pub fn tick<T>(&mut self, unit: &WindowStateUnit<T>) {
+        let now = Instant::now();
+        let Phase::WaitingToHide(deadline) = self.phase else {
+            return;
+        };
+        if now < deadline {
+            return;
+        }
+        let shown_size = unit.get_size();
+        // NOTE: not configured yet
+        if shown_size == (0, 0) {
+            return;
+        }
+        self.shown_size = Some(shown_size);
+        self.phase = Phase::Sliding {
+            start: now,
+            from: self.length_of(shown_size),
+            to: self.settings.trigger_size,
+        };
+        unit.get_wlsurface().set_input_region(None);
+        if self.exclusive_zone > 0 && !unit.is_auto_exclusive_zone() {
+            unit.set_exclusive_zone(self.settings.trigger_size as i32);
+        }
+    }
+
+    /// apply the length of the slide when the compositor is ready for a new frame of the unit.
+    /// The scale factor of the application is the one of the input region restored once shown.
+    pub fn frame<T>(
+        &mut self,
+        unit: &WindowStateUnit<T>,
+        wl_region: Option<&WlRegion>,
+        scale_factor: f64,
+    ) {
+        let now = Instant::now();
+        let Phase::Sliding { start, to, .. } = self.phase else {
+            return;
+        };
+        if self.progress(start, now) < 1. {
+            self.apply_length(unit, self.current_length(now));
+            return;
+        }
+        self.apply_length(unit, to);
+        if to == self.settings.trigger_size {
+            self.phase = Phase::Hidden;
+            return;
+        }
+        if self.exclusive_zone > 0 && !unit.is_auto_exclusive_zone() {
+            unit.set_exclusive_zone(self.exclusive_zone);
+        }
+        self.restore_input_region(unit, wl_region, scale_factor);
+        self.phase = if self.hovered {
+            Phase::Shown
+        } else {
+            Phase::WaitingToHide(now + self.settings.hide_delay)
+        };
+    }
+
+    fn restore_input_region<T>(
+        &self,
+        unit: &WindowStateUnit<T>,
+        wl_region: Option<&WlRegion>,
+        scale_factor: f64,
+    ) {
+        region::set_input_region(self.input_region.as_ref(), unit, wl_region, scale_factor);
+        unit.get_wlsurface().commit();
+    }
+
+    fn apply_length<T>(&mut self, unit: &WindowStateUnit<T>, length: u32) {
+        if self.last_length == Some(length) {
+            return;
+        }
+        self.last_length = Some(length);
+        let Some((width, height)) = self.shown_size else {
+            return;
+        };
+        // NOTE: 0 keeps the axis stretched between the two anchors
+        let size = if self.vertical {
+            let stretched = self.anchor.contains(Anchor::Left | Anchor::Right);
+            (if stretched { 0 } else { width }, length)
+        } else {
+            let stretched = self.anchor.contains(Anchor::Top | Anchor::Bottom);
+            (length, if stretched { 0 } else { height })
+        };
+        unit.set_size(size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn top_bar() -> AutoHide {
+        let mut auto_hide = AutoHide::new(
+            AutoHideSettings {
+                trigger_size: 2,
+                hide_delay: Duration::from_millis(500),
+                animation_duration: Duration::from_millis(200),
+            },
+            Anchor::Top | Anchor::Left | Anchor::Right,
+            40,
+            None,
+        )
+        .expect("a bar anchored to the top edge can hide");
+        auto_hide.shown_size = Some((1920, 40));
+        auto_hide
+    }
+
+    #[test]
+    fn test_auto_hide_needs_an_edge() {
+        let settings = AutoHideSettings::default();
+        assert!(AutoHide::new(settings, Anchor::Top | Anchor::Left, 0, None).is_none());
+        assert!(AutoHide::new(settings, Anchor::empty(), 0, None).is_none());
+        assert!(AutoHide::new(settings, Anchor::Left, 0, None).is_some());
+    }
+
+    #[test]
+    fn test_auto_hide_pointer_enter_and_leave() {
+        let mut auto_hide = top_bar();
+        assert!(matches!(auto_hide.phase, Phase::WaitingToHide(_)));
+
+        auto_hide.pointer_entered();
+        assert_eq!(auto_hide.phase, Phase::Shown);
+        assert!(!auto_hide.is_sliding());
+
+        auto_hide.pointer_left();
+        assert!(matches!(auto_hide.phase, Phase::WaitingToHide(_)));
+    }
+
+    #[test]
+    fn test_auto_hide_reveal_from_hidden() {
+        let mut auto_hide = top_bar();
+        auto_hide.phase = Phase::Hidden;
+
+        auto_hide.pointer_entered();
+        assert!(matches!(
+            auto_hide.phase,
+            Phase::Sliding {
+                from: 2,
+                to: 40,
+                ..
+            }
+        ));
+        assert!(auto_hide.is_sliding());
+    }
+
+    #[test]
+    fn test_auto_hide_reverse_while_hiding() {
+        let mut auto_hide = top_bar();
+        auto_hide.phase = Phase::Sliding {
+            start: Instant::now() - Duration::from_millis(100),
+            from: 40,
+            to: 2,
+        };
+
+        auto_hide.pointer_entered();
+        let Phase::Sliding { from, to, .. } = auto_hide.phase else {
+            panic!("the layershell should slide back");
+        };
+        assert!(from > 2 && from < 40);
+        assert_eq!(to, 40);
+    }
+
+    #[test]
+    fn test_auto_hide_current_length() {
+        let mut auto_hide = top_bar();
+        let now = Instant::now();
+
+        auto_hide.phase = Phase::Shown;
+        assert_eq!(auto_hide.current_length(now), 40);
+
+        auto_hide.phase = Phase::Hidden;
+        assert_eq!(auto_hide.current_length(now), 2);
+
+        auto_hide.phase = Phase::Sliding {
+            start: now,
+            from: 40,
+            to: 2,
+        };
+        assert_eq!(auto_hide.current_length(now), 40);
+        assert_eq!(
+            auto_hide.current_length(now + Duration::from_millis(200)),
+            2
+        );
+        let middle = auto_hide.current_length(now + Duration::from_millis(100));
+        assert!(middle > 2 && middle < 40);
+    }
+
+    #[test]
+    fn test_auto_hide_keeps_the_input_region() {
+        let mut auto_hide = top_bar();
+        assert!(auto_hide.input_region_changed(Some(Region::new())));
+        assert_eq!(auto_hide.input_region, Some(Region::new()));
+
+        auto_hide.phase = Phase::Hidden;
+        assert!(!auto_hide.input_region_changed(Some(Region::full())));
+        assert_eq!(auto_hide.input_region, Some(Region::full()));
+    }
+}
@@ -0,0 +1,132 @@
+use iced_core::{layout, widget, Element, Size};
+use layershellev::reexport::Anchor;
+
+use crate::actions::LayershellCustomActions;
+use crate::settings::AutoSize;
+
+/// Keep track of the size of the content of a layershell, and tell when it should be resized.
+pub(crate) struct AutoSizeState {
+    mode: AutoSize,
+    anchor: Anchor,
+    last_size: Option<(u32, u32)>,
+    /// the widget tree of the last measure, it is diffed with the new view instead of being
+    /// created again
+    tree: Option<widget::Tree>,
+    /// the content is only measured again after an update, a redraw or an anchor change
+    dirty: bool,
+}
+
+impl Clone for AutoSizeState {
+    fn clone(&self) -> Self {
+        Self::new(self.mode, self.anchor)
+    }
+}
+
+impl AutoSizeState {
+    pub fn new(mode: AutoSize, anchor: Anchor) -> Self {
+        Self {
+            mode,
+            anchor,
+            last_size: None,
+            tree: None,
+            dirty: true,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.mode.is_enabled()
+    }
+
+    /// the view may have changed, it should be measured again
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.is_enabled() && self.dirty
+    }
+
+    /// follow the anchor changes, the stretched axes depend on it
+    pub fn update_anchor(&mut self, action: &LayershellCustomActions) {
+        match action {
+            LayershellCustomActions::AnchorChange(anchor)
+            | LayershellCustomActions::AnchorSizeChange(anchor, _) => {
+                self.anchor = *anchor;
+                self.last_size = None;
+                self.dirty = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn stretched(&self) -> (bool, bool) {
+        (
+            self.anchor.contains(Anchor::Left | Anchor::Right),
+            self.anchor.contains(Anchor::Top | Anchor::Bottom),
+        )
+    }
+
+    /// layout the element with shrinked limits, and return the actions to resize the layershell
+    /// if the size of the content changed
+    pub fn measure<Message, Theme, Renderer>(
+        &mut self,
+        element: &Element<'_, Message, Theme, Renderer>,
+        renderer: &Renderer,
+        surface_size: Size,
+    ) -> Vec<LayershellCustomActions>
+    where
+        Renderer: iced_core::Renderer,
+    {
+        if !self.is_dirty() {
+            return Vec::new();
+        }
+        self.dirty = false;
+        let (stretched_horizontally, stretched_vertically) = self.stretched();
+        let max = Size::new(
+            if stretched_horizontally {
+                surface_size.width
+            } else {
+                f32::INFINITY
+            },
+            if stretched_vertically {
+                surface_size.height
+            } else {
+                f32::INFINITY
+            },
+        );
+        let tree = match self.tree.as_mut() {
+            Some(tree) => {
+                tree.diff(element.as_widget());
+                tree
+            }
+            None => self.tree.insert(widget::Tree::new(element.as_widget())),
+        };
+        let node =
+            element
+                .as_widget()
+                .layout(tree, renderer, &layout::Limits::new(Size::ZERO, max));
+        let content = node.size();
+        if !content.width.is_finite() || !content.height.is_finite() {
+            tracing::warn!("the content cannot be measured, it should not fill the layershell");
+            return Vec::new();
+        }
+        // NOTE: 0 means the compositor decides the size of a stretched axis
+        let size = (
+            if stretched_horizontally {
+                0
+            } else {
+                content.width.ceil().max(1.) as u32
+            },
+            if stretched_vertically {
+                0
+            } else {
+                content.height.ceil().max(1.) as u32
+            },
+        );
+        if self.last_size == Some(size) {
+            return Vec::new();
+        }
+        self.last_size = Some(size);
+        vec![LayershellCustomActions::SizeChange(size)]
+    }
+}
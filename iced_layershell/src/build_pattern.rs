@@ -52,6 +52,16 @@ pub struct MainSettings {
     pub antialiasing: bool,
 
     pub virtual_keyboard_support: Option<VirtualKeyboardSettings>,
+
+    /// The cursor theme, used when the compositor does not support cursor-shape.
+    ///
+    /// By default, `XCURSOR_THEME` is used.
+    pub cursor_theme: Option<String>,
+
+    /// The logical size of the cursor, used when the compositor does not support cursor-shape.
+    ///
+    /// By default, `XCURSOR_SIZE` is used, or `24` if it is not set.
+    pub cursor_size: Option<u32>,
 }
 impl Default for MainSettings {
     fn default() -> Self {
@@ -63,6 +73,8 @@ impl Default for MainSettings {
             default_text_size: Pixels(16.0),
             antialiasing: false,
             virtual_keyboard_support: None,
+            cursor_theme: None,
+            cursor_size: None,
         }
     }
 }
@@ -7,6 +7,8 @@ use crate::actions::LayershellCustomActions;
 
 use crate::settings::LayerShellSettings;
 use crate::DefaultStyle;
+use crate::LoopSources;
+use layershellev::SeatId;
 
 use super::Renderer;
 use crate::Settings;
@@ -108,6 +110,37 @@ pub trait Program: Sized {
         1.0
     }
 
+    /// Inserts calloop sources, like timers or fd watches, into the event loop of the
+    /// [`Application`], see [`LoopSources`]. It is called once, after the state is initialized.
+    ///
+    /// By default, it inserts nothing.
+    fn loop_sources(&self, _state: &Self::State, _sources: &LoopSources<Self::Message>) {}
+
+    /// Called once at startup with the protocols supported by the compositor, before the first
+    /// view, so the state can hide the features the compositor cannot do.
+    ///
+    /// By default, it does nothing.
+    fn capabilities_reported(
+        &self,
+        _state: &mut Self::State,
+        _capabilities: layershellev::Capabilities,
+    ) {
+    }
+
+    /// Called with every pointer, keyboard and touch event, together with the seat which produced
+    /// it, so the input of several seats can be told apart. The returned message is handled by
+    /// [`Program::update`].
+    ///
+    /// By default, it returns `None`.
+    fn seat_input(
+        &self,
+        _state: &Self::State,
+        _seat: SeatId,
+        _event: &iced::Event,
+    ) -> Option<Self::Message> {
+        None
+    }
+
     fn run_with<I>(self, settings: MainSettings, initialize: I) -> Result
     where
         Self: 'static,
@@ -172,6 +205,19 @@ pub trait Program: Sized {
             fn scale_factor(&self) -> f64 {
                 self.program.scale_factor(&self.state)
             }
+
+            fn loop_sources(&self, sources: &LoopSources<Self::Message>) {
+                self.program.loop_sources(&self.state, sources)
+            }
+
+            fn capabilities_reported(&mut self, capabilities: layershellev::Capabilities) {
+                self.program
+                    .capabilities_reported(&mut self.state, capabilities)
+            }
+
+            fn seat_input(&self, seat: SeatId, event: &iced::Event) -> Option<Self::Message> {
+                self.program.seat_input(&self.state, seat, event)
+            }
         }
 
         let real_settings = Settings {
@@ -183,6 +229,8 @@ pub trait Program: Sized {
             default_text_size: settings.default_text_size,
             antialiasing: settings.antialiasing,
             virtual_keyboard_support: settings.virtual_keyboard_support,
+            cursor_theme: settings.cursor_theme,
+            cursor_size: settings.cursor_size,
         };
         #[allow(clippy::needless_update)]
         let renderer_settings = iced_graphics::Settings {
@@ -392,6 +440,27 @@ pub fn with_executor<P: Program, E: iced_futures::Executor>(
         fn scale_factor(&self, state: &Self::State) -> f64 {
             self.program.scale_factor(state)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, seat, event)
+        }
     }
 
     WithExecutor {
@@ -449,6 +518,27 @@ fn with_namespace<P: Program>(
         fn scale_factor(&self, state: &Self::State) -> f64 {
             self.program.scale_factor(state)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, seat, event)
+        }
     }
 
     WithNamespace { program, namespace }
@@ -503,6 +593,27 @@ pub fn with_subscription<P: Program>(
         fn scale_factor(&self, state: &Self::State) -> f64 {
             self.program.scale_factor(state)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, seat, event)
+        }
     }
 
     WithSubscription {
@@ -560,6 +671,27 @@ pub fn with_theme<P: Program>(
         fn scale_factor(&self, state: &Self::State) -> f64 {
             self.program.scale_factor(state)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, seat, event)
+        }
     }
 
     WithTheme { program, theme: f }
@@ -614,6 +746,27 @@ pub fn with_style<P: Program>(
         fn scale_factor(&self, state: &Self::State) -> f64 {
             self.program.scale_factor(state)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, seat, event)
+        }
     }
 
     WithStyle { program, style: f }
@@ -668,6 +821,27 @@ pub fn with_scale_factor<P: Program>(
         fn scale_factor(&self, state: &Self::State) -> f64 {
             (self.scale_factor)(state)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, seat, event)
+        }
     }
 
     WithScaleFactor {
@@ -676,6 +850,240 @@ pub fn with_scale_factor<P: Program>(
     }
 }
 
+pub fn with_loop_sources<P: Program>(
+    program: P,
+    f: impl Fn(&P::State, &LoopSources<P::Message>),
+) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+    struct WithLoopSources<P, F> {
+        program: P,
+        loop_sources: F,
+    }
+
+    impl<P: Program, F> Program for WithLoopSources<P, F>
+    where
+        F: Fn(&P::State, &LoopSources<P::Message>),
+    {
+        type State = P::State;
+        type Message = P::Message;
+        type Theme = P::Theme;
+        type Renderer = P::Renderer;
+        type Executor = P::Executor;
+
+        fn namespace(&self, state: &Self::State) -> String {
+            self.program.namespace(state)
+        }
+
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
+        }
+
+        fn view<'a>(
+            &self,
+            state: &'a Self::State,
+        ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+            self.program.view(state)
+        }
+
+        fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+            self.program.subscription(state)
+        }
+
+        fn theme(&self, state: &Self::State) -> Self::Theme {
+            self.program.theme(state)
+        }
+
+        fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+            self.program.style(state, theme)
+        }
+
+        fn scale_factor(&self, state: &Self::State) -> f64 {
+            self.program.scale_factor(state)
+        }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            (self.loop_sources)(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, seat, event)
+        }
+    }
+
+    WithLoopSources {
+        program,
+        loop_sources: f,
+    }
+}
+
+pub fn with_capabilities_reported<P: Program>(
+    program: P,
+    f: impl Fn(&mut P::State, layershellev::Capabilities),
+) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+    struct WithCapabilitiesReported<P, F> {
+        program: P,
+        capabilities_reported: F,
+    }
+
+    impl<P: Program, F> Program for WithCapabilitiesReported<P, F>
+    where
+        F: Fn(&mut P::State, layershellev::Capabilities),
+    {
+        type State = P::State;
+        type Message = P::Message;
+        type Theme = P::Theme;
+        type Renderer = P::Renderer;
+        type Executor = P::Executor;
+
+        fn namespace(&self, state: &Self::State) -> String {
+            self.program.namespace(state)
+        }
+
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
+        }
+
+        fn view<'a>(
+            &self,
+            state: &'a Self::State,
+        ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+            self.program.view(state)
+        }
+
+        fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+            self.program.subscription(state)
+        }
+
+        fn theme(&self, state: &Self::State) -> Self::Theme {
+            self.program.theme(state)
+        }
+
+        fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+            self.program.style(state, theme)
+        }
+
+        fn scale_factor(&self, state: &Self::State) -> f64 {
+            self.program.scale_factor(state)
+        }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            (self.capabilities_reported)(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, seat, event)
+        }
+    }
+
+    WithCapabilitiesReported {
+        program,
+        capabilities_reported: f,
+    }
+}
+
+pub fn with_seat_input<P: Program>(
+    program: P,
+    f: impl Fn(&P::State, SeatId, &iced::Event) -> Option<P::Message>,
+) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+    struct WithSeatInput<P, F> {
+        program: P,
+        seat_input: F,
+    }
+
+    impl<P: Program, F> Program for WithSeatInput<P, F>
+    where
+        F: Fn(&P::State, SeatId, &iced::Event) -> Option<P::Message>,
+    {
+        type State = P::State;
+        type Message = P::Message;
+        type Theme = P::Theme;
+        type Renderer = P::Renderer;
+        type Executor = P::Executor;
+
+        fn namespace(&self, state: &Self::State) -> String {
+            self.program.namespace(state)
+        }
+
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
+        }
+
+        fn view<'a>(
+            &self,
+            state: &'a Self::State,
+        ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+            self.program.view(state)
+        }
+
+        fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+            self.program.subscription(state)
+        }
+
+        fn theme(&self, state: &Self::State) -> Self::Theme {
+            self.program.theme(state)
+        }
+
+        fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+            self.program.style(state, theme)
+        }
+
+        fn scale_factor(&self, state: &Self::State) -> f64 {
+            self.program.scale_factor(state)
+        }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            (self.seat_input)(state, seat, event)
+        }
+    }
+
+    WithSeatInput {
+        program,
+        seat_input: f,
+    }
+}
+
 impl<P: Program> SingleApplication<P> {
     pub fn run(self) -> Result
     where
@@ -707,6 +1115,30 @@ impl<P: Program> SingleApplication<P> {
         }
     }
 
+    /// Sets the cursor theme of the [`Application`], used when the compositor does not support
+    /// cursor-shape.
+    pub fn cursor_theme(self, cursor_theme: impl Into<String>) -> Self {
+        Self {
+            settings: MainSettings {
+                cursor_theme: Some(cursor_theme.into()),
+                ..self.settings
+            },
+            ..self
+        }
+    }
+
+    /// Sets the logical cursor size of the [`Application`], used when the compositor does not
+    /// support cursor-shape.
+    pub fn cursor_size(self, cursor_size: u32) -> Self {
+        Self {
+            settings: MainSettings {
+                cursor_size: Some(cursor_size),
+                ..self.settings
+            },
+            ..self
+        }
+    }
+
     /// Sets the default [`Font`] of the [`Application`].
     pub fn default_font(self, default_font: Font) -> Self {
         Self {
@@ -801,6 +1233,45 @@ impl<P: Program> SingleApplication<P> {
             settings: self.settings,
         }
     }
+
+    /// Sets the calloop sources of the [`Application`], see [`LoopSources`].
+    pub fn loop_sources(
+        self,
+        f: impl Fn(&P::State, &LoopSources<P::Message>),
+    ) -> SingleApplication<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>>
+    {
+        SingleApplication {
+            raw: with_loop_sources(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the function called with the protocols supported by the compositor, see
+    /// [`Program::capabilities_reported`].
+    pub fn capabilities_reported(
+        self,
+        f: impl Fn(&mut P::State, layershellev::Capabilities),
+    ) -> SingleApplication<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>>
+    {
+        SingleApplication {
+            raw: with_capabilities_reported(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the function called with the input events and their seat, see
+    /// [`Program::seat_input`].
+    pub fn seat_input(
+        self,
+        f: impl Fn(&P::State, SeatId, &iced::Event) -> Option<P::Message>,
+    ) -> SingleApplication<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>>
+    {
+        SingleApplication {
+            raw: with_seat_input(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
     /// Sets the executor of the [`Application`].
     pub fn executor<E>(
         self,
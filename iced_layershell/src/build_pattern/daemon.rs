@@ -7,6 +7,8 @@ use crate::actions::LayershellCustomActionsWithId;
 
 use crate::settings::LayerShellSettings;
 use crate::DefaultStyle;
+use crate::LoopSources;
+use layershellev::SeatId;
 
 use super::Renderer;
 use crate::Settings;
@@ -36,6 +38,23 @@ pub trait Program: Sized {
     /// The theme of your [`Application`].
     type Theme: Default + DefaultStyle;
     fn remove_id(&self, _state: &mut Self::State, _id: iced_core::window::Id);
+
+    /// Called when a lock surface is created on an output, after the session is locked with
+    /// `LockSession`. The lock surfaces are passed to `remove_id` when the session is unlocked.
+    ///
+    /// By default, it does nothing.
+    fn lock_surface_added(&self, _state: &mut Self::State, _id: iced_core::window::Id) {}
+
+    /// Called when the compositor has locked the session, after `LockSession`.
+    ///
+    /// By default, it does nothing.
+    fn session_locked(&self, _state: &mut Self::State) {}
+
+    /// Called when the compositor refused to lock the session, or finished the lock. The lock
+    /// surfaces are passed to `remove_id` before it.
+    ///
+    /// By default, it does nothing.
+    fn session_lock_finished(&self, _state: &mut Self::State) {}
     /// Initializes the [`Application`] with the flags provided to
     /// [`run`] as part of the [`Settings`].
     ///
@@ -109,6 +128,38 @@ pub trait Program: Sized {
         1.0
     }
 
+    /// Inserts calloop sources, like timers or fd watches, into the event loop of the
+    /// [`Application`], see [`LoopSources`]. It is called once, after the state is initialized.
+    ///
+    /// By default, it inserts nothing.
+    fn loop_sources(&self, _state: &Self::State, _sources: &LoopSources<Self::Message>) {}
+
+    /// Called once at startup with the protocols supported by the compositor, before the first
+    /// view, so the state can hide the features the compositor cannot do.
+    ///
+    /// By default, it does nothing.
+    fn capabilities_reported(
+        &self,
+        _state: &mut Self::State,
+        _capabilities: layershellev::Capabilities,
+    ) {
+    }
+
+    /// Called with every pointer, keyboard and touch event, together with the seat which produced
+    /// it, so the input of several seats can be told apart. The returned message is handled by
+    /// [`Program::update`].
+    ///
+    /// By default, it returns `None`.
+    fn seat_input(
+        &self,
+        _state: &Self::State,
+        _id: iced_core::window::Id,
+        _seat: SeatId,
+        _event: &iced::Event,
+    ) -> Option<Self::Message> {
+        None
+    }
+
     fn run_with<I>(self, settings: MainSettings, initialize: I) -> Result
     where
         Self: 'static,
@@ -165,6 +216,18 @@ pub trait Program: Sized {
                 self.program.remove_id(&mut self.state, id)
             }
 
+            fn lock_surface_added(&mut self, id: iced_core::window::Id) {
+                self.program.lock_surface_added(&mut self.state, id)
+            }
+
+            fn session_locked(&mut self) {
+                self.program.session_locked(&mut self.state)
+            }
+
+            fn session_lock_finished(&mut self) {
+                self.program.session_lock_finished(&mut self.state)
+            }
+
             fn subscription(&self) -> iced::Subscription<Self::Message> {
                 self.program.subscription(&self.state)
             }
@@ -180,6 +243,24 @@ pub trait Program: Sized {
             fn scale_factor(&self, window: iced_core::window::Id) -> f64 {
                 self.program.scale_factor(&self.state, window)
             }
+
+            fn loop_sources(&self, sources: &LoopSources<Self::Message>) {
+                self.program.loop_sources(&self.state, sources)
+            }
+
+            fn capabilities_reported(&mut self, capabilities: layershellev::Capabilities) {
+                self.program
+                    .capabilities_reported(&mut self.state, capabilities)
+            }
+
+            fn seat_input(
+                &self,
+                id: iced_core::window::Id,
+                seat: SeatId,
+                event: &iced::Event,
+            ) -> Option<Self::Message> {
+                self.program.seat_input(&self.state, id, seat, event)
+            }
         }
 
         let real_settings = Settings {
@@ -191,6 +272,8 @@ pub trait Program: Sized {
             default_text_size: settings.default_text_size,
             antialiasing: settings.antialiasing,
             virtual_keyboard_support: settings.virtual_keyboard_support,
+            cursor_theme: settings.cursor_theme,
+            cursor_size: settings.cursor_size,
         };
         #[allow(clippy::needless_update)]
         let renderer_settings = iced_graphics::Settings {
@@ -412,6 +495,15 @@ fn with_namespace<P: Program>(
         fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
             self.program.remove_id(state, id)
         }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
 
         fn view<'a>(
             &self,
@@ -436,6 +528,28 @@ fn with_namespace<P: Program>(
         fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
             self.program.scale_factor(state, window)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
     }
 
     WithNamespace { program, namespace }
@@ -466,6 +580,15 @@ pub fn with_subscription<P: Program>(
         fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
             self.program.remove_id(state, id)
         }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
         fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
             self.program.update(state, message)
         }
@@ -493,6 +616,28 @@ pub fn with_subscription<P: Program>(
         fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
             self.program.scale_factor(state, window)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
     }
 
     WithSubscription {
@@ -526,6 +671,15 @@ pub fn with_theme<P: Program>(
         fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
             self.program.remove_id(state, id)
         }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
         fn namespace(&self, state: &Self::State) -> String {
             self.program.namespace(state)
         }
@@ -553,6 +707,28 @@ pub fn with_theme<P: Program>(
         fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
             self.program.scale_factor(state, window)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
     }
 
     WithTheme { program, theme: f }
@@ -591,6 +767,15 @@ pub fn with_style<P: Program>(
         fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
             self.program.remove_id(state, id)
         }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
         fn view<'a>(
             &self,
             state: &'a Self::State,
@@ -610,6 +795,28 @@ pub fn with_style<P: Program>(
         fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
             self.program.scale_factor(state, window)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
     }
 
     WithStyle { program, style: f }
@@ -640,6 +847,15 @@ pub fn with_scale_factor<P: Program>(
         fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
             self.program.remove_id(state, id)
         }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
         fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
             self.program.update(state, message)
         }
@@ -667,6 +883,28 @@ pub fn with_scale_factor<P: Program>(
         fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
             (self.scale_factor)(state, window)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
     }
 
     WithScaleFactor {
@@ -675,36 +913,44 @@ pub fn with_scale_factor<P: Program>(
     }
 }
 
-pub fn with_executor<P: Program, E: iced_futures::Executor>(
+pub fn with_loop_sources<P: Program>(
     program: P,
+    f: impl Fn(&P::State, &LoopSources<P::Message>),
 ) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
-    use std::marker::PhantomData;
-
-    struct WithExecutor<P, E> {
+    struct WithLoopSources<P, F> {
         program: P,
-        executor: PhantomData<E>,
+        loop_sources: F,
     }
 
-    impl<P: Program, E> Program for WithExecutor<P, E>
+    impl<P: Program, F> Program for WithLoopSources<P, F>
     where
-        E: iced_futures::Executor,
+        F: Fn(&P::State, &LoopSources<P::Message>),
     {
         type State = P::State;
         type Message = P::Message;
         type Theme = P::Theme;
         type Renderer = P::Renderer;
-        type Executor = E;
+        type Executor = P::Executor;
 
         fn namespace(&self, state: &Self::State) -> String {
             self.program.namespace(state)
         }
-
-        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
-            self.program.update(state, message)
-        }
         fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
             self.program.remove_id(state, id)
         }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
+        }
+
         fn view<'a>(
             &self,
             state: &'a Self::State,
@@ -728,130 +974,792 @@ pub fn with_executor<P: Program, E: iced_futures::Executor>(
         fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
             self.program.scale_factor(state, window)
         }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            (self.loop_sources)(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
     }
 
-    WithExecutor {
+    WithLoopSources {
         program,
-        executor: PhantomData::<E>,
+        loop_sources: f,
     }
 }
-impl<P: Program> Daemon<P> {
-    pub fn run(self) -> Result
-    where
-        Self: 'static,
-        P::State: Default,
-    {
-        self.raw.run(self.settings)
+
+pub fn with_capabilities_reported<P: Program>(
+    program: P,
+    f: impl Fn(&mut P::State, layershellev::Capabilities),
+) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+    struct WithCapabilitiesReported<P, F> {
+        program: P,
+        capabilities_reported: F,
     }
 
-    pub fn run_with<I>(self, initialize: I) -> Result
+    impl<P: Program, F> Program for WithCapabilitiesReported<P, F>
     where
-        Self: 'static,
-        I: FnOnce() -> (P::State, Task<P::Message>) + 'static,
+        F: Fn(&mut P::State, layershellev::Capabilities),
     {
-        self.raw.run_with(self.settings, initialize)
-    }
-    pub fn settings(self, settings: MainSettings) -> Self {
-        Self { settings, ..self }
-    }
+        type State = P::State;
+        type Message = P::Message;
+        type Theme = P::Theme;
+        type Renderer = P::Renderer;
+        type Executor = P::Executor;
 
-    /// Sets the [`Settings::antialiasing`] of the [`Application`].
-    pub fn antialiasing(self, antialiasing: bool) -> Self {
-        Self {
-            settings: MainSettings {
-                antialiasing,
-                ..self.settings
-            },
-            ..self
+        fn namespace(&self, state: &Self::State) -> String {
+            self.program.namespace(state)
+        }
+        fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.remove_id(state, id)
+        }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
         }
-    }
 
-    /// Sets the default [`Font`] of the [`Application`].
-    pub fn default_font(self, default_font: Font) -> Self {
-        Self {
-            settings: MainSettings {
-                default_font,
-                ..self.settings
-            },
-            ..self
+        fn view<'a>(
+            &self,
+            state: &'a Self::State,
+            window: iced_core::window::Id,
+        ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+            self.program.view(state, window)
         }
-    }
 
-    pub fn layer_settings(self, layer_settings: LayerShellSettings) -> Self {
-        Self {
-            settings: MainSettings {
-                layer_settings,
-                ..self.settings
-            },
-            ..self
+        fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+            self.program.subscription(state)
         }
-    }
 
-    /// Adds a font to the list of fonts that will be loaded at the start of the [`Application`].
-    pub fn font(mut self, font: impl Into<Cow<'static, [u8]>>) -> Self {
-        self.settings.fonts.push(font.into());
-        self
-    }
+        fn theme(&self, state: &Self::State) -> Self::Theme {
+            self.program.theme(state)
+        }
 
-    /// set the default_text_size
-    pub fn default_text_size(self, default_text_size: iced::Pixels) -> Self {
-        Self {
-            settings: MainSettings {
-                default_text_size,
-                ..self.settings
-            },
-            ..self
+        fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+            self.program.style(state, theme)
         }
-    }
 
-    pub fn namespace(
-        self,
-        namespace: impl NameSpace<P::State>,
-    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
-        Daemon {
-            raw: with_namespace(self.raw, move |state| namespace.namespace(state)),
-            settings: self.settings,
+        fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
+            self.program.scale_factor(state, window)
         }
-    }
-    /// Sets the style logic of the [`Application`].
-    pub fn style(
-        self,
-        f: impl Fn(&P::State, &P::Theme) -> crate::Appearance,
-    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
-        Daemon {
-            raw: with_style(self.raw, f),
-            settings: self.settings,
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
         }
-    }
-    /// Sets the subscription logic of the [`Application`].
-    pub fn subscription(
-        self,
-        f: impl Fn(&P::State) -> iced::Subscription<P::Message>,
-    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
-        Daemon {
-            raw: with_subscription(self.raw, f),
-            settings: self.settings,
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            (self.capabilities_reported)(state, capabilities)
         }
-    }
 
-    /// Sets the theme logic of the [`Application`].
-    pub fn theme(
-        self,
-        f: impl Fn(&P::State) -> P::Theme,
-    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
-        Daemon {
-            raw: with_theme(self.raw, f),
-            settings: self.settings,
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
         }
     }
 
-    /// Sets the scale factor of the [`Application`].
-    pub fn scale_factor(
-        self,
-        f: impl Fn(&P::State, iced_core::window::Id) -> f64,
-    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
-        Daemon {
-            raw: with_scale_factor(self.raw, f),
+    WithCapabilitiesReported {
+        program,
+        capabilities_reported: f,
+    }
+}
+
+pub fn with_seat_input<P: Program>(
+    program: P,
+    f: impl Fn(&P::State, iced_core::window::Id, SeatId, &iced::Event) -> Option<P::Message>,
+) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+    struct WithSeatInput<P, F> {
+        program: P,
+        seat_input: F,
+    }
+
+    impl<P: Program, F> Program for WithSeatInput<P, F>
+    where
+        F: Fn(&P::State, iced_core::window::Id, SeatId, &iced::Event) -> Option<P::Message>,
+    {
+        type State = P::State;
+        type Message = P::Message;
+        type Theme = P::Theme;
+        type Renderer = P::Renderer;
+        type Executor = P::Executor;
+
+        fn namespace(&self, state: &Self::State) -> String {
+            self.program.namespace(state)
+        }
+        fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.remove_id(state, id)
+        }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
+        }
+
+        fn view<'a>(
+            &self,
+            state: &'a Self::State,
+            window: iced_core::window::Id,
+        ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+            self.program.view(state, window)
+        }
+
+        fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+            self.program.subscription(state)
+        }
+
+        fn theme(&self, state: &Self::State) -> Self::Theme {
+            self.program.theme(state)
+        }
+
+        fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+            self.program.style(state, theme)
+        }
+
+        fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
+            self.program.scale_factor(state, window)
+        }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            (self.seat_input)(state, id, seat, event)
+        }
+    }
+
+    WithSeatInput {
+        program,
+        seat_input: f,
+    }
+}
+
+pub fn with_lock_surface_added<P: Program>(
+    program: P,
+    f: impl Fn(&mut P::State, iced_core::window::Id),
+) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+    struct WithLockSurfaceAdded<P, F> {
+        program: P,
+        lock_surface_added: F,
+    }
+
+    impl<P: Program, F> Program for WithLockSurfaceAdded<P, F>
+    where
+        F: Fn(&mut P::State, iced_core::window::Id),
+    {
+        type State = P::State;
+        type Message = P::Message;
+        type Theme = P::Theme;
+        type Renderer = P::Renderer;
+        type Executor = P::Executor;
+
+        fn namespace(&self, state: &Self::State) -> String {
+            self.program.namespace(state)
+        }
+        fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.remove_id(state, id)
+        }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            (self.lock_surface_added)(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
+        }
+
+        fn view<'a>(
+            &self,
+            state: &'a Self::State,
+            window: iced_core::window::Id,
+        ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+            self.program.view(state, window)
+        }
+
+        fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+            self.program.subscription(state)
+        }
+
+        fn theme(&self, state: &Self::State) -> Self::Theme {
+            self.program.theme(state)
+        }
+
+        fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+            self.program.style(state, theme)
+        }
+
+        fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
+            self.program.scale_factor(state, window)
+        }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
+    }
+
+    WithLockSurfaceAdded {
+        program,
+        lock_surface_added: f,
+    }
+}
+
+pub fn with_session_locked<P: Program>(
+    program: P,
+    f: impl Fn(&mut P::State),
+) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+    struct WithSessionLocked<P, F> {
+        program: P,
+        session_locked: F,
+    }
+
+    impl<P: Program, F> Program for WithSessionLocked<P, F>
+    where
+        F: Fn(&mut P::State),
+    {
+        type State = P::State;
+        type Message = P::Message;
+        type Theme = P::Theme;
+        type Renderer = P::Renderer;
+        type Executor = P::Executor;
+
+        fn namespace(&self, state: &Self::State) -> String {
+            self.program.namespace(state)
+        }
+        fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.remove_id(state, id)
+        }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            (self.session_locked)(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
+        }
+
+        fn view<'a>(
+            &self,
+            state: &'a Self::State,
+            window: iced_core::window::Id,
+        ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+            self.program.view(state, window)
+        }
+
+        fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+            self.program.subscription(state)
+        }
+
+        fn theme(&self, state: &Self::State) -> Self::Theme {
+            self.program.theme(state)
+        }
+
+        fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+            self.program.style(state, theme)
+        }
+
+        fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
+            self.program.scale_factor(state, window)
+        }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
+    }
+
+    WithSessionLocked {
+        program,
+        session_locked: f,
+    }
+}
+
+pub fn with_session_lock_finished<P: Program>(
+    program: P,
+    f: impl Fn(&mut P::State),
+) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+    struct WithSessionLockFinished<P, F> {
+        program: P,
+        session_lock_finished: F,
+    }
+
+    impl<P: Program, F> Program for WithSessionLockFinished<P, F>
+    where
+        F: Fn(&mut P::State),
+    {
+        type State = P::State;
+        type Message = P::Message;
+        type Theme = P::Theme;
+        type Renderer = P::Renderer;
+        type Executor = P::Executor;
+
+        fn namespace(&self, state: &Self::State) -> String {
+            self.program.namespace(state)
+        }
+        fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.remove_id(state, id)
+        }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            (self.session_lock_finished)(state)
+        }
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
+        }
+
+        fn view<'a>(
+            &self,
+            state: &'a Self::State,
+            window: iced_core::window::Id,
+        ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+            self.program.view(state, window)
+        }
+
+        fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+            self.program.subscription(state)
+        }
+
+        fn theme(&self, state: &Self::State) -> Self::Theme {
+            self.program.theme(state)
+        }
+
+        fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+            self.program.style(state, theme)
+        }
+
+        fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
+            self.program.scale_factor(state, window)
+        }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
+    }
+
+    WithSessionLockFinished {
+        program,
+        session_lock_finished: f,
+    }
+}
+
+pub fn with_executor<P: Program, E: iced_futures::Executor>(
+    program: P,
+) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+    use std::marker::PhantomData;
+
+    struct WithExecutor<P, E> {
+        program: P,
+        executor: PhantomData<E>,
+    }
+
+    impl<P: Program, E> Program for WithExecutor<P, E>
+    where
+        E: iced_futures::Executor,
+    {
+        type State = P::State;
+        type Message = P::Message;
+        type Theme = P::Theme;
+        type Renderer = P::Renderer;
+        type Executor = E;
+
+        fn namespace(&self, state: &Self::State) -> String {
+            self.program.namespace(state)
+        }
+
+        fn update(&self, state: &mut Self::State, message: Self::Message) -> Task<Self::Message> {
+            self.program.update(state, message)
+        }
+        fn remove_id(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.remove_id(state, id)
+        }
+        fn lock_surface_added(&self, state: &mut Self::State, id: iced_core::window::Id) {
+            self.program.lock_surface_added(state, id)
+        }
+        fn session_locked(&self, state: &mut Self::State) {
+            self.program.session_locked(state)
+        }
+        fn session_lock_finished(&self, state: &mut Self::State) {
+            self.program.session_lock_finished(state)
+        }
+        fn view<'a>(
+            &self,
+            state: &'a Self::State,
+            window: iced_core::window::Id,
+        ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+            self.program.view(state, window)
+        }
+
+        fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+            self.program.subscription(state)
+        }
+
+        fn theme(&self, state: &Self::State) -> Self::Theme {
+            self.program.theme(state)
+        }
+
+        fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+            self.program.style(state, theme)
+        }
+
+        fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
+            self.program.scale_factor(state, window)
+        }
+
+        fn loop_sources(&self, state: &Self::State, sources: &LoopSources<Self::Message>) {
+            self.program.loop_sources(state, sources)
+        }
+
+        fn capabilities_reported(
+            &self,
+            state: &mut Self::State,
+            capabilities: layershellev::Capabilities,
+        ) {
+            self.program.capabilities_reported(state, capabilities)
+        }
+
+        fn seat_input(
+            &self,
+            state: &Self::State,
+            id: iced_core::window::Id,
+            seat: SeatId,
+            event: &iced::Event,
+        ) -> Option<Self::Message> {
+            self.program.seat_input(state, id, seat, event)
+        }
+    }
+
+    WithExecutor {
+        program,
+        executor: PhantomData::<E>,
+    }
+}
+impl<P: Program> Daemon<P> {
+    pub fn run(self) -> Result
+    where
+        Self: 'static,
+        P::State: Default,
+    {
+        self.raw.run(self.settings)
+    }
+
+    pub fn run_with<I>(self, initialize: I) -> Result
+    where
+        Self: 'static,
+        I: FnOnce() -> (P::State, Task<P::Message>) + 'static,
+    {
+        self.raw.run_with(self.settings, initialize)
+    }
+    pub fn settings(self, settings: MainSettings) -> Self {
+        Self { settings, ..self }
+    }
+
+    /// Sets the [`Settings::antialiasing`] of the [`Application`].
+    pub fn antialiasing(self, antialiasing: bool) -> Self {
+        Self {
+            settings: MainSettings {
+                antialiasing,
+                ..self.settings
+            },
+            ..self
+        }
+    }
+
+    /// Sets the cursor theme of the [`Application`], used when the compositor does not support
+    /// cursor-shape.
+    pub fn cursor_theme(self, cursor_theme: impl Into<String>) -> Self {
+        Self {
+            settings: MainSettings {
+                cursor_theme: Some(cursor_theme.into()),
+                ..self.settings
+            },
+            ..self
+        }
+    }
+
+    /// Sets the logical cursor size of the [`Application`], used when the compositor does not
+    /// support cursor-shape.
+    pub fn cursor_size(self, cursor_size: u32) -> Self {
+        Self {
+            settings: MainSettings {
+                cursor_size: Some(cursor_size),
+                ..self.settings
+            },
+            ..self
+        }
+    }
+
+    /// Sets the default [`Font`] of the [`Application`].
+    pub fn default_font(self, default_font: Font) -> Self {
+        Self {
+            settings: MainSettings {
+                default_font,
+                ..self.settings
+            },
+            ..self
+        }
+    }
+
+    pub fn layer_settings(self, layer_settings: LayerShellSettings) -> Self {
+        Self {
+            settings: MainSettings {
+                layer_settings,
+                ..self.settings
+            },
+            ..self
+        }
+    }
+
+    /// Adds a font to the list of fonts that will be loaded at the start of the [`Application`].
+    pub fn font(mut self, font: impl Into<Cow<'static, [u8]>>) -> Self {
+        self.settings.fonts.push(font.into());
+        self
+    }
+
+    /// set the default_text_size
+    pub fn default_text_size(self, default_text_size: iced::Pixels) -> Self {
+        Self {
+            settings: MainSettings {
+                default_text_size,
+                ..self.settings
+            },
+            ..self
+        }
+    }
+
+    pub fn namespace(
+        self,
+        namespace: impl NameSpace<P::State>,
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_namespace(self.raw, move |state| namespace.namespace(state)),
+            settings: self.settings,
+        }
+    }
+    /// Sets the style logic of the [`Application`].
+    pub fn style(
+        self,
+        f: impl Fn(&P::State, &P::Theme) -> crate::Appearance,
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_style(self.raw, f),
+            settings: self.settings,
+        }
+    }
+    /// Sets the subscription logic of the [`Application`].
+    pub fn subscription(
+        self,
+        f: impl Fn(&P::State) -> iced::Subscription<P::Message>,
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_subscription(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the theme logic of the [`Application`].
+    pub fn theme(
+        self,
+        f: impl Fn(&P::State) -> P::Theme,
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_theme(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the scale factor of the [`Application`].
+    pub fn scale_factor(
+        self,
+        f: impl Fn(&P::State, iced_core::window::Id) -> f64,
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_scale_factor(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the calloop sources of the [`Application`], see [`LoopSources`].
+    pub fn loop_sources(
+        self,
+        f: impl Fn(&P::State, &LoopSources<P::Message>),
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_loop_sources(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the function called with the protocols supported by the compositor, see
+    /// [`Program::capabilities_reported`].
+    pub fn capabilities_reported(
+        self,
+        f: impl Fn(&mut P::State, layershellev::Capabilities),
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_capabilities_reported(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the function called with the input events and their seat, see
+    /// [`Program::seat_input`].
+    pub fn seat_input(
+        self,
+        f: impl Fn(&P::State, iced_core::window::Id, SeatId, &iced::Event) -> Option<P::Message>,
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_seat_input(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the function called when a lock surface is created, see
+    /// [`Program::lock_surface_added`].
+    pub fn lock_surface_added(
+        self,
+        f: impl Fn(&mut P::State, iced_core::window::Id),
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_lock_surface_added(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the function called when the session is locked, see [`Program::session_locked`].
+    pub fn session_locked(
+        self,
+        f: impl Fn(&mut P::State),
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_session_locked(self.raw, f),
+            settings: self.settings,
+        }
+    }
+
+    /// Sets the function called when the session lock is finished, see [`Program::session_lock_finished`].
+    pub fn session_lock_finished(
+        self,
+        f: impl Fn(&mut P::State),
+    ) -> Daemon<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>> {
+        Daemon {
+            raw: with_session_lock_finished(self.raw, f),
             settings: self.settings,
         }
     }
@@ -14,6 +14,10 @@ pub enum Error {
     /// The application graphics context could not be created.
     #[error("the application graphics context could not be created")]
     GraphicsCreationFailed(iced_graphics::Error),
+
+    /// The window action has no equivalent for a layershell.
+    #[error("the window action {0} is not supported by layershell")]
+    UnsupportedWindowAction(&'static str),
 }
 
 impl From<iced_graphics::Error> for Error {
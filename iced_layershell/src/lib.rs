@@ -1,13 +1,15 @@
 #![doc = include_str!("../README.md")]
 pub mod actions;
+pub mod animation;
 pub mod application;
+mod auto_hide;
+mod auto_size;
 pub mod build_pattern;
-mod clipboard;
-mod conversion;
 mod error;
 mod event;
+mod loop_sources;
 pub mod multi_window;
-mod proxy;
+pub mod region;
 mod sandbox;
 
 pub mod settings;
@@ -17,7 +19,10 @@ pub mod reexport {
     pub use layershellev::reexport::Anchor;
     pub use layershellev::reexport::KeyboardInteractivity;
     pub use layershellev::reexport::Layer;
+    pub use layershellev::Capabilities;
+    pub use layershellev::CustomCursor;
     pub use layershellev::NewLayerShellSettings;
+    pub use layershellev::SeatId;
 }
 
 use actions::{LayershellCustomActions, LayershellCustomActionsWithId};
@@ -29,43 +34,14 @@ pub use iced_layershell_macros::to_layer_message;
 
 pub use error::Error;
 
-use iced::{Color, Element, Theme};
+use iced::Element;
 use iced_futures::Subscription;
 
+pub use loop_sources::LoopSources;
 pub use sandbox::LayerShellSandbox;
 
 pub type Result = std::result::Result<(), error::Error>;
-/// The appearance of a program.
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Appearance {
-    /// The background [`Color`] of the application.
-    pub background_color: Color,
-
-    /// The default text [`Color`] of the application.
-    pub text_color: Color,
-}
-
-/// The default style of a [`Application`].
-pub trait DefaultStyle {
-    /// Returns the default style of a [`Appearance`].
-    fn default_style(&self) -> Appearance;
-}
-
-impl DefaultStyle for Theme {
-    fn default_style(&self) -> Appearance {
-        default(self)
-    }
-}
-
-/// The default [`Appearance`] of a [`Application`] with the built-in [`Theme`].
-pub fn default(theme: &Theme) -> Appearance {
-    let palette = theme.extended_palette();
-
-    Appearance {
-        background_color: palette.background.base.color,
-        text_color: palette.background.base.text,
-    }
-}
+pub use iced_shellcore::{default, Appearance, DefaultStyle};
 
 // layershell application
 pub trait Application: Sized {
@@ -157,6 +133,31 @@ pub trait Application: Sized {
         1.0
     }
 
+    /// Inserts calloop sources, like timers or fd watches, into the event loop of the
+    /// [`Application`], see [`LoopSources`]. It is called once, after [`new`](#tymethod.new).
+    ///
+    /// By default, it inserts nothing.
+    fn loop_sources(&self, _sources: &LoopSources<Self::Message>) {}
+
+    /// Called once at startup with the protocols supported by the compositor, before the first
+    /// view, so the [`Application`] can hide the features the compositor cannot do.
+    ///
+    /// By default, it does nothing.
+    fn capabilities_reported(&mut self, _capabilities: layershellev::Capabilities) {}
+
+    /// Called with every pointer, keyboard and touch event forwarded to the [`Application`],
+    /// together with the seat which produced it, so the input of several seats can be told
+    /// apart. The returned message is handled by [`update`](#tymethod.update).
+    ///
+    /// By default, it returns `None`.
+    fn seat_input(
+        &self,
+        _seat: layershellev::SeatId,
+        _event: &iced::Event,
+    ) -> Option<Self::Message> {
+        None
+    }
+
     /// Runs the [`Application`].
     ///
     /// On native platforms, this method will take control of the current thread
@@ -241,6 +242,18 @@ where
     fn scale_factor(&self) -> f64 {
         self.0.scale_factor()
     }
+
+    fn loop_sources(&self, sources: &LoopSources<Self::Message>) {
+        self.0.loop_sources(sources)
+    }
+
+    fn capabilities_reported(&mut self, capabilities: layershellev::Capabilities) {
+        self.0.capabilities_reported(capabilities)
+    }
+
+    fn seat_input(&self, seat: layershellev::SeatId, event: &iced::Event) -> Option<Self::Message> {
+        self.0.seat_input(seat, event)
+    }
 }
 
 pub trait MultiApplication: Sized {
@@ -279,6 +292,24 @@ pub trait MultiApplication: Sized {
     fn namespace(&self) -> String;
 
     fn remove_id(&mut self, _id: iced_core::window::Id) {}
+
+    /// Called when a lock surface is created on an output, after the session is locked with
+    /// `LockSession`. The lock surfaces are passed to [`remove_id`](#method.remove_id) when the
+    /// session is unlocked.
+    ///
+    /// By default, it does nothing.
+    fn lock_surface_added(&mut self, _id: iced_core::window::Id) {}
+
+    /// Called when the compositor has locked the session, after `LockSession`.
+    ///
+    /// By default, it does nothing.
+    fn session_locked(&mut self) {}
+
+    /// Called when the compositor refused to lock the session, or finished the lock. The lock
+    /// surfaces are passed to [`remove_id`](#method.remove_id) before it.
+    ///
+    /// By default, it does nothing.
+    fn session_lock_finished(&mut self) {}
     /// Handles a __message__ and updates the state of the [`Application`].
     ///
     /// This is where you define your __update logic__. All the __messages__,
@@ -337,6 +368,32 @@ pub trait MultiApplication: Sized {
         1.0
     }
 
+    /// Inserts calloop sources, like timers or fd watches, into the event loop of the
+    /// [`MultiApplication`], see [`LoopSources`]. It is called once, after [`new`](#tymethod.new).
+    ///
+    /// By default, it inserts nothing.
+    fn loop_sources(&self, _sources: &LoopSources<Self::Message>) {}
+
+    /// Called once at startup with the protocols supported by the compositor, before the first
+    /// view, so the [`MultiApplication`] can hide the features the compositor cannot do.
+    ///
+    /// By default, it does nothing.
+    fn capabilities_reported(&mut self, _capabilities: layershellev::Capabilities) {}
+
+    /// Called with every pointer, keyboard and touch event forwarded to the [`Application`],
+    /// together with the seat which produced it, so the input of several seats can be told
+    /// apart. The returned message is handled by [`update`](#tymethod.update).
+    ///
+    /// By default, it returns `None`.
+    fn seat_input(
+        &self,
+        _id: iced_core::window::Id,
+        _seat: layershellev::SeatId,
+        _event: &iced::Event,
+    ) -> Option<Self::Message> {
+        None
+    }
+
     /// Runs the multi-window [`Application`].
     ///
     /// On native platforms, this method will take control of the current thread
@@ -423,14 +480,45 @@ where
     fn scale_factor(&self, window: iced::window::Id) -> f64 {
         self.0.scale_factor(window)
     }
+
+    fn loop_sources(&self, sources: &LoopSources<Self::Message>) {
+        self.0.loop_sources(sources)
+    }
+
+    fn capabilities_reported(&mut self, capabilities: layershellev::Capabilities) {
+        self.0.capabilities_reported(capabilities)
+    }
+
+    fn seat_input(
+        &self,
+        id: iced_core::window::Id,
+        seat: layershellev::SeatId,
+        event: &iced::Event,
+    ) -> Option<Self::Message> {
+        self.0.seat_input(id, seat, event)
+    }
+
     fn remove_id(&mut self, id: iced_core::window::Id) {
         self.0.remove_id(id)
     }
+
+    fn lock_surface_added(&mut self, id: iced_core::window::Id) {
+        self.0.lock_surface_added(id)
+    }
+
+    fn session_locked(&mut self) {
+        self.0.session_locked()
+    }
+
+    fn session_lock_finished(&mut self) {
+        self.0.session_lock_finished()
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     use iced::widget::text;
+    use iced::{Color, Theme};
 
     struct TestApp {
         counter: i32,
@@ -0,0 +1,128 @@
+use std::{cell::RefCell, sync::mpsc::Sender};
+
+use iced_runtime::Action;
+use layershellev::{
+    calloop::{EventSource, LoopHandle},
+    WindowState,
+};
+
+/// The calloop sources, like timers or fd watches, to insert into the event loop of the
+/// layershell. They are dispatched on the same thread as the ui, and every message pushed by the
+/// callback of a source is passed to `update`.
+///
+/// A source is removed when its callback asks for it, for example with
+/// [TimeoutAction::Drop](layershellev::calloop::timer::TimeoutAction::Drop) or
+/// [PostAction::Remove](layershellev::calloop::PostAction::Remove).
+pub struct LoopSources<Message> {
+    pending: RefCell<Vec<Box<dyn PendingSource<Message>>>>,
+}
+
+impl<Message: 'static> LoopSources<Message> {
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Insert a source. The source is inserted into the loop once the layershell is created.
+    pub fn insert_source<S, F>(&self, source: S, callback: F)
+    where
+        S: EventSource + 'static,
+        F: FnMut(S::Event, &mut S::Metadata, &mut Vec<Message>) -> S::Ret + 'static,
+    {
+        self.pending
+            .borrow_mut()
+            .push(Box::new(Pending { source, callback }));
+    }
+
+    pub(crate) fn insert_into_single(
+        self,
+        handle: &LoopHandle<'static, WindowState<()>>,
+        sender: &Sender<Action<Message>>,
+    ) {
+        for pending in self.pending.into_inner() {
+            pending.insert_single(handle, sender.clone());
+        }
+    }
+
+    pub(crate) fn insert_into_multi(
+        self,
+        handle: &LoopHandle<'static, WindowState<iced::window::Id>>,
+        sender: &Sender<Action<Message>>,
+    ) {
+        for pending in self.pending.into_inner() {
+            pending.insert_multi(handle, sender.clone());
+        }
+    }
+}
+
+// NOTE: trait objects cannot have generic methods, so there is one method for every kind of
+// WindowState used by iced_layershell
+trait PendingSource<Message> {
+    fn insert_single(
+        self: Box<Self>,
+        handle: &LoopHandle<'static, WindowState<()>>,
+        sender: Sender<Action<Message>>,
+    );
+
+    fn insert_multi(
+        self: Box<Self>,
+        handle: &LoopHandle<'static, WindowState<iced::window::Id>>,
+        sender: Sender<Action<Message>>,
+    );
+}
+
+struct Pending<S, F> {
+    source: S,
+    callback: F,
+}
+
+impl<S, F> Pending<S, F> {
+    fn insert<Message, T>(
+        self,
+        handle: &LoopHandle<'static, WindowState<T>>,
+        sender: Sender<Action<Message>>,
+    ) where
+        Message: 'static,
+        S: EventSource + 'static,
+        F: FnMut(S::Event, &mut S::Metadata, &mut Vec<Message>) -> S::Ret + 'static,
+    {
+        let Self {
+            source,
+            mut callback,
+        } = self;
+        let mut messages = Vec::new();
+        if let Err(e) = handle.insert_source(source, move |event, metadata, _| {
+            let ret = callback(event, metadata, &mut messages);
+            for message in messages.drain(..) {
+                sender.send(Action::Output(message)).ok();
+            }
+            ret
+        }) {
+            log::error!("failed to insert the loop source: {}", e.error);
+        }
+    }
+}
+
+impl<Message, S, F> PendingSource<Message> for Pending<S, F>
+where
+    Message: 'static,
+    S: EventSource + 'static,
+    F: FnMut(S::Event, &mut S::Metadata, &mut Vec<Message>) -> S::Ret + 'static,
+{
+    fn insert_single(
+        self: Box<Self>,
+        handle: &LoopHandle<'static, WindowState<()>>,
+        sender: Sender<Action<Message>>,
+    ) {
+        self.insert(handle, sender);
+    }
+
+    fn insert_multi(
+        self: Box<Self>,
+        handle: &LoopHandle<'static, WindowState<iced::window::Id>>,
+        sender: Sender<Action<Message>>,
+    ) {
+        self.insert(handle, sender);
+    }
+}
@@ -1,32 +1,47 @@
-mod state;
 use crate::{
     actions::{
         IcedNewMenuSettings, IcedNewPopupSettings, LayerShellActionVec,
         LayershellCustomActionsWithId, LayershellCustomActionsWithIdInner, MenuDirection,
     },
-    multi_window::window_manager::WindowManager,
+    loop_sources::LoopSources,
+    region::{self, Region},
     settings::VirtualKeyboardSettings,
     DefaultStyle,
 };
 use std::{
-    borrow::Cow, collections::HashMap, f64, mem::ManuallyDrop, os::fd::AsFd, sync::Arc,
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    f64,
+    mem::ManuallyDrop,
+    os::fd::AsFd,
+    sync::Arc,
     time::Duration,
 };
 
 use crate::{
     actions::{LayerShellAction, LayershellCustomActions},
-    clipboard::LayerShellClipboard,
-    conversion,
+    animation::Animations,
+    auto_hide::AutoHide,
+    auto_size::AutoSizeState,
     error::Error,
 };
+use iced_shellcore::{
+    clipboard::ShellClipboard,
+    conversion,
+    multi_window::{
+        self, build_user_interface, build_user_interfaces, ShellAction, State,
+        UnsupportedWindowAction, WindowManager,
+    },
+    proxy::IcedProxy,
+};
 
 use super::Appearance;
 use iced_graphics::{compositor, Compositor};
 use iced_runtime::{Action, Task};
 
-use iced_core::{time::Instant, Size};
+use iced_core::time::Instant;
 
-use iced_runtime::{multi_window::Program, user_interface, Debug, UserInterface};
+use iced_runtime::{multi_window::Program, user_interface, Debug};
 
 use iced_futures::{Executor, Runtime, Subscription};
 
@@ -34,19 +49,17 @@ use layershellev::{
     calloop::timer::{TimeoutAction, Timer},
     reexport::wayland_client::{WlCompositor, WlRegion},
     reexport::zwp_virtual_keyboard_v1,
-    LayerEvent, NewPopUpSettings, ReturnData, WindowState, WindowWrapper,
+    CustomCursor, LayerEvent, NewPopUpSettings, ReturnData, SeatHandler, SeatId, WindowState,
+    WindowWrapper,
 };
 
 use futures::{channel::mpsc, StreamExt};
 
 use crate::{
     event::{IcedLayerEvent, MultiWindowIcedLayerEvent},
-    proxy::IcedProxy,
     settings::Settings,
 };
 
-mod window_manager;
-
 /// An interactive, native cross-platform application.
 ///
 /// This trait is the main entrypoint of Iced. Once implemented, you can run
@@ -86,6 +99,25 @@ where
     }
 
     fn remove_id(&mut self, _id: iced_core::window::Id);
+
+    /// Called when a lock surface is created on an output, after the session is locked with
+    /// `LockSession`. The lock surfaces are passed to [`remove_id`](#tymethod.remove_id) when the
+    /// session is unlocked.
+    ///
+    /// By default, it does nothing.
+    fn lock_surface_added(&mut self, _id: iced_core::window::Id) {}
+
+    /// Called when the compositor has locked the session, after `LockSession`.
+    ///
+    /// By default, it does nothing.
+    fn session_locked(&mut self) {}
+
+    /// Called when the compositor refused to lock the session, or finished the lock. The lock
+    /// surfaces are passed to [`remove_id`](#tymethod.remove_id) before it.
+    ///
+    /// By default, it does nothing.
+    fn session_lock_finished(&mut self) {}
+
     /// Returns the current `Theme` of the [`Application`].
     fn theme(&self) -> Self::Theme;
 
@@ -120,6 +152,32 @@ where
         1.0
     }
 
+    /// Inserts calloop sources, like timers or fd watches, into the event loop of the
+    /// [`Application`], see [`LoopSources`]. It is called once, after [`new`](#tymethod.new).
+    ///
+    /// By default, it inserts nothing.
+    fn loop_sources(&self, _sources: &LoopSources<Self::Message>) {}
+
+    /// Called once at startup with the protocols supported by the compositor, before the first
+    /// view, so the [`Application`] can hide the features the compositor cannot do.
+    ///
+    /// By default, it does nothing.
+    fn capabilities_reported(&mut self, _capabilities: layershellev::Capabilities) {}
+
+    /// Called with every pointer, keyboard and touch event forwarded to the [`Application`],
+    /// together with the seat which produced it, so the input of several seats can be told
+    /// apart. The returned message is handled by [`update`](#tymethod.update).
+    ///
+    /// By default, it returns `None`.
+    fn seat_input(
+        &self,
+        _id: iced_core::window::Id,
+        _seat: SeatId,
+        _event: &iced::Event,
+    ) -> Option<Self::Message> {
+        None
+    }
+
     /// Defines whether or not to use natural scrolling
     fn natural_scroll(&self) -> bool {
         false
@@ -155,18 +213,22 @@ where
 
     let (message_sender, message_receiver) = std::sync::mpsc::channel::<Action<A::Message>>();
 
-    let proxy = IcedProxy::new(message_sender);
+    let proxy = IcedProxy::new(message_sender.clone());
     let mut runtime: MultiRuntime<E, A::Message> = {
         let executor = E::new().map_err(Error::ExecutorCreationFailed)?;
 
         Runtime::new(executor, proxy)
     };
-    let (application, task) = {
+    let (mut application, task) = {
         let flags = settings.flags;
 
         runtime.enter(|| A::new(flags))
     };
 
+    let loop_sources = LoopSources::new();
+    application.loop_sources(&loop_sources);
+    let mut loop_sources = Some(loop_sources);
+
     if let Some(stream) = iced_runtime::task::into_stream(task) {
         runtime.run(stream);
     }
@@ -176,20 +238,26 @@ where
     ));
 
     let is_background_mode = settings.layer_settings.start_mode.is_background();
+    let declared_input_region = settings.layer_settings.declared_input_region();
     let ev: WindowState<iced::window::Id> =
         layershellev::WindowState::new(&application.namespace())
             .with_start_mode(settings.layer_settings.start_mode)
+            .with_cursor_theme(settings.cursor_theme.clone())
+            .with_cursor_size(settings.cursor_size)
             .with_use_display_handle(true)
             .with_events_transparent(settings.layer_settings.events_transparent)
             .with_option_size(settings.layer_settings.size)
             .with_layer(settings.layer_settings.layer)
             .with_anchor(settings.layer_settings.anchor)
             .with_exclusize_zone(settings.layer_settings.exclusive_zone)
+            .with_auto_exclusive_zone(settings.layer_settings.auto_exclusive_zone)
             .with_margin(settings.layer_settings.margin)
             .with_keyboard_interacivity(settings.layer_settings.keyboard_interactivity)
             .build()
             .expect("Cannot create layershell");
 
+    application.capabilities_reported(ev.capabilities());
+
     let window = Arc::new(ev.gen_main_wrapper());
 
     let (mut event_sender, event_receiver) =
@@ -206,12 +274,32 @@ where
         window,
         settings.fonts,
         is_background_mode,
+        AutoSizeState::new(
+            settings.layer_settings.auto_size,
+            settings.layer_settings.anchor,
+        ),
     ));
 
     let mut context = task::Context::from_waker(task::noop_waker_ref());
 
     let mut pointer_serial: u32 = 0;
+    // NOTE: the serial is only valid for the pointer of the seat which sent it
+    let mut pointer_seat: Option<SeatId> = None;
+    let mut custom_cursor: Option<CustomCursor> = None;
+    let mut last_interaction = iced_core::mouse::Interaction::default();
     let mut wl_input_region: Option<WlRegion> = None;
+    let mut wl_opaque_region: Option<WlRegion> = None;
+    // NOTE: the main layershells are created from the settings, only they follow the auto hide
+    // and the input region in the settings
+    let mut main_windows: HashSet<layershellev::id::Id> = HashSet::new();
+    let mut auto_hides: HashMap<layershellev::id::Id, AutoHide> = HashMap::new();
+    // NOTE: the regions are in the logical coordinates of the application, they are applied
+    // again when its scale factor changes
+    let mut scale_factors: HashMap<layershellev::id::Id, f64> = HashMap::new();
+    let mut input_regions: HashMap<layershellev::id::Id, Region> = HashMap::new();
+    // NOTE: their input region is empty, the declared one is kept in input_regions
+    let mut mouse_passthroughs: HashSet<layershellev::id::Id> = HashSet::new();
+    let mut animations = Animations::default();
 
     let _ = ev.running_with_proxy(message_receiver, move |event, ev, index| {
         use layershellev::DispatchMessage;
@@ -228,25 +316,40 @@ where
                     .bind::<WlCompositor, _, _>(qh, 1..=1, ())
                     .expect("could not bind wl_compositor");
                 wl_input_region = Some(wl_compositor.create_region(qh, ()));
-
-                if settings.virtual_keyboard_support.is_some() {
-                    let virtual_keyboard_manager = globals
-                        .bind::<zwp_virtual_keyboard_v1::ZwpVirtualKeyboardManagerV1, _, _>(
+                wl_opaque_region = Some(wl_compositor.create_region(qh, ()));
+
+                if let Some(VirtualKeyboardSettings {
+                    file,
+                    keymap_size,
+                    keymap_format,
+                }) = settings.virtual_keyboard_support.as_ref()
+                {
+                    match (
+                        globals.bind::<zwp_virtual_keyboard_v1::ZwpVirtualKeyboardManagerV1, _, _>(
                             qh,
                             1..=1,
                             (),
-                        )
-                        .expect("no support virtual_keyboard");
-                    let VirtualKeyboardSettings {
-                        file,
-                        keymap_size,
-                        keymap_format,
-                    } = settings.virtual_keyboard_support.as_ref().unwrap();
-                    let seat = ev.get_seat();
-                    let virtual_keyboard_in =
-                        virtual_keyboard_manager.create_virtual_keyboard(seat, qh, ());
-                    virtual_keyboard_in.keymap((*keymap_format).into(), file.as_fd(), *keymap_size);
-                    ev.set_virtual_keyboard(virtual_keyboard_in);
+                        ),
+                        ev.get_seat(),
+                    ) {
+                        (Ok(virtual_keyboard_manager), Some(seat)) => {
+                            let virtual_keyboard_in =
+                                virtual_keyboard_manager.create_virtual_keyboard(seat, qh, ());
+                            virtual_keyboard_in.keymap(
+                                (*keymap_format).into(),
+                                file.as_fd(),
+                                *keymap_size,
+                            );
+                            ev.set_virtual_keyboard(virtual_keyboard_in);
+                        }
+                        (Err(_), _) => tracing::error!(
+                            "the compositor does not support zwp_virtual_keyboard_v1, \
+                            the virtual keyboard is disabled"
+                        ),
+                        (Ok(_), None) => tracing::error!(
+                            "the compositor has no wl_seat, the virtual keyboard is disabled"
+                        ),
+                    }
                 }
             }
             LayerEvent::RequestMessages(message) => 'outside: {
@@ -260,6 +363,41 @@ where
                         let Some(unit) = ev.get_mut_unit_with_id(sended_id.unwrap()) else {
                             break 'outside;
                         };
+                        let is_main_window =
+                            !unit.is_session_lock() && unit.get_binding().is_none();
+                        if is_main_window && main_windows.insert(unit.id()) {
+                            if let Some(auto_hide) =
+                                settings.layer_settings.auto_hide.and_then(|auto_hide| {
+                                    AutoHide::new(
+                                        auto_hide,
+                                        settings.layer_settings.anchor,
+                                        settings.layer_settings.exclusive_zone,
+                                        declared_input_region.clone(),
+                                    )
+                                })
+                            {
+                                auto_hides.insert(unit.id(), auto_hide);
+                            }
+                            if let (Some(region), Some(wl_region)) = (
+                                settings.layer_settings.input_region.as_ref(),
+                                wl_input_region.as_ref(),
+                            ) {
+                                let scale_factor =
+                                    scale_factors.get(&unit.id()).copied().unwrap_or(1.);
+                                region.set_input_region(unit, wl_region, scale_factor);
+                                input_regions.insert(unit.id(), region.clone());
+                            }
+                        }
+                        if unit.is_session_lock() && unit.get_binding().is_none() {
+                            let id = iced::window::Id::unique();
+                            unit.set_binding(id);
+                            event_sender
+                                .start_send(MultiWindowIcedLayerEvent(
+                                    None,
+                                    IcedLayerEvent::LockSurfaceAdded(id),
+                                ))
+                                .expect("Cannot send");
+                        }
                         event_sender
                             .start_send(MultiWindowIcedLayerEvent(
                                 sended_id,
@@ -274,8 +412,57 @@ where
                             .expect("Cannot send");
                         break 'outside;
                     }
-                    DispatchMessage::MouseEnter { serial, .. } => {
+                    DispatchMessage::MouseEnter {
+                        pointer,
+                        serial,
+                        seat,
+                        ..
+                    } => {
                         pointer_serial = *serial;
+                        pointer_seat = *seat;
+                        if let Some(cursor) = custom_cursor.as_ref() {
+                            ev.append_return_data(ReturnData::RequestSetCustomCursor((
+                                cursor.clone(),
+                                pointer.clone(),
+                                pointer_serial,
+                            )));
+                        }
+                        if let Some(auto_hide) = sended_id.and_then(|id| auto_hides.get_mut(&id)) {
+                            auto_hide.pointer_entered();
+                        }
+                    }
+                    DispatchMessage::MouseLeave { .. } => {
+                        if let Some(auto_hide) = sended_id.and_then(|id| auto_hides.get_mut(&id)) {
+                            auto_hide.pointer_left();
+                        }
+                    }
+                    DispatchMessage::FrameDone { .. } => 'out: {
+                        let Some(id) = sended_id else {
+                            break 'out;
+                        };
+                        if !animations.frame_done(ev, id) {
+                            break 'out;
+                        }
+                        let (Some(auto_hide), Some(unit)) =
+                            (auto_hides.get_mut(&id), ev.get_unit_with_id(id))
+                        else {
+                            break 'out;
+                        };
+                        let scale_factor = scale_factors.get(&id).copied().unwrap_or(1.);
+                        auto_hide.frame(unit, wl_input_region.as_ref(), scale_factor);
+                        if auto_hide.is_sliding() {
+                            animations.request_frame(ev, id);
+                        }
+                    }
+                    DispatchMessage::SessionLockFinished => {
+                        for id in unlock_session(ev) {
+                            event_sender
+                                .start_send(MultiWindowIcedLayerEvent(
+                                    None,
+                                    IcedLayerEvent::WindowRemoved(id),
+                                ))
+                                .ok();
+                        }
                     }
                     _ => {}
                 }
@@ -294,6 +481,28 @@ where
                     .ok();
             }
             LayerEvent::NormalDispatch => {
+                if let Some(loop_handle) = ev.get_loop_handler() {
+                    if let Some(loop_sources) = loop_sources.take() {
+                        loop_sources.insert_into_multi(loop_handle, &message_sender);
+                    }
+                }
+                main_windows.retain(|id| ev.get_unit_with_id(*id).is_some());
+                scale_factors.retain(|id, _| ev.get_unit_with_id(*id).is_some());
+                input_regions.retain(|id, _| ev.get_unit_with_id(*id).is_some());
+                mouse_passthroughs.retain(|id| ev.get_unit_with_id(*id).is_some());
+                auto_hides.retain(|id, auto_hide| {
+                    let Some(unit) = ev.get_unit_with_id(*id) else {
+                        return false;
+                    };
+                    auto_hide.tick(unit);
+                    true
+                });
+                // NOTE: the slides are stepped on the frame callbacks, like the animations
+                for (id, auto_hide) in auto_hides.iter() {
+                    if auto_hide.is_sliding() {
+                        animations.request_frame(ev, *id);
+                    }
+                }
                 event_sender
                     .start_send(MultiWindowIcedLayerEvent(
                         sended_id,
@@ -333,6 +542,7 @@ where
                             let Some(window) = ev.get_window_with_id(id) else {
                                 break 'out;
                             };
+                            animations.stop_size(id);
                             window.set_anchor_with_size(anchor, size);
                         }
                         LayershellCustomActions::LayerChange(layer) => {
@@ -351,6 +561,7 @@ where
                             let Some(window) = ev.get_window_with_id(id) else {
                                 break 'out;
                             };
+                            animations.stop_margin(id);
                             window.set_margin(margin);
                         }
                         LayershellCustomActions::SizeChange((width, height)) => {
@@ -360,8 +571,100 @@ where
                             let Some(window) = ev.get_window_with_id(id) else {
                                 break 'out;
                             };
+                            animations.stop_size(id);
                             window.set_size((width, height));
                         }
+                        LayershellCustomActions::ExclusiveZoneChange(zone_size) => {
+                            let Some(id) = id else {
+                                break 'out;
+                            };
+                            let Some(window) = ev.get_window_with_id(id) else {
+                                break 'out;
+                            };
+                            window.set_exclusive_zone(zone_size);
+                        }
+                        LayershellCustomActions::KeyboardInteractivityChange(
+                            keyboard_interactivity,
+                        ) => {
+                            let Some(id) = id else {
+                                break 'out;
+                            };
+                            let Some(window) = ev.get_window_with_id(id) else {
+                                break 'out;
+                            };
+                            window.set_keyboard_interactivity(keyboard_interactivity);
+                        }
+                        LayershellCustomActions::ExclusiveEdgeChange(edge) => {
+                            let Some(id) = id else {
+                                break 'out;
+                            };
+                            let Some(window) = ev.get_window_with_id(id) else {
+                                break 'out;
+                            };
+                            window.set_exclusive_edge(edge);
+                        }
+                        LayershellCustomActions::AnimatedMarginChange {
+                            margin,
+                            duration,
+                            easing,
+                        } => {
+                            let Some(id) = id else {
+                                break 'out;
+                            };
+                            animations.animate_margin(ev, id, margin, duration, easing);
+                        }
+                        LayershellCustomActions::AnimatedSizeChange {
+                            size,
+                            duration,
+                            easing,
+                        } => {
+                            let Some(id) = id else {
+                                break 'out;
+                            };
+                            animations.animate_size(ev, id, size, duration, easing);
+                        }
+                        LayershellCustomActions::AnimatedOpacityChange {
+                            opacity,
+                            duration,
+                            easing,
+                        } => {
+                            let Some(id) = id else {
+                                break 'out;
+                            };
+                            animations.animate_opacity(ev, id, opacity, duration, easing);
+                        }
+                        LayershellCustomActions::OpacityChange(opacity) => {
+                            let Some(id) = id else {
+                                break 'out;
+                            };
+                            animations.opacity_changed(id, opacity);
+                            ev.append_return_data(ReturnData::RequestSetOpacity((
+                                id,
+                                layershellev::alpha_multiplier(opacity),
+                            )));
+                        }
+                        LayershellCustomActions::InputRegionChange(region) => {
+                            let Some(id) = id else {
+                                break 'out;
+                            };
+                            let Some(window) = ev.get_window_with_id(id) else {
+                                break 'out;
+                            };
+                            let Some(wl_region) = &wl_input_region else {
+                                break 'out;
+                            };
+                            input_regions.insert(id, region.clone());
+                            if mouse_passthroughs.contains(&id) {
+                                break 'out;
+                            }
+                            if let Some(auto_hide) = auto_hides.get_mut(&id) {
+                                if !auto_hide.input_region_changed(Some(region.clone())) {
+                                    break 'out;
+                                }
+                            }
+                            let scale_factor = scale_factors.get(&id).copied().unwrap_or(1.);
+                            region.set_input_region(window, wl_region, scale_factor);
+                        }
                         LayershellCustomActions::SetInputRegion(set_region) => {
                             let set_region = set_region.0;
                             let Some(id) = id else {
@@ -384,19 +687,47 @@ where
                             window
                                 .get_wlsurface()
                                 .set_input_region(wl_input_region.as_ref());
+                            input_regions.remove(&id);
+                        }
+                        LayershellCustomActions::SetCustomCursor(cursor) => {
+                            custom_cursor = cursor;
+                            let Some(pointer) = pointer_seat
+                                .and_then(|seat| ev.get_pointer_with_seat(seat))
+                                .cloned()
+                            else {
+                                break 'out;
+                            };
+                            let data = match custom_cursor.as_ref() {
+                                Some(cursor) => ReturnData::RequestSetCustomCursor((
+                                    cursor.clone(),
+                                    pointer,
+                                    pointer_serial,
+                                )),
+                                None => ReturnData::RequestSetCursorShape((
+                                    conversion::mouse_interaction(last_interaction),
+                                    pointer,
+                                    pointer_serial,
+                                )),
+                            };
+                            ev.append_return_data(data);
                         }
                         LayershellCustomActions::VirtualKeyboardPressed { time, key } => {
                             use layershellev::reexport::wayland_client::KeyState;
-                            let ky = ev.get_virtual_keyboard().unwrap();
+                            let Some(ky) = ev.get_virtual_keyboard() else {
+                                tracing::error!(
+                                    "no virtual keyboard, the key {key} is not pressed"
+                                );
+                                break 'out;
+                            };
                             ky.key(time, key, KeyState::Pressed.into());
 
                             let eh = ev.get_loop_handler().unwrap();
                             eh.insert_source(
                                 Timer::from_duration(Duration::from_micros(100)),
                                 move |_, _, state| {
-                                    let ky = state.get_virtual_keyboard().unwrap();
-
-                                    ky.key(time, key, KeyState::Released.into());
+                                    if let Some(ky) = state.get_virtual_keyboard() {
+                                        ky.key(time, key, KeyState::Released.into());
+                                    }
                                     TimeoutAction::Drop
                                 },
                             )
@@ -454,6 +785,19 @@ where
                         LayershellCustomActions::ForgetLastOutput => {
                             ev.forget_last_output();
                         }
+                        LayershellCustomActions::LockSession => {
+                            ev.append_return_data(ReturnData::RequestSessionLock);
+                        }
+                        LayershellCustomActions::UnlockSession => {
+                            for id in unlock_session(ev) {
+                                event_sender
+                                    .start_send(MultiWindowIcedLayerEvent(
+                                        None,
+                                        IcedLayerEvent::WindowRemoved(id),
+                                    ))
+                                    .ok();
+                            }
+                        }
                     }
                 }
                 LayerShellAction::NewMenu((menusettings, info)) => 'out: {
@@ -466,7 +810,14 @@ where
                     ev.append_return_data(ReturnData::NewPopUp((popup_settings, id, Some(info))))
                 }
                 LayerShellAction::Mouse(mouse) => {
-                    let Some(pointer) = ev.get_pointer() else {
+                    last_interaction = mouse;
+                    // NOTE: the custom cursor stays until it is unset
+                    if custom_cursor.is_some() {
+                        continue;
+                    }
+                    let Some(pointer) =
+                        pointer_seat.and_then(|seat| ev.get_pointer_with_seat(seat))
+                    else {
                         return ReturnData::None;
                     };
 
@@ -482,6 +833,74 @@ where
                 LayerShellAction::RedrawWindow(index) => {
                     ev.append_return_data(ReturnData::RedrawIndexRequest(index));
                 }
+                LayerShellAction::OpaqueRegion(id, opaque) => 'out: {
+                    let Some(window) = id.and_then(|id| ev.get_window_with_id(id)) else {
+                        break 'out;
+                    };
+                    if !opaque {
+                        window.set_opaque_region(None);
+                        break 'out;
+                    }
+                    let Some(wl_region) = &wl_opaque_region else {
+                        break 'out;
+                    };
+                    let scale_factor = scale_factors.get(&window.id()).copied().unwrap_or(1.);
+                    Region::full().set_opaque_region(window, wl_region, scale_factor);
+                }
+                LayerShellAction::MousePassthrough(id, passthrough) => 'out: {
+                    let Some(window) = id.and_then(|id| ev.get_window_with_id(id)) else {
+                        break 'out;
+                    };
+                    let id = window.id();
+                    let changed = if passthrough {
+                        mouse_passthroughs.insert(id)
+                    } else {
+                        mouse_passthroughs.remove(&id)
+                    };
+                    if !changed {
+                        break 'out;
+                    }
+                    // NOTE: an empty input region lets every pointer event pass through
+                    let region = if passthrough {
+                        Some(Region::new())
+                    } else {
+                        input_regions.get(&id).cloned()
+                    };
+                    if let Some(auto_hide) = auto_hides.get_mut(&id) {
+                        if !auto_hide.input_region_changed(region.clone()) {
+                            break 'out;
+                        }
+                    }
+                    let scale_factor = scale_factors.get(&id).copied().unwrap_or(1.);
+                    region::set_input_region(
+                        region.as_ref(),
+                        window,
+                        wl_input_region.as_ref(),
+                        scale_factor,
+                    );
+                }
+                LayerShellAction::ScaleFactor(id, scale_factor) => 'out: {
+                    let Some(window) = id.and_then(|id| ev.get_window_with_id(id)) else {
+                        break 'out;
+                    };
+                    let id = window.id();
+                    if scale_factors.insert(id, scale_factor) == Some(scale_factor)
+                        || mouse_passthroughs.contains(&id)
+                    {
+                        break 'out;
+                    }
+                    let (Some(region), Some(wl_region)) =
+                        (input_regions.get(&id), wl_input_region.as_ref())
+                    else {
+                        break 'out;
+                    };
+                    if let Some(auto_hide) = auto_hides.get_mut(&id) {
+                        if !auto_hide.input_region_changed(Some(region.clone())) {
+                            break 'out;
+                        }
+                    }
+                    region.set_input_region(window, wl_region, scale_factor);
+                }
                 _ => {}
             }
         }
@@ -490,6 +909,19 @@ where
     Ok(())
 }
 
+/// Unlock the session, return the ids of the removed lock surfaces
+fn unlock_session(ev: &mut WindowState<iced::window::Id>) -> Vec<iced::window::Id> {
+    let ids = ev
+        .get_unit_iter()
+        .filter(|unit| unit.is_session_lock())
+        .filter_map(|unit| unit.get_binding().copied())
+        .collect();
+    if ev.unlock_session().is_none() {
+        return Vec::new();
+    }
+    ids
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn run_instance<A, E, C>(
     mut application: A,
@@ -501,6 +933,7 @@ async fn run_instance<A, E, C>(
     window: Arc<WindowWrapper>,
     fonts: Vec<Cow<'static, [u8]>>,
     is_background_mode: bool,
+    auto_size: AutoSizeState,
 ) where
     A: Application + 'static,
     E: Executor + 'static,
@@ -519,8 +952,12 @@ async fn run_instance<A, E, C>(
 
     let mut window_manager = WindowManager::new();
 
-    let mut clipboard = LayerShellClipboard::connect(&window);
+    let mut clipboard = ShellClipboard::connect(&window);
     let mut ui_caches: HashMap<window::Id, user_interface::Cache> = HashMap::new();
+    // NOTE: only the main layershells, which are created from the settings, follow the auto size
+    let mut auto_sizes: HashMap<window::Id, AutoSizeState> = HashMap::new();
+    let mut opaque_windows: HashMap<window::Id, bool> = HashMap::new();
+    let mut scale_factors: HashMap<window::Id, f64> = HashMap::new();
 
     let mut user_interfaces = ManuallyDrop::new(build_user_interfaces(
         &application,
@@ -551,14 +988,27 @@ async fn run_instance<A, E, C>(
                 let mut is_new_window = false;
                 let (id, window) = if window_manager.get_mut_alias(wrapper.id()).is_none() {
                     is_new_window = true;
+                    let is_main_window = info.is_none();
                     let id = info.unwrap_or(window::Id::unique());
+                    if is_main_window && auto_size.is_enabled() {
+                        auto_sizes.insert(id, auto_size.clone());
+                    }
 
-                    let window = window_manager.insert(
-                        id,
+                    let theme = application.theme();
+                    let appearance = application.style(&theme);
+                    let state = State::new(
+                        application.scale_factor(id),
+                        theme,
+                        appearance,
                         (width, height),
                         fractal_scale,
+                        wrapper.viewport.clone(),
+                    );
+                    let window = window_manager.insert(
+                        id,
+                        wrapper.id(),
+                        state,
                         Arc::new(wrapper),
-                        &application,
                         &mut compositor,
                     );
                     let logical_size = window.state.logical_size();
@@ -588,6 +1038,9 @@ async fn run_instance<A, E, C>(
                     let (id, window) = window_manager.get_mut_alias(wrapper.id()).unwrap();
                     let ui = user_interfaces.remove(&id).expect("Get User interface");
                     window.state.update_view_port(width, height, fractal_scale);
+                    if let Some(auto_size) = auto_sizes.get_mut(&id) {
+                        auto_size.mark_dirty();
+                    }
 
                     let _ = user_interfaces.insert(
                         id,
@@ -596,6 +1049,17 @@ async fn run_instance<A, E, C>(
                     (id, window)
                 };
 
+                // NOTE: the compositor does not need to draw what is below a fully opaque background
+                let opaque = region::is_opaque(window.state.background_color());
+                if opaque_windows.insert(id, opaque) != Some(opaque) {
+                    custom_actions.push(LayerShellAction::OpaqueRegion(Some(window.id), opaque));
+                }
+                let scale_factor = application.scale_factor(id);
+                if scale_factors.insert(id, scale_factor) != Some(scale_factor) {
+                    custom_actions
+                        .push(LayerShellAction::ScaleFactor(Some(window.id), scale_factor));
+                }
+
                 let ui = user_interfaces.get_mut(&id).expect("Get User interface");
 
                 let redraw_event =
@@ -605,7 +1069,7 @@ async fn run_instance<A, E, C>(
 
                 events.push((Some(id), redraw_event.clone()));
                 ui.update(
-                    &[redraw_event.clone()],
+                    std::slice::from_ref(&redraw_event),
                     cursor,
                     &mut window.renderer,
                     &mut clipboard,
@@ -677,8 +1141,8 @@ async fn run_instance<A, E, C>(
                     }
                 }
             }
-            MultiWindowIcedLayerEvent(None, IcedLayerEvent::Window(event)) => {
-                let Some((_id, window)) = window_manager.first_window() else {
+            MultiWindowIcedLayerEvent(None, IcedLayerEvent::Window(event, seat)) => {
+                let Some((id, window)) = window_manager.first_window() else {
                     continue;
                 };
                 // NOTE: just follow the other events
@@ -687,10 +1151,15 @@ async fn run_instance<A, E, C>(
                     window.state.scale_factor(),
                     window.state.modifiers(),
                 ) {
+                    if let Some(message) =
+                        seat.and_then(|seat| application.seat_input(*id, seat, &event))
+                    {
+                        messages.push(message);
+                    }
                     events.push((None, event));
                 }
             }
-            MultiWindowIcedLayerEvent(Some(id), IcedLayerEvent::Window(event)) => {
+            MultiWindowIcedLayerEvent(Some(id), IcedLayerEvent::Window(event, seat)) => {
                 let Some((id, window)) = window_manager.get_mut_alias(id) else {
                     continue;
                 };
@@ -700,6 +1169,11 @@ async fn run_instance<A, E, C>(
                     window.state.scale_factor(),
                     window.state.modifiers(),
                 ) {
+                    if let Some(message) =
+                        seat.and_then(|seat| application.seat_input(id, seat, &event))
+                    {
+                        messages.push(message);
+                    }
                     events.push((Some(id), event));
                 }
             }
@@ -779,7 +1253,7 @@ async fn run_instance<A, E, C>(
 
                     debug.event_processing_finished();
 
-                    for (event, status) in window_events.drain(..).zip(statuses.into_iter()) {
+                    for (event, status) in window_events.drain(..).zip(statuses) {
                         runtime.broadcast(iced_futures::subscription::Event::Interaction {
                             window: id,
                             event,
@@ -807,9 +1281,17 @@ async fn run_instance<A, E, C>(
 
                     // Update application
                     update(&mut application, &mut runtime, &mut debug, &mut messages);
-
-                    for (_id, window) in window_manager.iter_mut() {
-                        window.state.synchronize(&application);
+                    auto_sizes.values_mut().for_each(AutoSizeState::mark_dirty);
+
+                    for (id, window) in window_manager.iter_mut() {
+                        let theme = application.theme();
+                        let appearance = application.style(&theme);
+                        let scale_factor = application.scale_factor(id);
+                        window.state.synchronize(scale_factor, theme, appearance);
+                        if scale_factors.insert(id, scale_factor) != Some(scale_factor) {
+                            custom_actions
+                                .push(LayerShellAction::ScaleFactor(Some(window.id), scale_factor));
+                        }
                     }
 
                     if !is_background_mode {
@@ -831,6 +1313,51 @@ async fn run_instance<A, E, C>(
                     custom_actions.append(&mut window_refresh_events);
                 }
             }
+            MultiWindowIcedLayerEvent(_, IcedLayerEvent::LockSurfaceAdded(id)) => {
+                let cached_interfaces: HashMap<window::Id, user_interface::Cache> =
+                    ManuallyDrop::into_inner(user_interfaces)
+                        .drain()
+                        .map(|(id, ui)| (id, ui.into_cache()))
+                        .collect();
+
+                application.lock_surface_added(id);
+                user_interfaces = ManuallyDrop::new(build_user_interfaces(
+                    &application,
+                    &mut debug,
+                    &mut window_manager,
+                    cached_interfaces,
+                ));
+            }
+            MultiWindowIcedLayerEvent(_, IcedLayerEvent::SessionLocked) => {
+                let cached_interfaces: HashMap<window::Id, user_interface::Cache> =
+                    ManuallyDrop::into_inner(user_interfaces)
+                        .drain()
+                        .map(|(id, ui)| (id, ui.into_cache()))
+                        .collect();
+
+                application.session_locked();
+                user_interfaces = ManuallyDrop::new(build_user_interfaces(
+                    &application,
+                    &mut debug,
+                    &mut window_manager,
+                    cached_interfaces,
+                ));
+            }
+            MultiWindowIcedLayerEvent(_, IcedLayerEvent::SessionLockFinished) => {
+                let cached_interfaces: HashMap<window::Id, user_interface::Cache> =
+                    ManuallyDrop::into_inner(user_interfaces)
+                        .drain()
+                        .map(|(id, ui)| (id, ui.into_cache()))
+                        .collect();
+
+                application.session_lock_finished();
+                user_interfaces = ManuallyDrop::new(build_user_interfaces(
+                    &application,
+                    &mut debug,
+                    &mut window_manager,
+                    cached_interfaces,
+                ));
+            }
             MultiWindowIcedLayerEvent(_, IcedLayerEvent::WindowRemoved(id)) => {
                 let mut cached_interfaces: HashMap<window::Id, user_interface::Cache> =
                     ManuallyDrop::into_inner(user_interfaces)
@@ -841,6 +1368,9 @@ async fn run_instance<A, E, C>(
                 application.remove_id(id);
                 window_manager.remove(id);
                 cached_interfaces.remove(&id);
+                auto_sizes.remove(&id);
+                opaque_windows.remove(&id);
+                scale_factors.remove(&id);
                 user_interfaces = ManuallyDrop::new(build_user_interfaces(
                     &application,
                     &mut debug,
@@ -880,6 +1410,41 @@ async fn run_instance<A, E, C>(
             }
             _ => {}
         }
+        if !auto_sizes.is_empty() {
+            for LayershellCustomActionsWithIdInner(layer_id, _, action) in
+                custom_actions.iter().filter_map(|action| match action {
+                    LayerShellAction::CustomActionsWithId(action) => Some(action),
+                    _ => None,
+                })
+            {
+                let Some((id, _)) =
+                    layer_id.and_then(|layer_id| window_manager.get_alias(layer_id))
+                else {
+                    continue;
+                };
+                if let Some(auto_size) = auto_sizes.get_mut(&id) {
+                    auto_size.update_anchor(action);
+                }
+            }
+            for (id, auto_size) in auto_sizes.iter_mut() {
+                if !auto_size.is_dirty() {
+                    continue;
+                }
+                let Some(window) = window_manager.get(*id) else {
+                    continue;
+                };
+                let view = application.view(*id);
+                let actions =
+                    auto_size.measure(&view, &window.renderer, window.state.logical_size());
+                custom_actions.extend(actions.into_iter().map(|action| {
+                    LayerShellAction::CustomActionsWithId(LayershellCustomActionsWithIdInner(
+                        window_manager.get_shell_id(*id),
+                        None,
+                        action,
+                    ))
+                }));
+            }
+        }
         let mut copyactions = vec![];
         std::mem::swap(&mut copyactions, &mut custom_actions);
         control_sender.start_send(copyactions).ok();
@@ -887,60 +1452,6 @@ async fn run_instance<A, E, C>(
     let _ = ManuallyDrop::into_inner(user_interfaces);
 }
 
-#[allow(clippy::type_complexity)]
-pub fn build_user_interfaces<'a, A: Application, C>(
-    application: &'a A,
-    debug: &mut Debug,
-    window_manager: &mut WindowManager<A, C>,
-    mut cached_user_interfaces: HashMap<iced::window::Id, user_interface::Cache>,
-) -> HashMap<iced::window::Id, UserInterface<'a, A::Message, A::Theme, A::Renderer>>
-where
-    C: Compositor<Renderer = A::Renderer>,
-    A::Theme: DefaultStyle,
-{
-    cached_user_interfaces
-        .drain()
-        .filter_map(|(id, cache)| {
-            let window = window_manager.get_mut(id)?;
-
-            Some((
-                id,
-                build_user_interface(
-                    application,
-                    cache,
-                    &mut window.renderer,
-                    window.state.logical_size(),
-                    debug,
-                    id,
-                ),
-            ))
-        })
-        .collect()
-}
-
-/// Builds a [`UserInterface`] for the provided [`Application`], logging
-/// [`struct@Debug`] information accordingly.
-fn build_user_interface<'a, A: Application>(
-    application: &'a A,
-    cache: user_interface::Cache,
-    renderer: &mut A::Renderer,
-    size: Size,
-    debug: &mut Debug,
-    id: iced::window::Id,
-) -> UserInterface<'a, A::Message, A::Theme, A::Renderer>
-where
-    A::Theme: DefaultStyle,
-{
-    debug.view_started();
-    let view = application.view(id);
-    debug.view_finished();
-
-    debug.layout_started();
-    let user_interface = UserInterface::build(view, size, cache, renderer);
-    debug.layout_finished();
-    user_interface
-}
-
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn update<A: Application, E: Executor>(
     application: &mut A,
@@ -975,11 +1486,11 @@ pub(crate) fn run_action<A, C>(
     compositor: &mut C,
     event: Action<A::Message>,
     messages: &mut Vec<A::Message>,
-    clipboard: &mut LayerShellClipboard,
+    clipboard: &mut ShellClipboard,
     custom_actions: &mut Vec<LayerShellAction>,
     should_exit: &mut bool,
     debug: &mut Debug,
-    window_manager: &mut WindowManager<A, C>,
+    window_manager: &mut WindowManager<layershellev::id::Id, A::Theme, C>,
     ui_caches: &mut HashMap<iced::window::Id, user_interface::Cache>,
 ) where
     A: Application,
@@ -987,18 +1498,29 @@ pub(crate) fn run_action<A, C>(
     A::Theme: DefaultStyle,
     A::Message: 'static + TryInto<LayershellCustomActionsWithId, Error = A::Message>,
 {
-    use iced_core::widget::operation;
-    use iced_runtime::clipboard;
-    use iced_runtime::window;
-    use iced_runtime::window::Action as WinowAction;
-    use iced_runtime::Action;
-    match event {
-        Action::Output(stream) => match stream.try_into() {
+    let action = match multi_window::run_action(
+        application,
+        compositor,
+        event,
+        clipboard,
+        debug,
+        window_manager,
+        ui_caches,
+    ) {
+        Ok(Some(action)) => action,
+        Ok(None) => return,
+        Err(UnsupportedWindowAction(name)) => {
+            tracing::error!("{}", Error::UnsupportedWindowAction(name));
+            return;
+        }
+    };
+    match action {
+        ShellAction::Output(stream) => match stream.try_into() {
             Ok(action) => {
                 let action: LayershellCustomActionsWithId = action;
 
                 let option_id = if let LayershellCustomActions::RemoveWindow(id) = action.1 {
-                    let option_id = window_manager.get_layer_id(id);
+                    let option_id = window_manager.get_shell_id(id);
                     if option_id.is_none() {
                         return;
                     }
@@ -1008,7 +1530,7 @@ pub(crate) fn run_action<A, C>(
                 };
                 custom_actions.push(LayerShellAction::CustomActionsWithId(
                     LayershellCustomActionsWithIdInner(
-                        action.0.and_then(|id| window_manager.get_layer_id(id)),
+                        action.0.and_then(|id| window_manager.get_shell_id(id)),
                         option_id,
                         action.1,
                     ),
@@ -1018,89 +1540,41 @@ pub(crate) fn run_action<A, C>(
                 messages.push(stream);
             }
         },
-
-        Action::Clipboard(action) => match action {
-            clipboard::Action::Read { target, channel } => {
-                let _ = channel.send(clipboard.read(target));
-            }
-            clipboard::Action::Write { target, contents } => {
-                clipboard.write(target, contents);
-            }
-        },
-        Action::Widget(action) => {
-            let mut current_operation = Some(action);
-
-            let mut uis = build_user_interfaces(
-                application,
-                debug,
-                window_manager,
-                std::mem::take(ui_caches),
-            );
-
-            'operate: while let Some(mut operation) = current_operation.take() {
-                for (id, ui) in uis.iter_mut() {
-                    if let Some(window) = window_manager.get_mut(*id) {
-                        ui.operate(&window.renderer, operation.as_mut());
-
-                        match operation.finish() {
-                            operation::Outcome::None => {}
-                            operation::Outcome::Some(_message) => {
-                                //proxy.send_event(message).ok();
-
-                                // operation completed, don't need to try to operate on rest of UIs
-                                break 'operate;
-                            }
-                            operation::Outcome::Chain(next) => {
-                                current_operation = Some(next);
-                            }
-                        }
-                    }
-                }
-            }
-
-            *ui_caches = uis.drain().map(|(id, ui)| (id, ui.into_cache())).collect();
+        ShellAction::Exit => {
+            *should_exit = true;
         }
-        Action::Window(action) => match action {
-            WinowAction::Close(id) => {
-                if let Some(layerid) = window_manager.get_layer_id(id) {
-                    custom_actions.push(LayerShellAction::CustomActionsWithId(
-                        LayershellCustomActionsWithIdInner(
-                            Some(layerid),
-                            Some(layerid),
-                            LayershellCustomActions::RemoveWindow(id),
-                        ),
-                    ))
-                }
+        ShellAction::Close(id) => {
+            if let Some(layerid) = window_manager.get_shell_id(id) {
+                custom_actions.push(LayerShellAction::CustomActionsWithId(
+                    LayershellCustomActionsWithIdInner(
+                        Some(layerid),
+                        Some(layerid),
+                        LayershellCustomActions::RemoveWindow(id),
+                    ),
+                ))
             }
-            WinowAction::Screenshot(id, channel) => 'out: {
-                let Some(window) = window_manager.get_mut(id) else {
-                    break 'out;
-                };
-                let bytes = compositor.screenshot(
-                    &mut window.renderer,
-                    &mut window.surface,
-                    window.state.viewport(),
-                    window.state.background_color(),
-                    &debug.overlay(),
-                );
-
-                let _ = channel.send(window::Screenshot::new(
-                    bytes,
-                    window.state.physical_size(),
-                    window.state.viewport().scale_factor(),
-                ));
+        }
+        ShellAction::Resize(id, size) => {
+            if let Some(layerid) = window_manager.get_shell_id(id) {
+                custom_actions.push(LayerShellAction::CustomActionsWithId(
+                    LayershellCustomActionsWithIdInner(
+                        Some(layerid),
+                        None,
+                        LayershellCustomActions::SizeChange((
+                            size.width.ceil() as u32,
+                            size.height.ceil() as u32,
+                        )),
+                    ),
+                ))
             }
-            _ => {}
-        },
-        Action::Exit => {
-            *should_exit = true;
         }
-        Action::LoadFont { bytes, channel } => {
-            // TODO: Error handling (?)
-            compositor.load_font(bytes.clone());
-
-            let _ = channel.send(Ok(()));
+        ShellAction::MousePassthrough(id, passthrough) => {
+            if let Some(layerid) = window_manager.get_shell_id(id) {
+                custom_actions.push(LayerShellAction::MousePassthrough(
+                    Some(layerid),
+                    passthrough,
+                ))
+            }
         }
-        _ => {}
     }
 }
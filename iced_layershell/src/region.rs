@@ -0,0 +1,192 @@
+use iced_core::{Color, Point, Rectangle, Size};
+use layershellev::reexport::wayland_client::WlRegion;
+use layershellev::WindowStateUnit;
+
+/// A region of a layershell, made of the union of rectangles in logical coordinates.
+///
+/// The logical coordinates are the ones of the view, so the region stays the same when the
+/// scale of the output or the scale factor of the application changes. An empty region lets all
+/// the pointer events go through.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Region {
+    rectangles: Vec<Rectangle>,
+}
+
+impl Region {
+    /// the empty region
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the region covering the whole layershell, whatever its size is
+    pub fn full() -> Self {
+        Self::new().with_rectangle(Rectangle::new(
+            Point::ORIGIN,
+            Size::new(f32::INFINITY, f32::INFINITY),
+        ))
+    }
+
+    /// add a rectangle to the region
+    pub fn with_rectangle(mut self, rectangle: Rectangle) -> Self {
+        self.add(rectangle);
+        self
+    }
+
+    /// add a rectangle to the region
+    pub fn add(&mut self, rectangle: Rectangle) {
+        self.rectangles.push(rectangle);
+    }
+
+    pub fn rectangles(&self) -> &[Rectangle] {
+        &self.rectangles
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rectangles.is_empty()
+    }
+
+    /// fill the wl_region with the rectangles, in the surface coordinates of a layershell whose
+    /// application has the scale factor
+    fn fill(&self, wl_region: &WlRegion, scale_factor: f64) {
+        wl_region.subtract(0, 0, i32::MAX, i32::MAX);
+        for (x, y, width, height) in self
+            .rectangles
+            .iter()
+            .filter_map(|rectangle| surface_rectangle(rectangle, scale_factor))
+        {
+            wl_region.add(x, y, width, height);
+        }
+    }
+
+    /// set the region as the input region of the unit
+    pub(crate) fn set_input_region<T>(
+        &self,
+        unit: &WindowStateUnit<T>,
+        wl_region: &WlRegion,
+        scale_factor: f64,
+    ) {
+        self.fill(wl_region, scale_factor);
+        unit.get_wlsurface().set_input_region(Some(wl_region));
+    }
+
+    /// set the region as the opaque region of the unit
+    pub(crate) fn set_opaque_region<T>(
+        &self,
+        unit: &WindowStateUnit<T>,
+        wl_region: &WlRegion,
+        scale_factor: f64,
+    ) {
+        self.fill(wl_region, scale_factor);
+        unit.set_opaque_region(Some(wl_region));
+    }
+}
+
+/// if the compositor can skip what is below a layershell with this background, only a fully
+/// opaque one hides it
+pub(crate) fn is_opaque(background_color: Color) -> bool {
+    background_color.a >= 1.
+}
+
+/// set the input region of the unit, None is the whole unit
+pub(crate) fn set_input_region<T>(
+    region: Option<&Region>,
+    unit: &WindowStateUnit<T>,
+    wl_region: Option<&WlRegion>,
+    scale_factor: f64,
+) {
+    match (region, wl_region) {
+        (Some(region), Some(wl_region)) => region.set_input_region(unit, wl_region, scale_factor),
+        _ => unit.get_wlsurface().set_input_region(None),
+    }
+}
+
+/// the rectangle in surface coordinates, as `(x, y, width, height)`: it is scaled by the scale
+/// factor of the application, rounded outward and clipped to the surface. It is None when
+/// nothing is left.
+fn surface_rectangle(rectangle: &Rectangle, scale_factor: f64) -> Option<(i32, i32, i32, i32)> {
+    let scale_factor = scale_factor as f32;
+    let left = rectangle.x * scale_factor;
+    let top = rectangle.y * scale_factor;
+    let x = left.floor().max(0.);
+    let y = top.floor().max(0.);
+    let width = (left + rectangle.width * scale_factor).ceil() - x;
+    let height = (top + rectangle.height * scale_factor).ceil() - y;
+    // NOTE: it also skips the NaN sizes
+    if !(width > 0. && height > 0.) {
+        return None;
+    }
+    // NOTE: the casts saturate, so the infinite rectangles cover the whole surface
+    Some((x as i32, y as i32, width as i32, height as i32))
+}
+
+impl From<Rectangle> for Region {
+    fn from(rectangle: Rectangle) -> Self {
+        Self::new().with_rectangle(rectangle)
+    }
+}
+
+impl FromIterator<Rectangle> for Region {
+    fn from_iter<I: IntoIterator<Item = Rectangle>>(iter: I) -> Self {
+        Self {
+            rectangles: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rectangle(x: f32, y: f32, width: f32, height: f32) -> Rectangle {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn test_surface_rectangle_scaled() {
+        let view = rectangle(10., 20., 30., 40.);
+        assert_eq!(surface_rectangle(&view, 1.), Some((10, 20, 30, 40)));
+        assert_eq!(surface_rectangle(&view, 2.), Some((20, 40, 60, 80)));
+        assert_eq!(surface_rectangle(&view, 0.5), Some((5, 10, 15, 20)));
+    }
+
+    #[test]
+    fn test_surface_rectangle_rounded_outward() {
+        assert_eq!(
+            surface_rectangle(&rectangle(0.5, 1.5, 1., 1.), 1.),
+            Some((0, 1, 2, 2))
+        );
+        assert_eq!(
+            surface_rectangle(&rectangle(1., 1., 1., 1.), 1.5),
+            Some((1, 1, 2, 2))
+        );
+    }
+
+    #[test]
+    fn test_surface_rectangle_clipped() {
+        assert_eq!(
+            surface_rectangle(&rectangle(-5., -10., 10., 20.), 1.),
+            Some((0, 0, 5, 10))
+        );
+        assert_eq!(surface_rectangle(&rectangle(-5., 0., 5., 10.), 1.), None);
+        assert_eq!(surface_rectangle(&rectangle(5., 5., 0., 10.), 2.), None);
+    }
+
+    #[test]
+    fn test_is_opaque() {
+        assert!(is_opaque(Color::BLACK));
+        assert!(is_opaque(Color::from_rgb(0.2, 0.4, 0.6)));
+        assert!(!is_opaque(Color::TRANSPARENT));
+        assert!(!is_opaque(Color::from_rgba(0.2, 0.4, 0.6, 0.99)));
+    }
+
+    #[test]
+    fn test_surface_rectangle_full() {
+        let full = Region::full();
+        for scale_factor in [1., 2., 0.5] {
+            assert_eq!(
+                surface_rectangle(&full.rectangles()[0], scale_factor),
+                Some((0, 0, i32::MAX, i32::MAX))
+            );
+        }
+    }
+}
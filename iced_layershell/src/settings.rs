@@ -1,8 +1,9 @@
-use std::{borrow::Cow, fs::File};
+use std::{borrow::Cow, fs::File, time::Duration};
 
 use iced::{Font, Pixels};
 
 use crate::reexport::{Anchor, KeyboardInteractivity, Layer};
+use crate::region::Region;
 
 pub use layershellev::StartMode;
 
@@ -54,6 +55,16 @@ pub struct Settings<Flags> {
     pub antialiasing: bool,
 
     pub virtual_keyboard_support: Option<VirtualKeyboardSettings>,
+
+    /// The cursor theme, used when the compositor does not support cursor-shape.
+    ///
+    /// By default, `XCURSOR_THEME` is used.
+    pub cursor_theme: Option<String>,
+
+    /// The logical size of the cursor, used when the compositor does not support cursor-shape.
+    ///
+    /// By default, `XCURSOR_SIZE` is used, or `24` if it is not set.
+    pub cursor_size: Option<u32>,
 }
 
 impl<Flags> Default for Settings<Flags>
@@ -70,6 +81,55 @@ where
             default_text_size: Pixels(16.0),
             antialiasing: false,
             virtual_keyboard_support: None,
+            cursor_theme: None,
+            cursor_size: None,
+        }
+    }
+}
+
+/// Resize the layershell to the size of its content.
+///
+/// The view is laid out with `Length::Shrink`, and the layershell is resized whenever the size of
+/// the content changes. The axes stretched between two anchors keep the size given by the
+/// compositor, and the content should not fill the other axes. Set
+/// [`LayerShellSettings::auto_exclusive_zone`] to reserve the content as the exclusive zone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutoSize {
+    /// use the size in [`LayerShellSettings`]
+    #[default]
+    Disabled,
+    /// follow the size of the content
+    Content,
+}
+
+impl AutoSize {
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, Self::Disabled)
+    }
+}
+
+/// Hide the layershell to a thin strip on its anchored edge, and reveal it when the pointer
+/// touches the strip.
+///
+/// The layershell should be anchored to one edge, or to one edge and the two edges perpendicular
+/// to it. While it is hidden, the input region is reset to the whole strip, and a positive
+/// exclusive zone is reduced to the strip. Both are restored when it is shown again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoHideSettings {
+    /// the size of the strip left on the edge when the layershell is hidden
+    pub trigger_size: u32,
+    /// the delay before hiding, after the pointer leaves the layershell
+    pub hide_delay: Duration,
+    /// the duration of the slide when hiding or revealing
+    pub animation_duration: Duration,
+}
+
+impl Default for AutoHideSettings {
+    fn default() -> Self {
+        Self {
+            trigger_size: 2,
+            hide_delay: Duration::from_millis(500),
+            animation_duration: Duration::from_millis(200),
         }
     }
 }
@@ -84,6 +144,30 @@ pub struct LayerShellSettings {
     pub keyboard_interactivity: KeyboardInteractivity,
     pub start_mode: StartMode,
     pub events_transparent: bool,
+    /// The input region set when the layershell is shown, see [`Region`]. None keeps the whole
+    /// layershell as the input region.
+    pub input_region: Option<Region>,
+    /// Compute the exclusive zone from the size given by the compositor and the anchored edge,
+    /// and recompute it when they change, so `exclusive_zone` does not need to be kept in sync
+    /// by hand.
+    pub auto_exclusive_zone: bool,
+    /// Resize the layershell to its content, see [`AutoSize`]. In the multi window mode, it
+    /// applies to the layershells created from these settings.
+    pub auto_size: AutoSize,
+    /// Hide the layershell when the pointer is away, see [`AutoHideSettings`]. In the multi
+    /// window mode, it applies to the layershells created from these settings.
+    pub auto_hide: Option<AutoHideSettings>,
+}
+
+impl LayerShellSettings {
+    /// the input region set when the layershell is created, None is the whole layershell
+    pub(crate) fn declared_input_region(&self) -> Option<Region> {
+        if self.events_transparent {
+            Some(Region::new())
+        } else {
+            self.input_region.clone()
+        }
+    }
 }
 
 impl Default for LayerShellSettings {
@@ -97,6 +181,10 @@ impl Default for LayerShellSettings {
             keyboard_interactivity: KeyboardInteractivity::OnDemand,
             events_transparent: false,
             start_mode: StartMode::default(),
+            input_region: None,
+            auto_exclusive_zone: false,
+            auto_size: AutoSize::Disabled,
+            auto_hide: None,
         }
     }
 }
@@ -133,6 +221,10 @@ mod tests {
             settings.layer_settings.start_mode,
             StartMode::Active
         ));
+        assert_eq!(settings.layer_settings.input_region, None);
+        assert!(!settings.layer_settings.auto_exclusive_zone);
+        assert_eq!(settings.layer_settings.auto_size, AutoSize::Disabled);
+        assert_eq!(settings.layer_settings.auto_hide, None);
     }
 
     #[test]
@@ -162,6 +254,13 @@ mod tests {
             keyboard_interactivity: KeyboardInteractivity::None,
             start_mode: StartMode::TargetScreen("HDMI-1".to_string()),
             events_transparent: false,
+            input_region: Some(Region::from(iced::Rectangle::new(
+                iced::Point::ORIGIN,
+                iced::Size::new(1920., 70.),
+            ))),
+            auto_exclusive_zone: true,
+            auto_size: AutoSize::Content,
+            auto_hide: Some(AutoHideSettings::default()),
         };
 
         assert_eq!(layer_settings.anchor, Anchor::Top | Anchor::Left);
@@ -177,5 +276,18 @@ mod tests {
             layer_settings.start_mode,
             StartMode::TargetScreen("HDMI-1".to_string())
         );
+        assert_eq!(
+            layer_settings.input_region.as_ref().map(Region::rectangles),
+            Some(
+                [iced::Rectangle::new(
+                    iced::Point::ORIGIN,
+                    iced::Size::new(1920., 70.)
+                )]
+                .as_slice()
+            )
+        );
+        assert!(layer_settings.auto_exclusive_zone);
+        assert_eq!(layer_settings.auto_size, AutoSize::Content);
+        assert_eq!(layer_settings.auto_hide, Some(AutoHideSettings::default()));
     }
 }
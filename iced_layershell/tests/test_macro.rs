@@ -22,3 +22,95 @@ fn test_layer_message_macro_multi() {
     let (_id, message) = TestEnum::layershell_open(NewLayerShellSettings::default());
     assert!(matches!(message, TestEnum::NewLayerShell { .. }))
 }
+
+#[test]
+fn test_layer_message_macro_multi_lock_session() {
+    #[to_layer_message(multi)]
+    #[derive(Debug, Clone)]
+    enum TestEnum {
+        TestA,
+    }
+    use iced_layershell::actions::{LayershellCustomActions, LayershellCustomActionsWithId};
+    let action: Result<LayershellCustomActionsWithId, TestEnum> = TestEnum::LockSession.try_into();
+    assert!(matches!(
+        action,
+        Ok(LayershellCustomActionsWithId(
+            None,
+            LayershellCustomActions::LockSession
+        ))
+    ));
+}
+
+#[test]
+fn test_layer_message_macro_animated_margin() {
+    #[to_layer_message]
+    #[derive(Debug, Clone)]
+    enum TestEnum {
+        TestA,
+    }
+    use iced_layershell::actions::LayershellCustomActions;
+    use iced_layershell::animation::Easing;
+    let action: Result<LayershellCustomActions, TestEnum> = TestEnum::AnimatedMarginChange {
+        margin: (0, 0, 10, 0),
+        duration: std::time::Duration::from_millis(200),
+        easing: Easing::EaseOut,
+    }
+    .try_into();
+    assert!(matches!(
+        action,
+        Ok(LayershellCustomActions::AnimatedMarginChange {
+            margin: (0, 0, 10, 0),
+            easing: Easing::EaseOut,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_layer_message_macro_animated_opacity() {
+    #[to_layer_message]
+    #[derive(Debug, Clone)]
+    enum TestEnum {
+        TestA,
+    }
+    use iced_layershell::actions::LayershellCustomActions;
+    use iced_layershell::animation::Easing;
+    let action: Result<LayershellCustomActions, TestEnum> = TestEnum::AnimatedOpacityChange {
+        opacity: 0.5,
+        duration: std::time::Duration::from_millis(200),
+        easing: Easing::Linear,
+    }
+    .try_into();
+    assert!(matches!(
+        action,
+        Ok(LayershellCustomActions::AnimatedOpacityChange {
+            easing: Easing::Linear,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_layer_message_macro_multi_keyboard_interactivity() {
+    #[to_layer_message(multi)]
+    #[derive(Debug, Clone)]
+    enum TestEnum {
+        TestA,
+    }
+    use iced_layershell::actions::{LayershellCustomActions, LayershellCustomActionsWithId};
+    use iced_layershell::reexport::KeyboardInteractivity;
+    let id = iced::window::Id::unique();
+    let action: Result<LayershellCustomActionsWithId, TestEnum> =
+        TestEnum::KeyboardInteractivityChange {
+            id,
+            keyboard_interactivity: KeyboardInteractivity::Exclusive,
+        }
+        .try_into();
+    assert!(matches!(
+        action,
+        Ok(LayershellCustomActionsWithId(
+            Some(action_id),
+            LayershellCustomActions::KeyboardInteractivityChange(KeyboardInteractivity::Exclusive)
+        )) if action_id == id
+    ));
+}
@@ -43,6 +43,14 @@ pub fn to_layer_message(attr: TokenStream2, input: TokenStream2) -> manyhow::Res
                 LayerChange{id: iced::window::Id, layer:iced_layershell::reexport::Layer},
                 MarginChange{id: iced::window::Id, margin: (i32, i32, i32, i32)},
                 SizeChange{id: iced::window::Id, size: (u32, u32)},
+                ExclusiveZoneChange{id: iced::window::Id, zone_size: i32},
+                KeyboardInteractivityChange{id: iced::window::Id, keyboard_interactivity: iced_layershell::reexport::KeyboardInteractivity},
+                ExclusiveEdgeChange{id: iced::window::Id, edge: iced_layershell::reexport::Anchor},
+                AnimatedMarginChange{id: iced::window::Id, margin: (i32, i32, i32, i32), duration: std::time::Duration, easing: iced_layershell::animation::Easing},
+                AnimatedSizeChange{id: iced::window::Id, size: (u32, u32), duration: std::time::Duration, easing: iced_layershell::animation::Easing},
+                OpacityChange{id: iced::window::Id, opacity: f64},
+                AnimatedOpacityChange{id: iced::window::Id, opacity: f64, duration: std::time::Duration, easing: iced_layershell::animation::Easing},
+                InputRegionChange{id: iced::window::Id, region: iced_layershell::region::Region},
                 VirtualKeyboardPressed {
                     time: u32,
                     key: u32,
@@ -52,6 +60,9 @@ pub fn to_layer_message(attr: TokenStream2, input: TokenStream2) -> manyhow::Res
                 NewMenu { settings: iced_layershell::actions::IcedNewMenuSettings, id: iced::window::Id },
                 RemoveWindow(iced::window::Id),
                 ForgetLastOutput,
+                SetCustomCursor(Option<iced_layershell::reexport::CustomCursor>),
+                LockSession,
+                UnlockSession,
             };
             let try_into_impl = quote! {
                 impl #impl_gen TryInto<iced_layershell::actions::LayershellCustomActionsWithId> for #ident #ty_gen #where_gen {
@@ -68,6 +79,14 @@ pub fn to_layer_message(attr: TokenStream2, input: TokenStream2) -> manyhow::Res
                             Self::LayerChange { id, layer } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::LayerChange(layer))),
                             Self::MarginChange { id, margin } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::MarginChange(margin))),
                             Self::SizeChange { id, size } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::SizeChange(size))),
+                            Self::ExclusiveZoneChange { id, zone_size } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::ExclusiveZoneChange(zone_size))),
+                            Self::KeyboardInteractivityChange { id, keyboard_interactivity } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::KeyboardInteractivityChange(keyboard_interactivity))),
+                            Self::ExclusiveEdgeChange { id, edge } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::ExclusiveEdgeChange(edge))),
+                            Self::AnimatedMarginChange { id, margin, duration, easing } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::AnimatedMarginChange { margin, duration, easing })),
+                            Self::AnimatedSizeChange { id, size, duration, easing } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::AnimatedSizeChange { size, duration, easing })),
+                            Self::OpacityChange { id, opacity } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::OpacityChange(opacity))),
+                            Self::AnimatedOpacityChange { id, opacity, duration, easing } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::AnimatedOpacityChange { opacity, duration, easing })),
+                            Self::InputRegionChange { id, region } => Ok(LayershellCustomActionsWithId::new(Some(id), LayershellCustomActions::InputRegionChange(region))),
                             Self::VirtualKeyboardPressed { time, key } => Ok(LayershellCustomActionsWithId::new(
                                 None,
                                 LayershellCustomActions::VirtualKeyboardPressed { time, key })
@@ -77,6 +96,9 @@ pub fn to_layer_message(attr: TokenStream2, input: TokenStream2) -> manyhow::Res
                             Self::NewMenu { settings, id } =>  Ok(LayershellCustomActionsWithId::new(None, LayershellCustomActions::NewMenu {settings, id })),
                             Self::RemoveWindow(id) => Ok(LayershellCustomActionsWithId::new(None, LayershellCustomActions::RemoveWindow(id))),
                             Self::ForgetLastOutput => Ok(LayershellCustomActionsWithId::new(None, LayershellCustomActions::ForgetLastOutput)),
+                            Self::SetCustomCursor(cursor) => Ok(LayershellCustomActionsWithId::new(None, LayershellCustomActions::SetCustomCursor(cursor))),
+                            Self::LockSession => Ok(LayershellCustomActionsWithId::new(None, LayershellCustomActions::LockSession)),
+                            Self::UnlockSession => Ok(LayershellCustomActionsWithId::new(None, LayershellCustomActions::UnlockSession)),
                             _ => Err(self)
                         }
                     }
@@ -118,10 +140,31 @@ pub fn to_layer_message(attr: TokenStream2, input: TokenStream2) -> manyhow::Res
                 LayerChange(iced_layershell::reexport::Layer),
                 MarginChange((i32, i32, i32, i32)),
                 SizeChange((u32, u32)),
+                ExclusiveZoneChange(i32),
+                KeyboardInteractivityChange(iced_layershell::reexport::KeyboardInteractivity),
+                ExclusiveEdgeChange(iced_layershell::reexport::Anchor),
+                AnimatedMarginChange {
+                    margin: (i32, i32, i32, i32),
+                    duration: std::time::Duration,
+                    easing: iced_layershell::animation::Easing,
+                },
+                AnimatedSizeChange {
+                    size: (u32, u32),
+                    duration: std::time::Duration,
+                    easing: iced_layershell::animation::Easing,
+                },
+                OpacityChange(f64),
+                AnimatedOpacityChange {
+                    opacity: f64,
+                    duration: std::time::Duration,
+                    easing: iced_layershell::animation::Easing,
+                },
+                InputRegionChange(iced_layershell::region::Region),
                 VirtualKeyboardPressed {
                     time: u32,
                     key: u32,
                 },
+                SetCustomCursor(Option<iced_layershell::reexport::CustomCursor>),
             };
             let try_into_impl = quote! {
                 impl #impl_gen TryInto<iced_layershell::actions::LayershellCustomActions> for #ident #ty_gen #where_gen {
@@ -138,10 +181,19 @@ pub fn to_layer_message(attr: TokenStream2, input: TokenStream2) -> manyhow::Res
 
                             Self::MarginChange(margin) => Ok(LayershellCustomActions::MarginChange(margin)),
                             Self::SizeChange(size) => Ok(LayershellCustomActions::SizeChange(size)),
+                            Self::ExclusiveZoneChange(zone_size) => Ok(LayershellCustomActions::ExclusiveZoneChange(zone_size)),
+                            Self::KeyboardInteractivityChange(keyboard_interactivity) => Ok(LayershellCustomActions::KeyboardInteractivityChange(keyboard_interactivity)),
+                            Self::ExclusiveEdgeChange(edge) => Ok(LayershellCustomActions::ExclusiveEdgeChange(edge)),
+                            Self::AnimatedMarginChange { margin, duration, easing } => Ok(LayershellCustomActions::AnimatedMarginChange { margin, duration, easing }),
+                            Self::AnimatedSizeChange { size, duration, easing } => Ok(LayershellCustomActions::AnimatedSizeChange { size, duration, easing }),
+                            Self::OpacityChange(opacity) => Ok(LayershellCustomActions::OpacityChange(opacity)),
+                            Self::AnimatedOpacityChange { opacity, duration, easing } => Ok(LayershellCustomActions::AnimatedOpacityChange { opacity, duration, easing }),
+                            Self::InputRegionChange(region) => Ok(LayershellCustomActions::InputRegionChange(region)),
                             Self::VirtualKeyboardPressed { time, key } => Ok(LayershellCustomActions::VirtualKeyboardPressed {
                                 time,
                                 key
                             }),
+                            Self::SetCustomCursor(cursor) => Ok(LayershellCustomActions::SetCustomCursor(cursor)),
                             _ => Err(self)
                         }
                     }
@@ -7,7 +7,51 @@ pub(crate) enum SessionShellAction {
     Mouse(Interaction),
     RedrawAll,
     RedrawWindow(SessionId),
+    VirtualKeyboardPressed { time: u32, key: u32 },
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct UnLockAction;
+
+/// NOTE: DO NOT USE THIS ENUM DIERCTLY
+/// use macro to_session_message
+#[derive(Debug, Clone, Copy)]
+pub enum SessionLockCustomActions {
+    UnLock,
+    /// press and release the key with the virtual keyboard, it needs
+    /// [`VirtualKeyboardSettings`](crate::settings::VirtualKeyboardSettings)
+    VirtualKeyboardPressed {
+        time: u32,
+        key: u32,
+    },
+}
+
+impl From<UnLockAction> for SessionLockCustomActions {
+    fn from(_: UnLockAction) -> Self {
+        Self::UnLock
+    }
+}
+
+/// The error of `TryInto<UnLockAction>` for the enums of
+/// [to_session_message](crate::to_session_message), it gives the message back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotUnLock<Message>(pub Message);
+
+/// The messages which are handled by the session lock instead of `update`.
+///
+/// It is implemented for every message which can be turned into [UnLockAction], with the message
+/// itself as the error, and for the enums of [to_session_message](crate::to_session_message),
+/// which also press the keys of the virtual keyboard with
+/// [SessionLockCustomActions::VirtualKeyboardPressed].
+pub trait SessionLockMessage: Sized {
+    fn try_into_action(self) -> Result<SessionLockCustomActions, Self>;
+}
+
+impl<T> SessionLockMessage for T
+where
+    T: TryInto<UnLockAction, Error = T>,
+{
+    fn try_into_action(self) -> Result<SessionLockCustomActions, Self> {
+        self.try_into().map(SessionLockCustomActions::from)
+    }
+}
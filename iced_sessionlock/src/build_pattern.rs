@@ -1,7 +1,9 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, sync::Arc};
 
 use iced::{Font, Pixels};
 
+use crate::settings::VirtualKeyboardSettings;
+
 /// The renderer of iced program.
 pub trait Renderer: iced_core::text::Renderer + iced_graphics::compositor::Default {}
 
@@ -39,6 +41,8 @@ pub struct MainSettings {
     /// By default, it is disabled.
     ///
     pub antialiasing: bool,
+
+    pub virtual_keyboard_support: Option<VirtualKeyboardSettings>,
 }
 impl Default for MainSettings {
     fn default() -> Self {
@@ -48,6 +52,7 @@ impl Default for MainSettings {
             default_font: Font::default(),
             default_text_size: Pixels(16.0),
             antialiasing: false,
+            virtual_keyboard_support: None,
         }
     }
 }
@@ -58,7 +63,8 @@ mod pattern {
     use super::*;
     use iced::{Element, Task};
 
-    use crate::actions::UnLockAction;
+    use crate::actions::SessionLockMessage;
+    use crate::output::OutputInfo;
 
     use crate::DefaultStyle;
 
@@ -78,10 +84,7 @@ mod pattern {
         type Renderer: Renderer;
 
         /// The type of __messages__ your [`Application`] will produce.
-        type Message: std::fmt::Debug
-            + Send
-            + 'static
-            + TryInto<UnLockAction, Error = Self::Message>;
+        type Message: std::fmt::Debug + Send + 'static + SessionLockMessage;
 
         /// The theme of your [`Application`].
         type Theme: Default + DefaultStyle;
@@ -158,6 +161,19 @@ mod pattern {
             1.0
         }
 
+        /// Returns the __message__ produced when the [`OutputInfo`] of the window changes. It is
+        /// also called when the window is opened.
+        ///
+        /// By default, it returns `None`.
+        fn output_changed(
+            &self,
+            _state: &Self::State,
+            _window: iced_core::window::Id,
+            _output: &OutputInfo,
+        ) -> Option<Self::Message> {
+            None
+        }
+
         fn run_with<I>(self, settings: MainSettings, initialize: I) -> Result
         where
             Self: 'static,
@@ -226,6 +242,14 @@ mod pattern {
                 fn scale_factor(&self, window: iced::window::Id) -> f64 {
                     self.program.scale_factor(&self.state, window)
                 }
+
+                fn output_changed(
+                    &self,
+                    window: iced::window::Id,
+                    output: &OutputInfo,
+                ) -> Option<Self::Message> {
+                    self.program.output_changed(&self.state, window, output)
+                }
             }
 
             let real_settings = Settings {
@@ -235,6 +259,7 @@ mod pattern {
                 fonts: settings.fonts,
                 default_text_size: settings.default_text_size,
                 antialiasing: settings.antialiasing,
+                virtual_keyboard_support: settings.virtual_keyboard_support.map(Arc::new),
             };
             #[allow(clippy::needless_update)]
             let renderer_settings = iced_graphics::Settings {
@@ -326,7 +351,7 @@ mod pattern {
     ) -> Application<impl Program<Message = Message, Theme = Theme, State = State>>
     where
         State: 'static,
-        Message: 'static + TryInto<UnLockAction, Error = Message> + Send + std::fmt::Debug,
+        Message: 'static + SessionLockMessage + Send + std::fmt::Debug,
         Theme: Default + DefaultStyle,
         Renderer: self::Renderer,
     {
@@ -342,7 +367,7 @@ mod pattern {
         impl<State, Message, Theme, Renderer, Update, View> Program
             for Instance<State, Message, Theme, Renderer, Update, View>
         where
-            Message: 'static + TryInto<UnLockAction, Error = Message> + Send + std::fmt::Debug,
+            Message: 'static + SessionLockMessage + Send + std::fmt::Debug,
             Theme: Default + DefaultStyle,
             Renderer: self::Renderer,
             Update: self::Update<State, Message>,
@@ -438,6 +463,15 @@ mod pattern {
             fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
                 self.program.scale_factor(state, window)
             }
+
+            fn output_changed(
+                &self,
+                state: &Self::State,
+                window: iced_core::window::Id,
+                output: &OutputInfo,
+            ) -> Option<Self::Message> {
+                self.program.output_changed(state, window, output)
+            }
         }
 
         WithExecutor {
@@ -500,6 +534,15 @@ mod pattern {
             fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
                 self.program.scale_factor(state, window)
             }
+
+            fn output_changed(
+                &self,
+                state: &Self::State,
+                window: iced_core::window::Id,
+                output: &OutputInfo,
+            ) -> Option<Self::Message> {
+                self.program.output_changed(state, window, output)
+            }
         }
 
         WithSubscription {
@@ -562,6 +605,15 @@ mod pattern {
             fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
                 self.program.scale_factor(state, window)
             }
+
+            fn output_changed(
+                &self,
+                state: &Self::State,
+                window: iced_core::window::Id,
+                output: &OutputInfo,
+            ) -> Option<Self::Message> {
+                self.program.output_changed(state, window, output)
+            }
         }
 
         WithTheme { program, theme: f }
@@ -621,6 +673,15 @@ mod pattern {
             fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
                 self.program.scale_factor(state, window)
             }
+
+            fn output_changed(
+                &self,
+                state: &Self::State,
+                window: iced_core::window::Id,
+                output: &OutputInfo,
+            ) -> Option<Self::Message> {
+                self.program.output_changed(state, window, output)
+            }
         }
 
         WithStyle { program, style: f }
@@ -680,6 +741,15 @@ mod pattern {
             fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
                 (self.scale_factor)(state, window)
             }
+
+            fn output_changed(
+                &self,
+                state: &Self::State,
+                window: iced_core::window::Id,
+                output: &OutputInfo,
+            ) -> Option<Self::Message> {
+                self.program.output_changed(state, window, output)
+            }
         }
 
         WithScaleFactor {
@@ -688,6 +758,77 @@ mod pattern {
         }
     }
 
+    pub fn with_output_changed<P: Program>(
+        program: P,
+        f: impl Fn(&P::State, iced_core::window::Id, &OutputInfo) -> Option<P::Message>,
+    ) -> impl Program<State = P::State, Message = P::Message, Theme = P::Theme> {
+        struct WithOutputChanged<P, F> {
+            program: P,
+            output_changed: F,
+        }
+
+        impl<P: Program, F> Program for WithOutputChanged<P, F>
+        where
+            F: Fn(&P::State, iced_core::window::Id, &OutputInfo) -> Option<P::Message>,
+        {
+            type State = P::State;
+            type Message = P::Message;
+            type Theme = P::Theme;
+            type Renderer = P::Renderer;
+            type Executor = P::Executor;
+
+            fn namespace(&self, state: &Self::State) -> String {
+                self.program.namespace(state)
+            }
+
+            fn update(
+                &self,
+                state: &mut Self::State,
+                message: Self::Message,
+            ) -> Task<Self::Message> {
+                self.program.update(state, message)
+            }
+
+            fn view<'a>(
+                &self,
+                state: &'a Self::State,
+                window: iced_core::window::Id,
+            ) -> Element<'a, Self::Message, Self::Theme, Self::Renderer> {
+                self.program.view(state, window)
+            }
+
+            fn subscription(&self, state: &Self::State) -> iced::Subscription<Self::Message> {
+                self.program.subscription(state)
+            }
+
+            fn theme(&self, state: &Self::State) -> Self::Theme {
+                self.program.theme(state)
+            }
+
+            fn style(&self, state: &Self::State, theme: &Self::Theme) -> crate::Appearance {
+                self.program.style(state, theme)
+            }
+
+            fn scale_factor(&self, state: &Self::State, window: iced_core::window::Id) -> f64 {
+                self.program.scale_factor(state, window)
+            }
+
+            fn output_changed(
+                &self,
+                state: &Self::State,
+                window: iced_core::window::Id,
+                output: &OutputInfo,
+            ) -> Option<Self::Message> {
+                (self.output_changed)(state, window, output)
+            }
+        }
+
+        WithOutputChanged {
+            program,
+            output_changed: f,
+        }
+    }
+
     impl<P: Program> Application<P> {
         pub fn run(self) -> Result
         where
@@ -793,6 +934,19 @@ mod pattern {
                 settings: self.settings,
             }
         }
+
+        /// Sets the message produced when the [`OutputInfo`] of a window of the [`Application`]
+        /// changes, see [`Program::output_changed`].
+        pub fn output_changed(
+            self,
+            f: impl Fn(&P::State, iced_core::window::Id, &OutputInfo) -> Option<P::Message>,
+        ) -> Application<impl Program<State = P::State, Message = P::Message, Theme = P::Theme>>
+        {
+            Application {
+                raw: with_output_changed(self.raw, f),
+                settings: self.settings,
+            }
+        }
         /// Sets the executor of the [`Application`].
         pub fn executor<E>(
             self,
@@ -1,4 +1,5 @@
 use iced_futures::futures;
+use sessionlockev::SessonLockEventError;
 
 /// An error that occurred while running an application.
 #[derive(Debug, thiserror::Error)]
@@ -14,6 +15,22 @@ pub enum Error {
     /// The application graphics context could not be created.
     #[error("the application graphics context could not be created")]
     GraphicsCreationFailed(iced_graphics::Error),
+
+    /// The window action has no equivalent for a session lock.
+    #[error("the window action {0} is not supported by the session lock")]
+    UnsupportedWindowAction(&'static str),
+
+    /// The compositor refused to lock the session.
+    #[error("the compositor refused to lock the session")]
+    LockRefused,
+
+    /// The compositor finished the session lock after the session was locked.
+    #[error("the compositor finished the session lock")]
+    LockFinished,
+
+    /// The session lock could not be created, or its event loop failed.
+    #[error("the session lock event loop failed")]
+    EventLoopFailed(SessonLockEventError),
 }
 
 impl From<iced_graphics::Error> for Error {
@@ -21,3 +38,13 @@ impl From<iced_graphics::Error> for Error {
         Error::GraphicsCreationFailed(error)
     }
 }
+
+impl From<SessonLockEventError> for Error {
+    fn from(error: SessonLockEventError) -> Error {
+        match error {
+            SessonLockEventError::LockRefused => Error::LockRefused,
+            SessonLockEventError::LockFinished => Error::LockFinished,
+            error => Error::EventLoopFailed(error),
+        }
+    }
+}
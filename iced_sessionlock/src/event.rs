@@ -1,87 +1,9 @@
 use sessionlockev::id::Id;
-use sessionlockev::keyboard::ModifiersState;
-use sessionlockev::reexport::wayland_client::{ButtonState, KeyState, WEnum};
-use sessionlockev::xkb_keyboard::KeyEvent as SessionLockEvent;
 use sessionlockev::{DispatchMessage, WindowWrapper};
 
-use iced_core::keyboard::Modifiers as IcedModifiers;
-#[derive(Debug, Clone, Copy)]
-pub enum IcedButtonState {
-    Pressed,
-    Released,
-}
-
-#[derive(Debug, Clone, Copy)]
-pub enum IcedKeyState {
-    Pressed,
-    Released,
-}
+pub use iced_shellcore::event::{IcedButtonState, WindowEvent};
 
-impl From<WEnum<KeyState>> for IcedKeyState {
-    fn from(value: WEnum<KeyState>) -> Self {
-        match value {
-            WEnum::Value(KeyState::Released) => Self::Released,
-            WEnum::Value(KeyState::Pressed) => Self::Pressed,
-            _ => unreachable!(),
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-pub enum WindowEvent {
-    ScaleFactorChanged {
-        scale_u32: u32,
-        scale_float: f64,
-    },
-    CursorEnter {
-        x: f64,
-        y: f64,
-    },
-    CursorMoved {
-        x: f64,
-        y: f64,
-    },
-    CursorLeft,
-    MouseInput(IcedButtonState),
-    Keyboard {
-        state: IcedKeyState,
-        key: u32,
-        modifiers: IcedModifiers,
-    },
-    KeyBoardInput {
-        event: SessionLockEvent,
-        is_synthetic: bool,
-    },
-    ModifiersChanged(ModifiersState),
-    Axis {
-        x: f32,
-        y: f32,
-    },
-    PixelDelta {
-        x: f32,
-        y: f32,
-    },
-    TouchDown {
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    TouchUp {
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    TouchMotion {
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    TouchCancel {
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-}
+use crate::output::OutputInfo;
 
 #[derive(Debug)]
 pub enum IcedSessionLockEvent<Message: 'static> {
@@ -90,6 +12,7 @@ pub enum IcedSessionLockEvent<Message: 'static> {
         height: u32,
         scale_float: f64,
         wrapper: WindowWrapper,
+        output: Option<OutputInfo>,
     },
     #[allow(unused)]
     RequestRefresh {
@@ -99,6 +22,8 @@ pub enum IcedSessionLockEvent<Message: 'static> {
     Window(WindowEvent),
     NormalUpdate,
     UserEvent(Message),
+    WindowRemoved,
+    OutputChanged(OutputInfo),
 }
 
 #[derive(Debug)]
@@ -134,16 +59,12 @@ impl<Message: 'static> From<&DispatchMessage> for IcedSessionLockEvent<Message>
                 surface_y: y,
                 ..
             } => IcedSessionLockEvent::Window(WindowEvent::CursorMoved { x: *x, y: *y }),
-            DispatchMessage::MouseLeave => IcedSessionLockEvent::Window(WindowEvent::CursorLeft),
-            DispatchMessage::MouseButton { state, .. } => match state {
-                WEnum::Value(ButtonState::Pressed) => {
-                    IcedSessionLockEvent::Window(WindowEvent::MouseInput(IcedButtonState::Pressed))
-                }
-                WEnum::Value(ButtonState::Released) => {
-                    IcedSessionLockEvent::Window(WindowEvent::MouseInput(IcedButtonState::Released))
-                }
-                _ => unreachable!(),
-            },
+            DispatchMessage::MouseLeave { .. } => {
+                IcedSessionLockEvent::Window(WindowEvent::CursorLeft)
+            }
+            DispatchMessage::MouseButton { state, button, .. } => IcedSessionLockEvent::Window(
+                WindowEvent::MouseInput(IcedButtonState::new(*state, *button)),
+            ),
             DispatchMessage::TouchUp { id, x, y, .. } => {
                 IcedSessionLockEvent::Window(WindowEvent::TouchUp {
                     id: *id,
@@ -182,13 +103,16 @@ impl<Message: 'static> From<&DispatchMessage> for IcedSessionLockEvent<Message>
             DispatchMessage::KeyboardInput {
                 event,
                 is_synthetic,
+                ..
             } => IcedSessionLockEvent::Window(WindowEvent::KeyBoardInput {
                 event: event.clone(),
                 is_synthetic: *is_synthetic,
             }),
-            DispatchMessage::ModifiersChanged(modifiers) => {
+            DispatchMessage::ModifiersChanged { modifiers, .. } => {
                 IcedSessionLockEvent::Window(WindowEvent::ModifiersChanged(*modifiers))
             }
+            DispatchMessage::Unfocus { .. } => IcedSessionLockEvent::Window(WindowEvent::Unfocus),
+            DispatchMessage::Focused { .. } => IcedSessionLockEvent::Window(WindowEvent::Focused),
             DispatchMessage::Axis {
                 horizontal,
                 vertical,
@@ -2,15 +2,15 @@
 pub mod actions;
 pub mod build_pattern;
 pub mod multi_window;
+pub mod output;
+#[cfg(feature = "pam")]
+pub mod pam;
 pub mod settings;
 
-mod clipboard;
-mod conversion;
 mod error;
 mod event;
-mod proxy;
 
-use iced::{Color, Element, Theme};
+use iced::Element;
 use iced_futures::Subscription;
 use iced_runtime::Task;
 
@@ -18,40 +18,12 @@ pub use iced_sessionlock_macros::to_session_message;
 
 pub use error::Error;
 
-use actions::UnLockAction;
+use actions::SessionLockMessage;
+use output::OutputInfo;
 use settings::Settings;
 
 pub type Result = std::result::Result<(), error::Error>;
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Appearance {
-    /// The background [`Color`] of the application.
-    pub background_color: Color,
-
-    /// The default text [`Color`] of the application.
-    pub text_color: Color,
-}
-
-/// The default style of a [`MultiApplication`].
-pub trait DefaultStyle {
-    /// Returns the default style of a [`MultiApplication`].
-    fn default_style(&self) -> Appearance;
-}
-
-impl DefaultStyle for Theme {
-    fn default_style(&self) -> Appearance {
-        default(self)
-    }
-}
-
-/// The default [`Appearance`] of a [`MultiApplication`] with the built-in [`Theme`].
-pub fn default(theme: &Theme) -> Appearance {
-    let palette = theme.extended_palette();
-
-    Appearance {
-        background_color: palette.background.base.color,
-        text_color: palette.background.base.text,
-    }
-}
+pub use iced_shellcore::{default, Appearance, DefaultStyle};
 
 pub trait MultiApplication: Sized {
     /// The [`Executor`] that will run commands and subscriptions.
@@ -146,6 +118,20 @@ pub trait MultiApplication: Sized {
         1.0
     }
 
+    /// Returns the __message__ produced when the [`OutputInfo`] of the `window` changes. It is
+    /// also called when the window is opened, so the [`MultiApplication`] can tell which output
+    /// every window is shown on.
+    ///
+    /// By default, it returns `None`.
+    #[allow(unused_variables)]
+    fn output_changed(
+        &self,
+        window: iced::window::Id,
+        output: &OutputInfo,
+    ) -> Option<Self::Message> {
+        None
+    }
+
     /// Runs the multi-window [`MultiApplication`].
     ///
     /// On native platforms, this method will take control of the current thread
@@ -154,11 +140,14 @@ pub trait MultiApplication: Sized {
     /// On the web platform, this method __will NOT return__ unless there is an
     /// [`Error`] during startup.
     ///
+    /// If the compositor refuses or finishes the lock, it returns [`Error::LockRefused`] or
+    /// [`Error::LockFinished`], so the application can exit with its own code.
+    ///
     /// [`Error`]: crate::Error
     fn run(settings: Settings<Self::Flags>) -> Result
     where
         Self: 'static,
-        Self::Message: 'static + TryInto<UnLockAction, Error = Self::Message>,
+        Self::Message: 'static + SessionLockMessage,
     {
         #[allow(clippy::needless_update)]
         let renderer_settings = iced_graphics::Settings {
@@ -232,4 +221,12 @@ where
     fn scale_factor(&self, window: iced::window::Id) -> f64 {
         self.0.scale_factor(window)
     }
+
+    fn output_changed(
+        &self,
+        window: iced::window::Id,
+        output: &OutputInfo,
+    ) -> Option<Self::Message> {
+        self.0.output_changed(window, output)
+    }
 }
@@ -1,36 +1,44 @@
-mod state;
-use crate::{
-    actions::{SessionShellActionVec, UnLockAction},
-    multi_window::window_manager::WindowManager,
+use crate::actions::{SessionLockCustomActions, SessionLockMessage, SessionShellActionVec};
+use std::{
+    borrow::Cow, collections::HashMap, f64, mem::ManuallyDrop, os::fd::AsFd, sync::Arc,
+    time::Duration,
 };
-use std::{borrow::Cow, collections::HashMap, f64, mem::ManuallyDrop, sync::Arc};
 
-use crate::{
-    actions::SessionShellAction, clipboard::SessionLockClipboard, conversion, error::Error,
+use crate::{actions::SessionShellAction, error::Error};
+use iced_shellcore::{
+    clipboard::ShellClipboard,
+    conversion,
+    multi_window::{
+        self, build_user_interface, build_user_interfaces, ShellAction, State,
+        UnsupportedWindowAction, WindowManager,
+    },
+    proxy::IcedProxy,
 };
 
 use super::{Appearance, DefaultStyle};
 use iced::Task;
 use iced_graphics::Compositor;
 
-use iced_core::{time::Instant, Size};
+use iced_core::time::Instant;
 
-use iced_runtime::{multi_window::Program, user_interface, Action, Debug, UserInterface};
+use iced_runtime::{multi_window::Program, user_interface, Action, Debug};
 
 use iced_futures::{Executor, Runtime, Subscription};
 
-use sessionlockev::{ReturnData, SessionLockEvent, WindowState, WindowWrapper};
+use sessionlockev::{
+    calloop::timer::{TimeoutAction, Timer},
+    reexport::zwp_virtual_keyboard_v1,
+    ReturnData, SeatHandler, SeatId, SessionLockEvent, WindowState, WindowWrapper,
+};
 
 use futures::{channel::mpsc, StreamExt};
 
 use crate::{
     event::{IcedSessionLockEvent, MultiWindowIcedSessionLockEvent},
-    proxy::IcedProxy,
-    settings::Settings,
+    output::OutputInfo,
+    settings::{Settings, VirtualKeyboardSettings},
 };
 
-mod window_manager;
-
 /// An interactive, native cross-platform application.
 ///
 /// This trait is the main entrypoint of Iced. Once implemented, you can run
@@ -102,6 +110,18 @@ where
         1.0
     }
 
+    /// Returns the __message__ produced when the [`OutputInfo`] of the `window` changes. It is
+    /// also called when the window is opened.
+    ///
+    /// By default, it returns `None`.
+    fn output_changed(
+        &self,
+        _window: iced::window::Id,
+        _output: &OutputInfo,
+    ) -> Option<Self::Message> {
+        None
+    }
+
     /// Defines whether or not to use natural scrolling
     fn natural_scroll(&self) -> bool {
         false
@@ -127,7 +147,7 @@ where
     E: Executor + 'static,
     C: Compositor<Renderer = A::Renderer> + 'static,
     A::Theme: DefaultStyle,
-    A::Message: 'static + TryInto<UnLockAction, Error = A::Message>,
+    A::Message: 'static + SessionLockMessage,
 {
     use futures::task;
     use futures::Future;
@@ -159,8 +179,7 @@ where
 
     let ev: WindowState<()> = sessionlockev::WindowState::new()
         .with_use_display_handle(true)
-        .build()
-        .expect("Seems sessionlock is not supported");
+        .build()?;
 
     let window = Arc::new(ev.gen_main_wrapper());
 
@@ -183,13 +202,51 @@ where
     let mut context = task::Context::from_waker(task::noop_waker_ref());
 
     let mut pointer_serial: u32 = 0;
+    // NOTE: the serial is only valid for the pointer of the seat which sent it
+    let mut pointer_seat: Option<SeatId> = None;
 
-    let _ = ev.running_with_proxy(message_receiver, move |event, ev, id| {
+    ev.running_with_proxy(message_receiver, move |event, ev, id| {
         use sessionlockev::DispatchMessage;
         match event {
-            SessionLockEvent::InitRequest => {}
-            // TODO: maybe use it later
-            SessionLockEvent::BindProvide(_, _) => {}
+            SessionLockEvent::InitRequest if settings.virtual_keyboard_support.is_some() => {
+                return ReturnData::RequestBind;
+            }
+            SessionLockEvent::BindProvide(globals, qh) => {
+                if let Some(VirtualKeyboardSettings {
+                    file,
+                    keymap_size,
+                    keymap_format,
+                }) = settings.virtual_keyboard_support.as_deref()
+                {
+                    match (
+                        globals.bind::<zwp_virtual_keyboard_v1::ZwpVirtualKeyboardManagerV1, _, _>(
+                            qh,
+                            1..=1,
+                            (),
+                        ),
+                        ev.get_seat(),
+                    ) {
+                        (Ok(virtual_keyboard_manager), Some(seat)) => {
+                            let virtual_keyboard_in =
+                                virtual_keyboard_manager.create_virtual_keyboard(seat, qh, ());
+                            virtual_keyboard_in.keymap(
+                                (*keymap_format).into(),
+                                file.as_fd(),
+                                *keymap_size,
+                            );
+                            ev.set_virtual_keyboard(virtual_keyboard_in);
+                        }
+                        (Err(_), _) => log::error!(
+                            "the compositor does not support zwp_virtual_keyboard_v1, \
+                            the virtual keyboard is disabled"
+                        ),
+                        (Ok(_), None) => log::error!(
+                            "the compositor has no wl_seat, the virtual keyboard is disabled"
+                        ),
+                    }
+                }
+                return ReturnData::None;
+            }
             SessionLockEvent::RequestMessages(message) => 'outside: {
                 match message {
                     DispatchMessage::RequestRefresh {
@@ -197,6 +254,7 @@ where
                         height,
                         scale_float,
                     } => {
+                        let unit = ev.get_unit_with_id(id.unwrap()).unwrap();
                         event_sender
                             .start_send(MultiWindowIcedSessionLockEvent(
                                 id,
@@ -204,17 +262,16 @@ where
                                     width: *width,
                                     height: *height,
                                     scale_float: *scale_float,
-                                    wrapper: ev
-                                        .get_unit_with_id(id.unwrap())
-                                        .unwrap()
-                                        .gen_wrapper(),
+                                    wrapper: unit.gen_wrapper(),
+                                    output: unit.get_xdgoutput_info().map(OutputInfo::from),
                                 },
                             ))
                             .expect("Cannot send");
                         break 'outside;
                     }
-                    DispatchMessage::MouseEnter { serial, .. } => {
+                    DispatchMessage::MouseEnter { serial, seat, .. } => {
                         pointer_serial = *serial;
+                        pointer_seat = *seat;
                     }
                     _ => {}
                 }
@@ -223,6 +280,27 @@ where
                     .start_send(MultiWindowIcedSessionLockEvent(id, message.into()))
                     .expect("Cannot send");
             }
+            SessionLockEvent::SurfaceRemoved(removed_id) => {
+                event_sender
+                    .start_send(MultiWindowIcedSessionLockEvent(
+                        Some(removed_id),
+                        IcedSessionLockEvent::WindowRemoved,
+                    ))
+                    .expect("Cannot send");
+            }
+            SessionLockEvent::XdgInfoChanged(_) => {
+                if let Some(output) = id
+                    .and_then(|id| ev.get_unit_with_id(id))
+                    .and_then(|unit| unit.get_xdgoutput_info())
+                {
+                    event_sender
+                        .start_send(MultiWindowIcedSessionLockEvent(
+                            id,
+                            IcedSessionLockEvent::OutputChanged(output.into()),
+                        ))
+                        .expect("Cannot send");
+                }
+            }
             SessionLockEvent::NormalDispatch => {
                 event_sender
                     .start_send(MultiWindowIcedSessionLockEvent(
@@ -252,7 +330,9 @@ where
                 {
                     match flow {
                         SessionShellAction::Mouse(mouse) => {
-                            let Some(pointer) = ev.get_pointer() else {
+                            let Some(pointer) =
+                                pointer_seat.and_then(|seat| ev.get_pointer_with_seat(seat))
+                            else {
                                 break 'peddingBlock ReturnData::None;
                             };
 
@@ -268,13 +348,33 @@ where
                         SessionShellAction::RedrawWindow(index) => {
                             break 'peddingBlock ReturnData::RedrawIndexRequest(index);
                         }
+                        SessionShellAction::VirtualKeyboardPressed { time, key } => {
+                            use sessionlockev::reexport::wayland_client::KeyState;
+                            let Some(ky) = ev.get_virtual_keyboard() else {
+                                log::warn!("virtual keyboard is not enabled in the settings");
+                                break 'peddingBlock ReturnData::None;
+                            };
+                            ky.key(time, key, KeyState::Pressed.into());
+
+                            let eh = ev.get_loop_handler().unwrap();
+                            eh.insert_source(
+                                Timer::from_duration(Duration::from_micros(100)),
+                                move |_, _, state| {
+                                    if let Some(ky) = state.get_virtual_keyboard() {
+                                        ky.key(time, key, KeyState::Released.into());
+                                    }
+                                    TimeoutAction::Drop
+                                },
+                            )
+                            .ok();
+                        }
                     }
                 }
                 ReturnData::None
             }
             task::Poll::Ready(_) => ReturnData::RequestUnlockAndExist,
         }
-    });
+    })?;
     Ok(())
 }
 
@@ -295,7 +395,7 @@ async fn run_instance<A, E, C>(
     E: Executor + 'static,
     C: Compositor<Renderer = A::Renderer> + 'static,
     A::Theme: DefaultStyle,
-    A::Message: 'static + TryInto<UnLockAction, Error = A::Message>,
+    A::Message: 'static + SessionLockMessage,
 {
     use iced::window;
     use iced_core::Event;
@@ -307,8 +407,10 @@ async fn run_instance<A, E, C>(
     }
     let mut window_manager = WindowManager::new();
 
-    let mut clipboard = SessionLockClipboard::connect(&window);
+    let mut clipboard = ShellClipboard::connect(&window);
     let mut ui_caches: HashMap<window::Id, user_interface::Cache> = HashMap::new();
+    // the output info of every window, only the changes are passed to the application
+    let mut outputs: HashMap<window::Id, OutputInfo> = HashMap::new();
 
     let mut user_interfaces = ManuallyDrop::new(build_user_interfaces(
         &application,
@@ -331,17 +433,27 @@ async fn run_instance<A, E, C>(
                     height,
                     wrapper,
                     scale_float,
+                    output,
                 },
             ) => {
                 let (id, window) = if window_manager.get_mut_alias(wrapper.id()).is_none() {
                     let id = window::Id::unique();
 
-                    let window = window_manager.insert(
-                        id,
+                    let theme = application.theme();
+                    let appearance = application.style(&theme);
+                    let state = State::new(
+                        application.scale_factor(id),
+                        theme,
+                        appearance,
                         (width, height),
                         scale_float,
+                        wrapper.viewport.clone(),
+                    );
+                    let window = window_manager.insert(
+                        id,
+                        wrapper.id(),
+                        state,
                         Arc::new(wrapper),
-                        &application,
                         &mut compositor,
                     );
                     let logical_size = window.state.logical_size();
@@ -359,6 +471,10 @@ async fn run_instance<A, E, C>(
                     );
                     let _ = ui_caches.insert(id, user_interface::Cache::default());
 
+                    if let Some(output) = output {
+                        messages.extend(application.output_changed(id, &output));
+                        outputs.insert(id, output);
+                    }
                     events.push((
                         Some(id),
                         Event::Window(window::Event::Opened {
@@ -386,7 +502,7 @@ async fn run_instance<A, E, C>(
 
                 events.push((Some(id), redraw_event.clone()));
                 ui.update(
-                    &[redraw_event.clone()],
+                    std::slice::from_ref(&redraw_event),
                     cursor,
                     &mut window.renderer,
                     &mut clipboard,
@@ -444,6 +560,33 @@ async fn run_instance<A, E, C>(
 
                 debug.render_finished();
             }
+            MultiWindowIcedSessionLockEvent(Some(id), IcedSessionLockEvent::WindowRemoved) => {
+                let Some((id, _)) = window_manager.get_mut_alias(id) else {
+                    continue;
+                };
+                window_manager.remove(id);
+                outputs.remove(&id);
+                ui_caches.remove(&id);
+                let _ = user_interfaces.remove(&id);
+                runtime.broadcast(iced_futures::subscription::Event::Interaction {
+                    window: id,
+                    event: Event::Window(window::Event::Closed),
+                    status: iced_core::event::Status::Ignored,
+                });
+            }
+            MultiWindowIcedSessionLockEvent(
+                Some(id),
+                IcedSessionLockEvent::OutputChanged(output),
+            ) => {
+                let Some((id, _)) = window_manager.get_mut_alias(id) else {
+                    continue;
+                };
+                if outputs.get(&id) == Some(&output) {
+                    continue;
+                }
+                messages.extend(application.output_changed(id, &output));
+                outputs.insert(id, output);
+            }
             MultiWindowIcedSessionLockEvent(Some(id), IcedSessionLockEvent::Window(event)) => {
                 let Some((id, window)) = window_manager.get_mut_alias(id) else {
                     continue;
@@ -470,6 +613,7 @@ async fn run_instance<A, E, C>(
                     &mut compositor,
                     action,
                     &mut clipboard,
+                    &mut custom_actions,
                     &mut should_exit,
                     &mut debug,
                     &mut window_manager,
@@ -526,7 +670,7 @@ async fn run_instance<A, E, C>(
 
                     debug.event_processing_finished();
 
-                    for (event, status) in window_events.drain(..).zip(statuses.into_iter()) {
+                    for (event, status) in window_events.drain(..).zip(statuses) {
                         runtime.broadcast(iced_futures::subscription::Event::Interaction {
                             window: id,
                             event,
@@ -545,8 +689,12 @@ async fn run_instance<A, E, C>(
                     // Update application
                     update(&mut application, &mut runtime, &mut debug, &mut messages);
 
-                    for (_id, window) in window_manager.iter_mut() {
-                        window.state.synchronize(&application);
+                    for (id, window) in window_manager.iter_mut() {
+                        let theme = application.theme();
+                        let appearance = application.style(&theme);
+                        window
+                            .state
+                            .synchronize(application.scale_factor(id), theme, appearance);
                     }
 
                     custom_actions.push(SessionShellAction::RedrawAll);
@@ -569,60 +717,6 @@ async fn run_instance<A, E, C>(
     let _ = ManuallyDrop::into_inner(user_interfaces);
 }
 
-#[allow(clippy::type_complexity)]
-pub fn build_user_interfaces<'a, A: Application, C>(
-    application: &'a A,
-    debug: &mut Debug,
-    window_manager: &mut WindowManager<A, C>,
-    mut cached_user_interfaces: HashMap<iced::window::Id, user_interface::Cache>,
-) -> HashMap<iced::window::Id, UserInterface<'a, A::Message, A::Theme, A::Renderer>>
-where
-    C: Compositor<Renderer = A::Renderer>,
-    A::Theme: DefaultStyle,
-{
-    cached_user_interfaces
-        .drain()
-        .filter_map(|(id, cache)| {
-            let window = window_manager.get_mut(id)?;
-
-            Some((
-                id,
-                build_user_interface(
-                    application,
-                    cache,
-                    &mut window.renderer,
-                    window.state.logical_size(),
-                    debug,
-                    id,
-                ),
-            ))
-        })
-        .collect()
-}
-
-/// Builds a [`UserInterface`] for the provided [`Application`], logging
-/// [`struct@Debug`] information accordingly.
-fn build_user_interface<'a, A: Application>(
-    application: &'a A,
-    cache: user_interface::Cache,
-    renderer: &mut A::Renderer,
-    size: Size,
-    debug: &mut Debug,
-    id: iced::window::Id,
-) -> UserInterface<'a, A::Message, A::Theme, A::Renderer>
-where
-    A::Theme: DefaultStyle,
-{
-    debug.view_started();
-    let view = application.view(id);
-    debug.view_finished();
-
-    debug.layout_started();
-    let user_interface = UserInterface::build(view, size, cache, renderer);
-    debug.layout_finished();
-    user_interface
-}
-
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn update<A: Application, E: Executor>(
     application: &mut A,
@@ -657,103 +751,53 @@ pub(crate) fn run_action<A, C>(
     messages: &mut Vec<A::Message>,
     compositor: &mut C,
     action: Action<A::Message>,
-    clipboard: &mut SessionLockClipboard,
+    clipboard: &mut ShellClipboard,
+    custom_actions: &mut SessionShellActionVec,
     should_exit: &mut bool,
     debug: &mut Debug,
-    window_manager: &mut WindowManager<A, C>,
+    window_manager: &mut WindowManager<sessionlockev::id::Id, A::Theme, C>,
     ui_caches: &mut HashMap<iced::window::Id, user_interface::Cache>,
 ) where
     A: Application,
     C: Compositor<Renderer = A::Renderer> + 'static,
     A::Theme: DefaultStyle,
-    A::Message: 'static + TryInto<UnLockAction, Error = A::Message>,
+    A::Message: 'static + SessionLockMessage,
 {
-    use iced_core::widget::operation;
-    use iced_runtime::clipboard;
-    use iced_runtime::window;
-    use iced_runtime::window::Action as WinowAction;
-    //let mut customactions = Vec::new();
+    let action = match multi_window::run_action(
+        application,
+        compositor,
+        action,
+        clipboard,
+        debug,
+        window_manager,
+        ui_caches,
+    ) {
+        Ok(Some(action)) => action,
+        Ok(None) => return,
+        Err(UnsupportedWindowAction(name)) => {
+            log::error!("{}", Error::UnsupportedWindowAction(name));
+            return;
+        }
+    };
     match action {
-        Action::Output(message) => match message.try_into() {
-            Ok(action) => {
-                let _: UnLockAction = action;
+        ShellAction::Output(message) => match message.try_into_action() {
+            Ok(SessionLockCustomActions::UnLock) => {
                 *should_exit = true;
             }
-            Err(message) => messages.push(message),
-        },
-        Action::Clipboard(action) => match action {
-            clipboard::Action::Read { target, channel } => {
-                let _ = channel.send(clipboard.read(target));
-            }
-            clipboard::Action::Write { target, contents } => {
-                clipboard.write(target, contents);
+            Ok(SessionLockCustomActions::VirtualKeyboardPressed { time, key }) => {
+                custom_actions.push(SessionShellAction::VirtualKeyboardPressed { time, key });
             }
+            Err(message) => messages.push(message),
         },
-        Action::Widget(action) => {
-            let mut current_operation = Some(action);
-
-            let mut uis = build_user_interfaces(
-                application,
-                debug,
-                window_manager,
-                std::mem::take(ui_caches),
-            );
-
-            'operate: while let Some(mut operation) = current_operation.take() {
-                for (id, ui) in uis.iter_mut() {
-                    if let Some(window) = window_manager.get_mut(*id) {
-                        ui.operate(&window.renderer, operation.as_mut());
-
-                        match operation.finish() {
-                            operation::Outcome::None => {}
-                            operation::Outcome::Some(_message) => {
-                                //proxy.send_event(message).ok();
-
-                                // operation completed, don't need to try to operate on rest of UIs
-                                break 'operate;
-                            }
-                            operation::Outcome::Chain(next) => {
-                                current_operation = Some(next);
-                            }
-                        }
-                    }
-                }
-            }
-
-            *ui_caches = uis.drain().map(|(id, ui)| (id, ui.into_cache())).collect();
-        }
-        Action::Window(action) => 'out: {
-            match action {
-                WinowAction::Close(_) => {
-                    *should_exit = true;
-                }
-                WinowAction::Screenshot(id, channel) => {
-                    let Some(window) = window_manager.get_mut(id) else {
-                        break 'out;
-                    };
-                    let bytes = compositor.screenshot(
-                        &mut window.renderer,
-                        &mut window.surface,
-                        window.state.viewport(),
-                        window.state.background_color(),
-                        &debug.overlay(),
-                    );
-                    let _ = channel.send(window::Screenshot::new(
-                        bytes,
-                        window.state.physical_size(),
-                        window.state.viewport().scale_factor(),
-                    ));
-                }
-                _ => {}
-            }
+        // NOTE: the session is only unlocked by UnLock
+        ShellAction::Exit => {}
+        ShellAction::Close(_) => {
+            *should_exit = true;
         }
-        Action::LoadFont { bytes, channel } => {
-            // TODO: Error handling (?)
-            compositor.load_font(bytes.clone());
-
-            let _ = channel.send(Ok(()));
+        // NOTE: the compositor gives the size of the lock surfaces, and they take every input
+        ShellAction::Resize(..) => log::error!("a lock surface cannot be resized"),
+        ShellAction::MousePassthrough(..) => {
+            log::error!("the pointer events cannot pass through a lock surface")
         }
-
-        _ => {}
     }
 }
@@ -0,0 +1,29 @@
+use sessionlockev::ZxdgOutputInfo;
+
+/// The information of the output a lock window is shown on, it is passed to
+/// [`MultiApplication::output_changed`](crate::MultiApplication::output_changed) when the window
+/// is opened, and every time the information changes.
+///
+/// It is only available if the compositor supports `zxdg_output_manager_v1`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputInfo {
+    /// The name of the output, like `DP-1`.
+    pub name: String,
+    /// The human readable description of the output.
+    pub description: String,
+    /// The logical position of the output in the global compositor space.
+    pub position: (i32, i32),
+    /// The logical size of the output.
+    pub logical_size: (i32, i32),
+}
+
+impl From<&ZxdgOutputInfo> for OutputInfo {
+    fn from(info: &ZxdgOutputInfo) -> Self {
+        Self {
+            name: info.get_name().to_owned(),
+            description: info.get_description().to_owned(),
+            position: info.get_position(),
+            logical_size: info.get_logical_size(),
+        }
+    }
+}
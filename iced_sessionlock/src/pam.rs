@@ -0,0 +1,399 @@
+//! Password checking with PAM, enabled with the `pam` feature.
+//!
+//! ```rust, no_run
+//! use iced_runtime::Task;
+//! use iced_sessionlock::pam::{AuthError, Authenticator};
+//! use iced_sessionlock::to_session_message;
+//!
+//! #[to_session_message]
+//! #[derive(Debug, Clone)]
+//! enum Message {
+//!     Submit,
+//!     AuthFailed(AuthError),
+//! }
+//!
+//! let authenticator = Authenticator::new("login").with_max_retries(Some(3));
+//! let task: Task<Message> = authenticator.unlock("user", "password", Message::AuthFailed);
+//! ```
+use std::{
+    ffi::{c_char, c_int, c_void, CStr, CString},
+    future::Future,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    ptr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::channel::oneshot;
+use iced_runtime::Task;
+
+use crate::actions::UnLockAction;
+
+/// the time to wait before reporting a failed attempt, used by [Authenticator::new]
+pub const DEFAULT_FAIL_DELAY: Duration = Duration::from_secs(2);
+
+/// the errors of [Authenticator::authenticate]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AuthError {
+    #[error("authentication failed")]
+    Failed,
+    #[error("too many failed attempts")]
+    TooManyAttempts,
+    #[error("the user or the password contains a nul byte")]
+    InvalidInput,
+    #[error("pam error: {0}")]
+    Pam(String),
+}
+
+/// Check passwords with a PAM service. Every attempt runs on its own thread, so it can be awaited
+/// on any executor, or turned into a [Task] with [Authenticator::unlock].
+///
+/// The authenticator can be cloned, the clones share the count of failed attempts.
+#[derive(Debug, Clone)]
+pub struct Authenticator {
+    service: String,
+    config_dir: Option<PathBuf>,
+    max_retries: Option<u32>,
+    fail_delay: Duration,
+    failures: Arc<Mutex<u32>>,
+}
+
+impl Authenticator {
+    /// create an authenticator for the service, which is a file under `/etc/pam.d`
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            config_dir: None,
+            max_retries: None,
+            fail_delay: DEFAULT_FAIL_DELAY,
+            failures: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    /// read the service from this directory instead of `/etc/pam.d`, useful for testing. It needs
+    /// Linux-PAM 1.4 or newer, otherwise the attempts fail with [AuthError::Pam]
+    pub fn with_config_dir(mut self, config_dir: Option<PathBuf>) -> Self {
+        self.config_dir = config_dir;
+        self
+    }
+
+    /// the failed attempts allowed before [AuthError::TooManyAttempts] is returned, None means no
+    /// limit
+    pub fn with_max_retries(mut self, max_retries: Option<u32>) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// the time to wait before reporting a failed attempt
+    pub fn with_fail_delay(mut self, fail_delay: Duration) -> Self {
+        self.fail_delay = fail_delay;
+        self
+    }
+
+    /// the failed attempts since the last success
+    pub fn failed_attempts(&self) -> u32 {
+        *self.failures.lock().unwrap()
+    }
+
+    /// the attempts left, None if there is no limit
+    pub fn remaining_retries(&self) -> Option<u32> {
+        self.max_retries
+            .map(|max_retries| max_retries.saturating_sub(self.failed_attempts()))
+    }
+
+    /// check the password of the user. A success resets the count of failed attempts.
+    pub fn authenticate(
+        &self,
+        user: impl Into<String>,
+        password: impl Into<String>,
+    ) -> impl Future<Output = Result<(), AuthError>> + Send + 'static {
+        let this = self.clone();
+        let user = user.into();
+        let password = password.into();
+        async move {
+            if this.remaining_retries() == Some(0) {
+                return Err(AuthError::TooManyAttempts);
+            }
+            let (sender, receiver) = oneshot::channel();
+            let service = this.service.clone();
+            let config_dir = this.config_dir.clone();
+            let fail_delay = this.fail_delay;
+            std::thread::spawn(move || {
+                let result = authenticate_blocking(&service, config_dir.as_deref(), user, password);
+                if matches!(result, Err(AuthError::Failed)) {
+                    std::thread::sleep(fail_delay);
+                }
+                sender.send(result).ok();
+            });
+            let result = receiver
+                .await
+                .unwrap_or_else(|_| Err(AuthError::Pam("the pam thread panicked".to_owned())));
+
+            let mut failures = this.failures.lock().unwrap();
+            match result {
+                Ok(()) => *failures = 0,
+                Err(AuthError::Failed) => {
+                    *failures += 1;
+                    if this.max_retries.is_some_and(|max| *failures >= max) {
+                        return Err(AuthError::TooManyAttempts);
+                    }
+                }
+                Err(_) => {}
+            }
+            result
+        }
+    }
+
+    /// check the password of the user, and unlock the session on success
+    pub fn unlock<Message>(
+        &self,
+        user: impl Into<String>,
+        password: impl Into<String>,
+        on_failure: impl Fn(AuthError) -> Message + Send + 'static,
+    ) -> Task<Message>
+    where
+        Message: From<UnLockAction> + Send + 'static,
+    {
+        Task::perform(
+            self.authenticate(user, password),
+            move |result| match result {
+                Ok(()) => UnLockAction.into(),
+                Err(error) => on_failure(error),
+            },
+        )
+    }
+}
+
+const PAM_SUCCESS: c_int = 0;
+const PAM_BUF_ERR: c_int = 5;
+const PAM_AUTH_ERR: c_int = 7;
+const PAM_CRED_INSUFFICIENT: c_int = 8;
+const PAM_USER_UNKNOWN: c_int = 10;
+const PAM_MAXTRIES: c_int = 11;
+const PAM_CONV_ERR: c_int = 19;
+
+const PAM_PROMPT_ECHO_OFF: c_int = 1;
+const PAM_PROMPT_ECHO_ON: c_int = 2;
+const PAM_ERROR_MSG: c_int = 3;
+const PAM_TEXT_INFO: c_int = 4;
+
+const PAM_REFRESH_CRED: c_int = 0x0010;
+
+#[repr(C)]
+struct PamHandle {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct PamMessage {
+    msg_style: c_int,
+    msg: *const c_char,
+}
+
+#[repr(C)]
+struct PamResponse {
+    resp: *mut c_char,
+    resp_retcode: c_int,
+}
+
+type ConvFn =
+    extern "C" fn(c_int, *mut *const PamMessage, *mut *mut PamResponse, *mut c_void) -> c_int;
+
+#[repr(C)]
+struct PamConv {
+    conv: Option<ConvFn>,
+    appdata_ptr: *mut c_void,
+}
+
+#[link(name = "pam")]
+extern "C" {
+    fn pam_start(
+        service_name: *const c_char,
+        user: *const c_char,
+        pam_conversation: *const PamConv,
+        pamh: *mut *mut PamHandle,
+    ) -> c_int;
+    fn pam_authenticate(pamh: *mut PamHandle, flags: c_int) -> c_int;
+    fn pam_setcred(pamh: *mut PamHandle, flags: c_int) -> c_int;
+    fn pam_end(pamh: *mut PamHandle, pam_status: c_int) -> c_int;
+    fn pam_strerror(pamh: *mut PamHandle, errnum: c_int) -> *const c_char;
+}
+
+type StartConfdirFn = unsafe extern "C" fn(
+    service_name: *const c_char,
+    user: *const c_char,
+    pam_conversation: *const PamConv,
+    confdir: *const c_char,
+    pamh: *mut *mut PamHandle,
+) -> c_int;
+
+/// pam_start_confdir only exists since Linux-PAM 1.4, so it is looked up at runtime, and the
+/// crate still links against older pam libraries
+fn pam_start_confdir() -> Option<StartConfdirFn> {
+    // SAFETY: the name is nul terminated, and libpam is linked
+    let symbol = unsafe { libc::dlsym(libc::RTLD_DEFAULT, c"pam_start_confdir".as_ptr()) };
+    if symbol.is_null() {
+        return None;
+    }
+    // SAFETY: the symbol is pam_start_confdir, which has this signature
+    Some(unsafe { std::mem::transmute::<*mut c_void, StartConfdirFn>(symbol) })
+}
+
+/// the answers to the prompts of the conversation
+struct Credentials {
+    user: CString,
+    password: CString,
+}
+
+impl Drop for Credentials {
+    fn drop(&mut self) {
+        // NOTE: do not leave the password in the memory
+        let mut password = std::mem::take(&mut self.password).into_bytes_with_nul();
+        for byte in password.iter_mut() {
+            // SAFETY: the byte is valid, volatile so the write is not optimized out
+            unsafe { ptr::write_volatile(byte, 0) };
+        }
+    }
+}
+
+fn authenticate_blocking(
+    service: &str,
+    config_dir: Option<&Path>,
+    user: String,
+    password: String,
+) -> Result<(), AuthError> {
+    let service = CString::new(service).map_err(|_| AuthError::InvalidInput)?;
+    let config_dir = config_dir
+        .map(|dir| CString::new(dir.as_os_str().as_bytes()))
+        .transpose()
+        .map_err(|_| AuthError::InvalidInput)?;
+    let credentials = Credentials {
+        user: CString::new(user).map_err(|_| AuthError::InvalidInput)?,
+        password: CString::new(password).map_err(|_| AuthError::InvalidInput)?,
+    };
+    let conversation = PamConv {
+        conv: Some(conversation),
+        appdata_ptr: &credentials as *const Credentials as *mut c_void,
+    };
+
+    let start_confdir = match config_dir {
+        Some(_) => Some(pam_start_confdir().ok_or_else(|| {
+            AuthError::Pam("pam_start_confdir is not supported by this libpam".to_owned())
+        })?),
+        None => None,
+    };
+
+    let mut handle = ptr::null_mut();
+    // SAFETY: all the pointers are valid until pam_end
+    let ret = unsafe {
+        match (config_dir.as_ref(), start_confdir) {
+            (Some(dir), Some(pam_start_confdir)) => pam_start_confdir(
+                service.as_ptr(),
+                credentials.user.as_ptr(),
+                &conversation,
+                dir.as_ptr(),
+                &mut handle,
+            ),
+            _ => pam_start(
+                service.as_ptr(),
+                credentials.user.as_ptr(),
+                &conversation,
+                &mut handle,
+            ),
+        }
+    };
+    if ret != PAM_SUCCESS {
+        return Err(AuthError::Pam(pam_error(handle, ret)));
+    }
+
+    // SAFETY: the handle is started
+    let ret = unsafe { pam_authenticate(handle, 0) };
+    if ret == PAM_SUCCESS {
+        // NOTE: like other lock screens, refresh the credentials, like kerberos tickets
+        let cred_ret = unsafe { pam_setcred(handle, PAM_REFRESH_CRED) };
+        if cred_ret != PAM_SUCCESS {
+            log::warn!("pam_setcred failed: {}", pam_error(handle, cred_ret));
+        }
+    }
+    let result = match ret {
+        PAM_SUCCESS => Ok(()),
+        PAM_AUTH_ERR | PAM_CRED_INSUFFICIENT | PAM_USER_UNKNOWN => Err(AuthError::Failed),
+        PAM_MAXTRIES => Err(AuthError::TooManyAttempts),
+        _ => Err(AuthError::Pam(pam_error(handle, ret))),
+    };
+    // SAFETY: the handle is not used after it
+    unsafe { pam_end(handle, ret) };
+    result
+}
+
+fn pam_error(handle: *mut PamHandle, errnum: c_int) -> String {
+    // SAFETY: pam_strerror returns a static string
+    let message = unsafe { pam_strerror(handle, errnum) };
+    if message.is_null() {
+        return format!("unknown error {errnum}");
+    }
+    unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// answer the prompts of the pam modules, the password for the hidden ones and the user for the
+/// visible ones. The responses are allocated with malloc, pam frees them.
+extern "C" fn conversation(
+    num_msg: c_int,
+    msg: *mut *const PamMessage,
+    resp: *mut *mut PamResponse,
+    appdata_ptr: *mut c_void,
+) -> c_int {
+    if num_msg <= 0 || msg.is_null() || resp.is_null() || appdata_ptr.is_null() {
+        return PAM_CONV_ERR;
+    }
+    let count = num_msg as usize;
+    // SAFETY: appdata_ptr is the Credentials passed to pam_start
+    let credentials = unsafe { &*(appdata_ptr as *const Credentials) };
+    // SAFETY: calloc returns zeroed memory, so every resp is null
+    let responses =
+        unsafe { libc::calloc(count, std::mem::size_of::<PamResponse>()) } as *mut PamResponse;
+    if responses.is_null() {
+        return PAM_BUF_ERR;
+    }
+
+    for index in 0..count {
+        // SAFETY: pam passes num_msg messages
+        let message = unsafe { &**msg.add(index) };
+        let answer = match message.msg_style {
+            PAM_PROMPT_ECHO_OFF => Some(&credentials.password),
+            PAM_PROMPT_ECHO_ON => Some(&credentials.user),
+            PAM_ERROR_MSG | PAM_TEXT_INFO => {
+                if !message.msg.is_null() {
+                    let text = unsafe { CStr::from_ptr(message.msg) };
+                    log::info!("pam: {}", text.to_string_lossy());
+                }
+                None
+            }
+            _ => {
+                free_responses(responses, count);
+                return PAM_CONV_ERR;
+            }
+        };
+        let Some(answer) = answer else {
+            continue;
+        };
+        let copy = unsafe { libc::strdup(answer.as_ptr()) };
+        if copy.is_null() {
+            free_responses(responses, count);
+            return PAM_BUF_ERR;
+        }
+        unsafe { (*responses.add(index)).resp = copy };
+    }
+    unsafe { *resp = responses };
+    PAM_SUCCESS
+}
+
+fn free_responses(responses: *mut PamResponse, count: usize) {
+    for index in 0..count {
+        unsafe { libc::free((*responses.add(index)).resp as *mut c_void) };
+    }
+    unsafe { libc::free(responses as *mut c_void) };
+}
@@ -1,7 +1,17 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, fs::File, sync::Arc};
 
 use iced::{Font, Pixels};
 
+use sessionlockev::reexport::wayland_client::wl_keyboard::KeymapFormat;
+
+/// the keymap of the virtual keyboard, so the lock screen can type with an on-screen keypad
+#[derive(Debug)]
+pub struct VirtualKeyboardSettings {
+    pub file: File,
+    pub keymap_size: u32,
+    pub keymap_format: KeymapFormat,
+}
+
 #[derive(Debug, Clone)]
 pub struct Settings<Flags> {
     /// The identifier of the application.
@@ -37,6 +47,8 @@ pub struct Settings<Flags> {
     /// By default, it is disabled.
     ///
     pub antialiasing: bool,
+
+    pub virtual_keyboard_support: Option<Arc<VirtualKeyboardSettings>>,
 }
 
 impl<Flags> Default for Settings<Flags>
@@ -51,6 +63,7 @@ where
             default_font: Font::default(),
             default_text_size: Pixels(16.0),
             antialiasing: false,
+            virtual_keyboard_support: None,
         }
     }
 }
@@ -11,3 +11,82 @@ fn test_macro() {
     let e = TestEnum::UnLock;
     let _ = e.clone();
 }
+
+#[test]
+fn test_macro_from_unlock() {
+    use iced_sessionlock::actions::UnLockAction;
+
+    #[allow(dead_code)]
+    #[to_session_message]
+    #[derive(Debug, Clone)]
+    enum TestEnum {
+        TestA,
+    }
+    let e: TestEnum = UnLockAction.into();
+    assert!(matches!(e, TestEnum::UnLock));
+}
+
+#[test]
+fn test_macro_try_into_unlock() {
+    use iced_sessionlock::actions::{NotUnLock, UnLockAction};
+
+    #[allow(dead_code)]
+    #[to_session_message]
+    #[derive(Debug, Clone)]
+    enum TestEnum {
+        TestA,
+    }
+    let unlock: Result<UnLockAction, _> = TestEnum::UnLock.try_into();
+    assert!(unlock.is_ok());
+    let unlock: Result<UnLockAction, _> = TestEnum::TestA.try_into();
+    assert!(matches!(unlock, Err(NotUnLock(TestEnum::TestA))));
+}
+
+#[test]
+fn test_macro_virtual_keyboard() {
+    use iced_sessionlock::actions::{SessionLockCustomActions, SessionLockMessage};
+
+    #[allow(dead_code)]
+    #[to_session_message]
+    #[derive(Debug, Clone)]
+    enum TestEnum {
+        TestA,
+    }
+    let action = TestEnum::VirtualKeyboardPressed { time: 10, key: 2 }.try_into_action();
+    assert!(matches!(
+        action,
+        Ok(SessionLockCustomActions::VirtualKeyboardPressed { time: 10, key: 2 })
+    ));
+    let action = TestEnum::TestA.try_into_action();
+    assert!(matches!(action, Err(TestEnum::TestA)));
+}
+
+#[test]
+fn test_unlock_action_message() {
+    use iced_sessionlock::actions::{SessionLockCustomActions, SessionLockMessage, UnLockAction};
+
+    #[derive(Debug)]
+    enum Message {
+        UnLock,
+        TestA,
+    }
+
+    impl TryInto<UnLockAction> for Message {
+        type Error = Self;
+        fn try_into(self) -> Result<UnLockAction, Self::Error> {
+            if let Self::UnLock = self {
+                return Ok(UnLockAction);
+            }
+            Err(self)
+        }
+    }
+
+    assert!(matches!(
+        Message::UnLock.try_into_action(),
+        Ok(SessionLockCustomActions::UnLock)
+    ));
+    assert!(matches!(
+        Message::TestA.try_into_action(),
+        Err(Message::TestA)
+    ));
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "pam")]
+
+use std::{path::PathBuf, time::Duration};
+
+use iced_sessionlock::pam::{AuthError, Authenticator};
+
+fn test_authenticator(service: &str) -> Authenticator {
+    Authenticator::new(service)
+        .with_config_dir(Some(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/pam"),
+        ))
+        .with_fail_delay(Duration::ZERO)
+}
+
+#[test]
+fn test_pam_permit() {
+    let authenticator = test_authenticator("iced-sessionlock-permit");
+    let result = futures::executor::block_on(authenticator.authenticate("user", "password"));
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn test_pam_retries() {
+    let authenticator = test_authenticator("iced-sessionlock-deny").with_max_retries(Some(2));
+    let result = futures::executor::block_on(authenticator.authenticate("user", "password"));
+    assert_eq!(result, Err(AuthError::Failed));
+    assert_eq!(authenticator.remaining_retries(), Some(1));
+    let result = futures::executor::block_on(authenticator.authenticate("user", "password"));
+    assert_eq!(result, Err(AuthError::TooManyAttempts));
+    let result = futures::executor::block_on(authenticator.authenticate("user", "password"));
+    assert_eq!(result, Err(AuthError::TooManyAttempts));
+}
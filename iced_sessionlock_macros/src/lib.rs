@@ -23,28 +23,64 @@ pub fn to_session_message(
     let variants = data.take_enum().unwrap();
 
     let unlock_action: Path = syn::parse_quote!(iced_sessionlock::actions::UnLockAction);
+    let custom_actions: Path =
+        syn::parse_quote!(iced_sessionlock::actions::SessionLockCustomActions);
+    let session_lock_message: Path =
+        syn::parse_quote!(iced_sessionlock::actions::SessionLockMessage);
+    let not_unlock: Path = syn::parse_quote!(iced_sessionlock::actions::NotUnLock);
 
     let try_into = quote! {
+        impl #impl_gen #session_lock_message for #ident #ty_gen #where_gen {
+            fn try_into_action(self) -> Result<#custom_actions, Self> {
+                match self {
+                    Self::UnLock => Ok(#custom_actions::UnLock),
+                    Self::VirtualKeyboardPressed { time, key } => {
+                        Ok(#custom_actions::VirtualKeyboardPressed { time, key })
+                    }
+                    _ => Err(self)
+                }
+            }
+        }
+    };
+
+    // NOTE: the error is not Self, else the blanket SessionLockMessage would apply too
+    let try_into_unlock = quote! {
         impl #impl_gen TryInto<#unlock_action> for #ident #ty_gen #where_gen {
-            type Error = Self;
+            type Error = #not_unlock<Self>;
 
             fn try_into(self) -> Result<#unlock_action, Self::Error> {
                 match self {
                     Self::UnLock => Ok(#unlock_action),
-                    _ => Err(self)
+                    _ => Err(#not_unlock(self))
                 }
             }
         }
     };
 
+    let from_unlock = quote! {
+        impl #impl_gen From<#unlock_action> for #ident #ty_gen #where_gen {
+            fn from(_: #unlock_action) -> Self {
+                Self::UnLock
+            }
+        }
+    };
+
     Ok(quote! {
         #(#attrs)*
         #vis enum #ident #ty_gen #where_gen {
             #(#variants,)*
-            UnLock
+            UnLock,
+            VirtualKeyboardPressed {
+                time: u32,
+                key: u32,
+            },
         }
 
         #try_into
+
+        #try_into_unlock
+
+        #from_unlock
     })
 }
 
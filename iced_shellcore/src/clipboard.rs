@@ -1,8 +1,9 @@
 use iced_core::clipboard::Kind;
 use iced_core::Clipboard;
-use sessionlockev::WindowWrapper;
+use rwh_06::HasDisplayHandle;
 
-pub struct SessionLockClipboard {
+/// the clipboard of the windows
+pub struct ShellClipboard {
     state: State,
 }
 
@@ -11,9 +12,9 @@ enum State {
     Unavailable,
 }
 
-impl SessionLockClipboard {
+impl ShellClipboard {
     /// Creates a new [`Clipboard`] for the given window.
-    pub fn connect(window: &WindowWrapper) -> Self {
+    pub fn connect<W: HasDisplayHandle>(window: &W) -> Self {
         #[allow(unsafe_code)]
         let state = unsafe { window_clipboard::Clipboard::connect(window) }
             .ok()
@@ -64,7 +65,7 @@ impl SessionLockClipboard {
     }
 }
 
-impl Clipboard for SessionLockClipboard {
+impl Clipboard for ShellClipboard {
     fn read(&self, kind: Kind) -> Option<String> {
         self.read(kind)
     }
@@ -1,16 +1,16 @@
-mod keymap;
+pub mod keymap;
 
 use crate::event::IcedButtonState;
-use crate::event::WindowEvent as LayerShellEvent;
+use crate::event::WindowEvent;
 use iced::touch;
 use iced_core::SmolStr;
 use iced_core::{keyboard, mouse, Event as IcedEvent};
 use keymap::{key, physical_key};
-use layershellev::keyboard::KeyLocation;
-use layershellev::keyboard::ModifiersState;
-use layershellev::xkb_keyboard::ElementState;
-use layershellev::xkb_keyboard::KeyEvent as LayerShellKeyEvent;
 use std::ops::Mul;
+use waycrate_xkbkeycode::keyboard::KeyLocation;
+use waycrate_xkbkeycode::keyboard::ModifiersState;
+use waycrate_xkbkeycode::xkb_keyboard::ElementState;
+use waycrate_xkbkeycode::xkb_keyboard::KeyEvent;
 
 fn scale_down<T>((x, y): (T, T), scale_factor: f64) -> (T, T)
 where
@@ -24,14 +24,15 @@ where
     (x.try_into().unwrap(), y.try_into().unwrap())
 }
 
+/// convert the window event into the iced event
 pub fn window_event(
-    layerevent: &LayerShellEvent,
+    event: &WindowEvent,
     scale_factor: f64,
     modifiers: ModifiersState,
 ) -> Option<IcedEvent> {
-    match layerevent {
-        LayerShellEvent::CursorLeft => Some(IcedEvent::Mouse(mouse::Event::CursorLeft)),
-        LayerShellEvent::CursorMoved { x, y } => {
+    match event {
+        WindowEvent::CursorLeft => Some(IcedEvent::Mouse(mouse::Event::CursorLeft)),
+        WindowEvent::CursorMoved { x, y } => {
             let (x, y) = scale_down((*x, *y), scale_factor);
             Some(IcedEvent::Mouse(mouse::Event::CursorMoved {
                 position: iced_core::Point {
@@ -40,27 +41,25 @@ pub fn window_event(
                 },
             }))
         }
-        LayerShellEvent::CursorEnter { .. } => Some(IcedEvent::Mouse(mouse::Event::CursorEntered)),
-        LayerShellEvent::MouseInput(state) => Some(IcedEvent::Mouse(match state {
+        WindowEvent::CursorEnter { .. } => Some(IcedEvent::Mouse(mouse::Event::CursorEntered)),
+        WindowEvent::MouseInput(state) => Some(IcedEvent::Mouse(match state {
             IcedButtonState::Pressed(btn) => mouse::Event::ButtonPressed(*btn),
             IcedButtonState::Released(btn) => mouse::Event::ButtonReleased(*btn),
         })),
-        LayerShellEvent::Axis { x, y } => Some(IcedEvent::Mouse(mouse::Event::WheelScrolled {
+        WindowEvent::Axis { x, y } => Some(IcedEvent::Mouse(mouse::Event::WheelScrolled {
             delta: mouse::ScrollDelta::Lines { x: *x, y: *y },
         })),
 
-        LayerShellEvent::PixelDelta { x, y } => {
-            Some(IcedEvent::Mouse(mouse::Event::WheelScrolled {
-                delta: mouse::ScrollDelta::Pixels { x: *x, y: *y },
-            }))
-        }
-        LayerShellEvent::KeyBoardInput { event, .. } => Some(IcedEvent::Keyboard({
+        WindowEvent::PixelDelta { x, y } => Some(IcedEvent::Mouse(mouse::Event::WheelScrolled {
+            delta: mouse::ScrollDelta::Pixels { x: *x, y: *y },
+        })),
+        WindowEvent::KeyBoardInput { event, .. } => Some(IcedEvent::Keyboard({
             let key = event.key_without_modifiers();
             let text = event
                 .text_with_all_modifiers()
                 .map(SmolStr::new)
                 .filter(|text| !text.as_str().chars().any(is_private_use));
-            let LayerShellKeyEvent {
+            let KeyEvent {
                 state,
                 location,
                 logical_key,
@@ -96,7 +95,7 @@ pub fn window_event(
                 },
             }
         })),
-        LayerShellEvent::TouchDown { id, x, y } => {
+        WindowEvent::TouchDown { id, x, y } => {
             let (x, y) = scale_down((*x, *y), scale_factor);
             Some(IcedEvent::Touch(touch::Event::FingerPressed {
                 id: touch::Finger(*id as u64),
@@ -106,7 +105,7 @@ pub fn window_event(
                 },
             }))
         }
-        LayerShellEvent::TouchUp { id, x, y } => {
+        WindowEvent::TouchUp { id, x, y } => {
             let (x, y) = scale_down((*x, *y), scale_factor);
             Some(IcedEvent::Touch(touch::Event::FingerLifted {
                 id: touch::Finger(*id as u64),
@@ -116,7 +115,7 @@ pub fn window_event(
                 },
             }))
         }
-        LayerShellEvent::TouchMotion { id, x, y } => {
+        WindowEvent::TouchMotion { id, x, y } => {
             let (x, y) = scale_down((*x, *y), scale_factor);
             Some(IcedEvent::Touch(touch::Event::FingerMoved {
                 id: touch::Finger(*id as u64),
@@ -126,7 +125,7 @@ pub fn window_event(
                 },
             }))
         }
-        LayerShellEvent::TouchCancel { id, x, y } => {
+        WindowEvent::TouchCancel { id, x, y } => {
             let (x, y) = scale_down((*x, *y), scale_factor);
             Some(IcedEvent::Touch(touch::Event::FingerLost {
                 id: touch::Finger(*id as u64),
@@ -136,18 +135,20 @@ pub fn window_event(
                 },
             }))
         }
-        LayerShellEvent::ModifiersChanged(new_modifiers) => Some(IcedEvent::Keyboard(
+        WindowEvent::ModifiersChanged(new_modifiers) => Some(IcedEvent::Keyboard(
             keyboard::Event::ModifiersChanged(keymap::modifiers(*new_modifiers)),
         )),
-        LayerShellEvent::Unfocus => Some(IcedEvent::Window(iced::window::Event::Unfocused)),
-        LayerShellEvent::Focused => Some(IcedEvent::Window(iced::window::Event::Focused)),
+        WindowEvent::Unfocus => Some(IcedEvent::Window(iced::window::Event::Unfocused)),
+        WindowEvent::Focused => Some(IcedEvent::Window(iced::window::Event::Focused)),
         _ => None,
     }
 }
 
-pub(crate) fn mouse_interaction(interaction: mouse::Interaction) -> String {
-    use layershellev::reexport::wp_cursor_shape_device_v1::{Shape, ShapeName};
+/// the name of the cursor shape of the interaction
+pub fn mouse_interaction(interaction: mouse::Interaction) -> String {
     use mouse::Interaction;
+    use waycrate_shellcore::strtoshape::ShapeName;
+    use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::Shape;
     match interaction {
         Interaction::None => Shape::Default.name().to_owned(),
         Interaction::Idle => Shape::Wait.name().to_owned(),
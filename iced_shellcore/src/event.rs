@@ -0,0 +1,107 @@
+use iced::mouse;
+use iced_core::keyboard::Modifiers as IcedModifiers;
+use waycrate_xkbkeycode::{keyboard::ModifiersState, xkb_keyboard::KeyEvent};
+use wayland_client::{
+    protocol::{wl_keyboard::KeyState, wl_pointer::ButtonState},
+    WEnum,
+};
+
+fn from_u32_to_icedmouse(code: u32) -> mouse::Button {
+    match code {
+        273 => mouse::Button::Right,
+        _ => mouse::Button::Left,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum IcedButtonState {
+    Pressed(mouse::Button),
+    Released(mouse::Button),
+}
+
+impl IcedButtonState {
+    /// the state of the `wl_pointer` button with the linux button code
+    pub fn new(state: WEnum<ButtonState>, button: u32) -> Self {
+        let button = from_u32_to_icedmouse(button);
+        match state {
+            WEnum::Value(ButtonState::Pressed) => Self::Pressed(button),
+            WEnum::Value(ButtonState::Released) => Self::Released(button),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum IcedKeyState {
+    Pressed,
+    Released,
+}
+
+impl From<WEnum<KeyState>> for IcedKeyState {
+    fn from(value: WEnum<KeyState>) -> Self {
+        match value {
+            WEnum::Value(KeyState::Released) => Self::Released,
+            WEnum::Value(KeyState::Pressed) => Self::Pressed,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// the events of a window, the frontends turn the messages of their event loop into it
+#[derive(Debug, Clone)]
+pub enum WindowEvent {
+    ScaleFactorChanged {
+        scale_u32: u32,
+        scale_float: f64,
+    },
+    CursorEnter {
+        x: f64,
+        y: f64,
+    },
+    CursorMoved {
+        x: f64,
+        y: f64,
+    },
+    CursorLeft,
+    MouseInput(IcedButtonState),
+    Keyboard {
+        state: IcedKeyState,
+        key: u32,
+        modifiers: IcedModifiers,
+    },
+    KeyBoardInput {
+        event: KeyEvent,
+        is_synthetic: bool,
+    },
+    Unfocus,
+    Focused,
+    ModifiersChanged(ModifiersState),
+    Axis {
+        x: f32,
+        y: f32,
+    },
+    PixelDelta {
+        x: f32,
+        y: f32,
+    },
+    TouchDown {
+        id: i32,
+        x: f64,
+        y: f64,
+    },
+    TouchUp {
+        id: i32,
+        x: f64,
+        y: f64,
+    },
+    TouchMotion {
+        id: i32,
+        x: f64,
+        y: f64,
+    },
+    TouchCancel {
+        id: i32,
+        x: f64,
+        y: f64,
+    },
+}
@@ -0,0 +1,45 @@
+//! The iced integration shared by iced_layershell and iced_sessionlock.
+//!
+//! Both frontends turn the messages of their event loop into [event::WindowEvent], then into
+//! iced events with [conversion::window_event], and keep their windows in a
+//! [multi_window::WindowManager], so the conversion and the window management cannot drift
+//! between them.
+pub mod clipboard;
+pub mod conversion;
+pub mod event;
+pub mod multi_window;
+pub mod proxy;
+
+use iced::{Color, Theme};
+
+/// The appearance of a program.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Appearance {
+    /// The background [`Color`] of the application.
+    pub background_color: Color,
+
+    /// The default text [`Color`] of the application.
+    pub text_color: Color,
+}
+
+/// The default style of an application.
+pub trait DefaultStyle {
+    /// Returns the default style of a [`Appearance`].
+    fn default_style(&self) -> Appearance;
+}
+
+impl DefaultStyle for Theme {
+    fn default_style(&self) -> Appearance {
+        default(self)
+    }
+}
+
+/// The default [`Appearance`] of an application with the built-in [`Theme`].
+pub fn default(theme: &Theme) -> Appearance {
+    let palette = theme.extended_palette();
+
+    Appearance {
+        background_color: palette.background.base.color,
+        text_color: palette.background.base.text,
+    }
+}
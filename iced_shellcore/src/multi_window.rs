@@ -0,0 +1,9 @@
+mod action;
+mod state;
+mod user_interface;
+mod window_manager;
+
+pub use action::{run_action, ShellAction, UnsupportedWindowAction};
+pub use state::State;
+pub use user_interface::{build_user_interface, build_user_interfaces};
+pub use window_manager::{Window, WindowManager};
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use iced::window::Id as IcedId;
+use iced_core::{widget::operation, Size};
+use iced_graphics::Compositor;
+use iced_runtime::{
+    clipboard, multi_window::Program, user_interface, window, window::Action as WinowAction,
+    Action, Debug,
+};
+
+use super::{build_user_interfaces, WindowManager};
+use crate::clipboard::ShellClipboard;
+
+/// The actions which [run_action] leaves to the frontend, as they depend on the shell
+#[derive(Debug)]
+pub enum ShellAction<Message> {
+    /// the output of a task, it is either a message of the program or a custom action of the
+    /// shell
+    Output(Message),
+    /// the program asked to exit
+    Exit,
+    /// close the window
+    Close(IcedId),
+    /// resize the window to the logical size
+    Resize(IcedId, Size),
+    /// let the pointer events pass through the window when it is true, catch them again when
+    /// it is false
+    MousePassthrough(IcedId, bool),
+}
+
+/// A window action which has no equivalent for the surfaces of the shell, with its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedWindowAction(pub &'static str);
+
+/// Runs the [`Action`] which is the same for every shell: the clipboard, the widget operations,
+/// the window queries and the fonts. The other actions are returned as a [ShellAction], and the
+/// window actions the shell cannot do as an [UnsupportedWindowAction].
+#[allow(clippy::too_many_arguments)]
+pub fn run_action<P, Id, C>(
+    program: &P,
+    compositor: &mut C,
+    action: Action<P::Message>,
+    clipboard: &mut ShellClipboard,
+    debug: &mut Debug,
+    window_manager: &mut WindowManager<Id, P::Theme, C>,
+    ui_caches: &mut HashMap<IcedId, user_interface::Cache>,
+) -> Result<Option<ShellAction<P::Message>>, UnsupportedWindowAction>
+where
+    P: Program,
+    Id: Ord + Copy,
+    C: Compositor<Renderer = P::Renderer> + 'static,
+{
+    match action {
+        Action::Output(message) => return Ok(Some(ShellAction::Output(message))),
+        Action::Clipboard(action) => match action {
+            clipboard::Action::Read { target, channel } => {
+                let _ = channel.send(clipboard.read(target));
+            }
+            clipboard::Action::Write { target, contents } => {
+                clipboard.write(target, contents);
+            }
+        },
+        Action::Widget(action) => {
+            let mut current_operation = Some(action);
+
+            let mut uis =
+                build_user_interfaces(program, debug, window_manager, std::mem::take(ui_caches));
+
+            'operate: while let Some(mut operation) = current_operation.take() {
+                for (id, ui) in uis.iter_mut() {
+                    if let Some(window) = window_manager.get_mut(*id) {
+                        ui.operate(&window.renderer, operation.as_mut());
+
+                        match operation.finish() {
+                            operation::Outcome::None => {}
+                            operation::Outcome::Some(_message) => {
+                                // operation completed, don't need to try to operate on rest of UIs
+                                break 'operate;
+                            }
+                            operation::Outcome::Chain(next) => {
+                                current_operation = Some(next);
+                            }
+                        }
+                    }
+                }
+            }
+
+            *ui_caches = uis.drain().map(|(id, ui)| (id, ui.into_cache())).collect();
+        }
+        Action::Window(action) => match action {
+            WinowAction::Close(id) => return Ok(Some(ShellAction::Close(id))),
+            WinowAction::Resize(id, size) => return Ok(Some(ShellAction::Resize(id, size))),
+            WinowAction::EnableMousePassthrough(id) => {
+                return Ok(Some(ShellAction::MousePassthrough(id, true)))
+            }
+            WinowAction::DisableMousePassthrough(id) => {
+                return Ok(Some(ShellAction::MousePassthrough(id, false)))
+            }
+            WinowAction::Screenshot(id, channel) => {
+                let Some(window) = window_manager.get_mut(id) else {
+                    return Ok(None);
+                };
+                let bytes = compositor.screenshot(
+                    &mut window.renderer,
+                    &mut window.surface,
+                    window.state.viewport(),
+                    window.state.background_color(),
+                    &debug.overlay(),
+                );
+
+                let _ = channel.send(window::Screenshot::new(
+                    bytes,
+                    window.state.physical_size(),
+                    window.state.viewport().scale_factor(),
+                ));
+            }
+            WinowAction::GetSize(id, channel) => {
+                if let Some(window) = window_manager.get(id) {
+                    let _ = channel.send(window.state.logical_size());
+                }
+            }
+            WinowAction::GetScaleFactor(id, channel) => {
+                if let Some(window) = window_manager.get(id) {
+                    let _ = channel.send(window.state.viewport().scale_factor() as f32);
+                }
+            }
+            WinowAction::GetOldest(channel) => {
+                let _ = channel.send(window_manager.first_window().map(|(id, _)| *id));
+            }
+            WinowAction::GetLatest(channel) => {
+                let _ = channel.send(window_manager.last_window().map(|(id, _)| *id));
+            }
+            // NOTE: a shell surface is always shown as it is, it cannot be maximized, minimized
+            // or moved, and it does not know its position
+            WinowAction::GetMode(id, channel) => {
+                if window_manager.get(id).is_some() {
+                    let _ = channel.send(iced_core::window::Mode::Windowed);
+                }
+            }
+            WinowAction::GetMaximized(id, channel) => {
+                if window_manager.get(id).is_some() {
+                    let _ = channel.send(false);
+                }
+            }
+            WinowAction::GetMinimized(id, channel) => {
+                if window_manager.get(id).is_some() {
+                    let _ = channel.send(None);
+                }
+            }
+            WinowAction::GetPosition(id, channel) => {
+                if window_manager.get(id).is_some() {
+                    let _ = channel.send(None);
+                }
+            }
+            WinowAction::Open(..) => return Err(UnsupportedWindowAction("open")),
+            WinowAction::Drag(_) => return Err(UnsupportedWindowAction("drag")),
+            WinowAction::Maximize(..) => return Err(UnsupportedWindowAction("maximize")),
+            WinowAction::Minimize(..) => return Err(UnsupportedWindowAction("minimize")),
+            WinowAction::Move(..) => return Err(UnsupportedWindowAction("move")),
+            WinowAction::ChangeMode(..) => return Err(UnsupportedWindowAction("change_mode")),
+            WinowAction::ToggleMaximize(_) => {
+                return Err(UnsupportedWindowAction("toggle_maximize"))
+            }
+            WinowAction::ToggleDecorations(_) => {
+                return Err(UnsupportedWindowAction("toggle_decorations"))
+            }
+            WinowAction::RequestUserAttention(..) => {
+                return Err(UnsupportedWindowAction("request_user_attention"))
+            }
+            WinowAction::GainFocus(_) => return Err(UnsupportedWindowAction("gain_focus")),
+            WinowAction::ChangeLevel(..) => return Err(UnsupportedWindowAction("change_level")),
+            WinowAction::ShowSystemMenu(_) => {
+                return Err(UnsupportedWindowAction("show_system_menu"))
+            }
+            WinowAction::GetRawId(..) => return Err(UnsupportedWindowAction("get_raw_id")),
+            WinowAction::ChangeIcon(..) => return Err(UnsupportedWindowAction("change_icon")),
+            WinowAction::RunWithHandle(..) => {
+                return Err(UnsupportedWindowAction("run_with_handle"))
+            }
+        },
+        Action::Exit => return Ok(Some(ShellAction::Exit)),
+        Action::LoadFont { bytes, channel } => {
+            // TODO: Error handling (?)
+            compositor.load_font(bytes.clone());
+
+            let _ = channel.send(Ok(()));
+        }
+        _ => {}
+    }
+    Ok(None)
+}
@@ -1,53 +1,49 @@
-use crate::multi_window::Application;
-use crate::{Appearance, DefaultStyle};
+use crate::Appearance;
 use iced_core::{mouse as IcedMouse, Color, Point, Size};
 use iced_graphics::Viewport;
-use layershellev::keyboard::ModifiersState;
-use layershellev::reexport::wp_viewport::WpViewport;
-use layershellev::WindowWrapper;
+use waycrate_xkbkeycode::keyboard::ModifiersState;
+use wayland_protocols::wp::viewporter::client::wp_viewport::WpViewport;
 
 use crate::event::WindowEvent;
-use iced::window;
 
-pub struct State<A: Application>
-where
-    A::Theme: DefaultStyle,
-{
-    id: window::Id,
+/// the state of a window, its viewport follows the size and the scale of the surface and the
+/// scale factor of the application
+pub struct State<Theme> {
     application_scale_factor: f64,
     wayland_scale_factor: f64,
     real_window_size: Size<u32>,
     viewport: Viewport,
     viewport_version: usize,
-    theme: A::Theme,
+    theme: Theme,
     appearance: Appearance,
     mouse_position: Option<Point>,
     modifiers: ModifiersState,
-    wpviewport: WpViewport,
+    /// without wp_viewporter the surface is not scaled, so the scale stays 1
+    wpviewport: Option<WpViewport>,
 }
 
-impl<A: Application> State<A>
-where
-    A::Theme: DefaultStyle,
-{
+impl<Theme> State<Theme> {
+    /// the application_scale_factor, the theme and the appearance come from the application,
+    /// the size and the wayland_scale_factor from the surface
     pub fn new(
-        id: window::Id,
-        application: &A,
+        application_scale_factor: f64,
+        theme: Theme,
+        appearance: Appearance,
         (width, height): (u32, u32),
         wayland_scale_factor: f64,
-        window: &WindowWrapper,
+        wpviewport: Option<WpViewport>,
     ) -> Self {
-        let application_scale_factor = application.scale_factor(id);
-        let theme = application.theme();
-        let appearance = application.style(&theme);
-
+        let wayland_scale_factor = if wpviewport.is_some() {
+            wayland_scale_factor
+        } else {
+            1.
+        };
         let real_window_size = Size::new(width, height);
         let viewport = Viewport::with_physical_size(
             real_window_size,
             wayland_scale_factor * application_scale_factor,
         );
         Self {
-            id,
             application_scale_factor,
             wayland_scale_factor,
             real_window_size,
@@ -57,10 +53,7 @@ where
             appearance,
             mouse_position: None,
             modifiers: ModifiersState::default(),
-            wpviewport: window
-                .viewport
-                .clone()
-                .expect("iced_layershell need viewport support to better wayland hidpi"),
+            wpviewport,
         }
     }
     pub fn modifiers(&self) -> ModifiersState {
@@ -72,6 +65,7 @@ where
     }
 
     pub fn update_view_port(&mut self, width: u32, height: u32, scale: f64) {
+        let scale = self.supported_scale(scale);
         let real_window_size = Size::new(width, height);
         if self.real_window_size == real_window_size && self.wayland_scale_factor == scale {
             return;
@@ -82,13 +76,27 @@ where
             self.adjusted_physical_size(),
             self.current_wayland_scale() * self.application_scale_factor,
         );
-        let logical_size = self.viewport.logical_size();
+        self.set_destination();
+        self.viewport_version = self.viewport_version.wrapping_add(1);
+    }
+
+    fn supported_scale(&self, scale: f64) -> f64 {
+        if self.wpviewport.is_some() {
+            scale
+        } else {
+            1.
+        }
+    }
 
-        self.wpviewport.set_destination(
+    fn set_destination(&self) {
+        let Some(wpviewport) = self.wpviewport.as_ref() else {
+            return;
+        };
+        let logical_size = self.viewport.logical_size();
+        wpviewport.set_destination(
             logical_size.width.ceil() as i32,
             logical_size.height.ceil() as i32,
         );
-        self.viewport_version = self.viewport_version.wrapping_add(1);
     }
 
     fn adjusted_physical_size(&self) -> Size<u32> {
@@ -123,7 +131,7 @@ where
         self.appearance.background_color
     }
 
-    pub fn theme(&self) -> &A::Theme {
+    pub fn theme(&self) -> &Theme {
         &self.theme
     }
 
@@ -155,26 +163,21 @@ where
                 scale_float,
                 scale_u32: _,
             } => {
-                self.wayland_scale_factor = *scale_float;
+                self.wayland_scale_factor = self.supported_scale(*scale_float);
                 self.viewport = Viewport::with_physical_size(
                     self.adjusted_physical_size(),
-                    self.application_scale_factor * scale_float,
+                    self.application_scale_factor * self.wayland_scale_factor,
                 );
 
                 self.viewport_version = self.viewport_version.wrapping_add(1);
-                let logical_size = self.viewport.logical_size();
-
-                self.wpviewport.set_destination(
-                    logical_size.width.ceil() as i32,
-                    logical_size.height.ceil() as i32,
-                );
+                self.set_destination();
             }
             _ => {}
         }
     }
 
-    pub fn synchronize(&mut self, application: &A) {
-        let new_scale_factor = application.scale_factor(self.id);
+    /// update the state with the scale factor, the theme and the appearance of the application
+    pub fn synchronize(&mut self, new_scale_factor: f64, theme: Theme, appearance: Appearance) {
         if self.application_scale_factor != new_scale_factor {
             self.application_scale_factor = new_scale_factor;
             self.viewport = Viewport::with_physical_size(
@@ -183,7 +186,7 @@ where
             );
             self.viewport_version = self.viewport_version.wrapping_add(1);
         }
-        self.theme = application.theme();
-        self.appearance = application.style(&self.theme);
+        self.theme = theme;
+        self.appearance = appearance;
     }
 }
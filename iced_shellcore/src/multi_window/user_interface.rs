@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use iced::window::Id as IcedId;
+use iced_core::Size;
+use iced_graphics::Compositor;
+use iced_runtime::{multi_window::Program, user_interface, Debug, UserInterface};
+
+use super::WindowManager;
+
+/// Builds the [`UserInterface`] of every window which has a cache
+#[allow(clippy::type_complexity)]
+pub fn build_user_interfaces<'a, P, Id, C>(
+    program: &'a P,
+    debug: &mut Debug,
+    window_manager: &mut WindowManager<Id, P::Theme, C>,
+    mut cached_user_interfaces: HashMap<IcedId, user_interface::Cache>,
+) -> HashMap<IcedId, UserInterface<'a, P::Message, P::Theme, P::Renderer>>
+where
+    P: Program,
+    Id: Ord + Copy,
+    C: Compositor<Renderer = P::Renderer>,
+{
+    cached_user_interfaces
+        .drain()
+        .filter_map(|(id, cache)| {
+            let window = window_manager.get_mut(id)?;
+
+            Some((
+                id,
+                build_user_interface(
+                    program,
+                    cache,
+                    &mut window.renderer,
+                    window.state.logical_size(),
+                    debug,
+                    id,
+                ),
+            ))
+        })
+        .collect()
+}
+
+/// Builds a [`UserInterface`] for the provided [`Program`], logging
+/// [`struct@Debug`] information accordingly.
+pub fn build_user_interface<'a, P: Program>(
+    program: &'a P,
+    cache: user_interface::Cache,
+    renderer: &mut P::Renderer,
+    size: Size,
+    debug: &mut Debug,
+    id: IcedId,
+) -> UserInterface<'a, P::Message, P::Theme, P::Renderer> {
+    debug.view_started();
+    let view = program.view(id);
+    debug.view_finished();
+
+    debug.layout_started();
+    let user_interface = UserInterface::build(view, size, cache, renderer);
+    debug.layout_finished();
+    user_interface
+}
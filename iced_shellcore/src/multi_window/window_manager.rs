@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use super::state::State;
+use iced_graphics::{compositor, Compositor};
+
+use iced::mouse;
+use iced::window::Id as IcedId;
+
+/// a window of the program, the id is the id of the surface in the event loop
+pub struct Window<Id, Theme, C>
+where
+    C: Compositor,
+{
+    pub id: Id,
+    pub renderer: C::Renderer,
+    pub surface: C::Surface,
+    pub state: State<Theme>,
+    pub mouse_interaction: mouse::Interaction,
+}
+
+/// the windows of the program, with the map between the iced ids and the ids of the surfaces
+pub struct WindowManager<Id, Theme, C>
+where
+    C: Compositor,
+{
+    aliases: BTreeMap<Id, IcedId>,
+    back_aliases: BTreeMap<IcedId, Id>,
+    entries: BTreeMap<IcedId, Window<Id, Theme, C>>,
+}
+
+impl<Id, Theme, C> Default for WindowManager<Id, Theme, C>
+where
+    Id: Ord + Copy,
+    C: Compositor,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id, Theme, C> WindowManager<Id, Theme, C>
+where
+    Id: Ord + Copy,
+    C: Compositor,
+{
+    pub fn new() -> Self {
+        Self {
+            aliases: BTreeMap::new(),
+            back_aliases: BTreeMap::new(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn remove(&mut self, id: IcedId) {
+        if let Some(shell_id) = self.back_aliases.remove(&id) {
+            self.aliases.remove(&shell_id);
+        }
+        self.entries.remove(&id);
+    }
+
+    /// create the surface of the window, shell_id is the id of the surface in the event loop
+    pub fn insert<W>(
+        &mut self,
+        id: IcedId,
+        shell_id: Id,
+        state: State<Theme>,
+        window: W,
+        compositor: &mut C,
+    ) -> &mut Window<Id, Theme, C>
+    where
+        W: compositor::Window + Clone,
+    {
+        let physical_size = state.physical_size();
+        let surface = compositor.create_surface(window, physical_size.width, physical_size.height);
+        let renderer = compositor.create_renderer();
+        let _ = self.aliases.insert(shell_id, id);
+        let _ = self.back_aliases.insert(id, shell_id);
+
+        let _ = self.entries.insert(
+            id,
+            Window {
+                id: shell_id,
+                renderer,
+                surface,
+                state,
+                mouse_interaction: mouse::Interaction::Idle,
+            },
+        );
+        self.entries
+            .get_mut(&id)
+            .expect("Get window that was just inserted")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (IcedId, &mut Window<Id, Theme, C>)> {
+        self.entries.iter_mut().map(|(k, v)| (*k, v))
+    }
+
+    pub fn first_window(&self) -> Option<(&IcedId, &Window<Id, Theme, C>)> {
+        self.entries.iter().next()
+    }
+
+    pub fn last_window(&self) -> Option<(&IcedId, &Window<Id, Theme, C>)> {
+        self.entries.iter().next_back()
+    }
+
+    pub fn get_mut_alias(&mut self, id: Id) -> Option<(IcedId, &mut Window<Id, Theme, C>)> {
+        let id = self.aliases.get(&id).copied()?;
+
+        Some((id, self.get_mut(id)?))
+    }
+
+    pub fn get_alias(&self, id: Id) -> Option<(IcedId, &Window<Id, Theme, C>)> {
+        let id = self.aliases.get(&id).copied()?;
+
+        Some((id, self.get(id)?))
+    }
+
+    /// the id of the surface of the window in the event loop
+    pub fn get_shell_id(&self, id: IcedId) -> Option<Id> {
+        self.back_aliases.get(&id).copied()
+    }
+
+    pub fn get_mut(&mut self, id: IcedId) -> Option<&mut Window<Id, Theme, C>> {
+        self.entries.get_mut(&id)
+    }
+
+    pub fn get(&self, id: IcedId) -> Option<&Window<Id, Theme, C>> {
+        self.entries.get(&id)
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs::File;
+use std::os::fd::AsFd;
+use std::time::Duration;
+
+use layershellev::calloop::{
+    channel,
+    timer::{TimeoutAction, Timer},
+    EventLoop,
+};
+use layershellev::keyboard::{KeyCode, PhysicalKey};
+use layershellev::reexport::*;
+use layershellev::*;
+
+enum Message {
+    Tick(u32),
+}
+
+fn main() {
+    let mut ev: WindowState<()> = WindowState::new("Hello")
+        .with_size((0, 400))
+        .with_layer(Layer::Top)
+        .with_anchor(Anchor::Bottom | Anchor::Left | Anchor::Right)
+        .with_keyboard_interacivity(KeyboardInteractivity::Exclusive)
+        .with_exclusize_zone(-1)
+        .build()
+        .unwrap();
+
+    // NOTE: the loop is owned by the program, other sources can be added to it
+    let mut event_loop: EventLoop<WindowState<()>> = EventLoop::try_new().unwrap();
+    let (sender, channel) = channel::channel::<Message>();
+
+    ev.insert_into_loop(
+        &event_loop.handle(),
+        Some(channel),
+        |event, _ev, _index| match event {
+            LayerEvent::RequestBuffer(file, shm, qh, init_w, init_h) => {
+                draw(file, (init_w, init_h));
+                let pool = shm.create_pool(file.as_fd(), (init_w * init_h * 4) as i32, qh, ());
+                ReturnData::WlBuffer(pool.create_buffer(
+                    0,
+                    init_w as i32,
+                    init_h as i32,
+                    (init_w * 4) as i32,
+                    wl_shm::Format::Argb8888,
+                    qh,
+                    (),
+                ))
+            }
+            LayerEvent::UserEvent(Message::Tick(count)) => {
+                println!("tick {count}");
+                ReturnData::None
+            }
+            LayerEvent::RequestMessages(DispatchMessage::KeyboardInput { event, .. }) => {
+                if let PhysicalKey::Code(KeyCode::Escape) = event.physical_key {
+                    ReturnData::RequestExit
+                } else {
+                    ReturnData::None
+                }
+            }
+            _ => ReturnData::None,
+        },
+    )
+    .unwrap();
+
+    let mut count = 0;
+    event_loop
+        .handle()
+        .insert_source(
+            Timer::from_duration(Duration::from_secs(1)),
+            move |_, _, _| {
+                count += 1;
+                sender.send(Message::Tick(count)).ok();
+                TimeoutAction::ToDuration(Duration::from_secs(1))
+            },
+        )
+        .unwrap();
+
+    while !ev.is_exit_requested() {
+        event_loop.dispatch(None, &mut ev).unwrap();
+        if let Some(e) = ev.take_dispatch_error() {
+            panic!("{e}");
+        }
+    }
+}
+
+fn draw(tmp: &mut File, (buf_x, buf_y): (u32, u32)) {
+    use std::{cmp::min, io::Write};
+    let mut buf = std::io::BufWriter::new(tmp);
+    for y in 0..buf_y {
+        for x in 0..buf_x {
+            let a = 0xFF;
+            let r = min(((buf_x - x) * 0xFF) / buf_x, ((buf_y - y) * 0xFF) / buf_y);
+            let g = min((x * 0xFF) / buf_x, ((buf_y - y) * 0xFF) / buf_y);
+            let b = min(((buf_x - x) * 0xFF) / buf_x, (y * 0xFF) / buf_y);
+
+            let color = (a << 24) + (r << 16) + (g << 8) + b;
+            buf.write_all(&color.to_ne_bytes()).unwrap();
+        }
+    }
+    buf.flush().unwrap();
+}
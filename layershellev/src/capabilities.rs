@@ -0,0 +1,48 @@
+use std::ops::RangeInclusive;
+
+/// The protocols supported by the compositor, with the versions bound by layershellev.
+///
+/// None means the compositor does not support the protocol, and the features depending on it are
+/// disabled, or fall back to something simpler. It is reported when the [crate::WindowState] is
+/// built, take [crate::WindowState::capabilities].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// zwlr_layer_shell_v1, the OnDemand keyboard interactivity needs the version 4, and the
+    /// exclusive edge needs the version 5
+    pub layer_shell: Option<u32>,
+    /// xdg_wm_base, needed by the popups
+    pub xdg_wm_base: Option<u32>,
+    /// zxdg_output_manager_v1, needed to get the names of the outputs, like in
+    /// [crate::StartMode::TargetScreen]
+    pub xdg_output: Option<u32>,
+    /// wp_cursor_shape_manager_v1, without it the cursor is loaded from the cursor theme
+    pub cursor_shape: Option<u32>,
+    /// wp_viewporter, needed to show the surface with a fractional scale
+    pub viewporter: Option<u32>,
+    /// wp_fractional_scale_manager_v1, without it the scale is always 1
+    pub fractional_scale: Option<u32>,
+    /// ext_session_lock_manager_v1, needed to lock the session
+    pub session_lock: Option<u32>,
+    /// wp_alpha_modifier_v1, needed to set the opacity of the surface
+    pub alpha_modifier: Option<u32>,
+    /// zwp_virtual_keyboard_manager_v1, bound by the frontends to send the virtual key events
+    pub virtual_keyboard: Option<u32>,
+}
+
+/// the version of a global bound with the `versions`, None if the compositor is too old for them
+pub(crate) fn bound_version(version: u32, versions: RangeInclusive<u32>) -> Option<u32> {
+    (version >= *versions.start()).then(|| version.min(*versions.end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bound_version() {
+        assert_eq!(bound_version(2, 3..=5), None);
+        assert_eq!(bound_version(3, 3..=5), Some(3));
+        assert_eq!(bound_version(4, 3..=5), Some(4));
+        assert_eq!(bound_version(6, 3..=5), Some(5));
+    }
+}
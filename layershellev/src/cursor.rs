@@ -0,0 +1,230 @@
+use std::{io::Write, os::fd::AsFd};
+
+use waycrate_shellcore::cursor::{self, CursorHandler, ThemedCursor};
+use wayland_client::{
+    protocol::{
+        wl_buffer::WlBuffer, wl_compositor::WlCompositor, wl_pointer::WlPointer, wl_shm,
+        wl_shm::WlShm,
+    },
+    Connection, QueueHandle,
+};
+use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_manager_v1::WpCursorShapeManagerV1;
+
+use crate::{CustomCursor, LayerEventError, WindowState};
+
+impl<T: 'static> WindowState<T> {
+    /// the name of the cursor theme: the one set with [WindowState::with_cursor_theme], else
+    /// `XCURSOR_THEME`, else `default`
+    pub fn cursor_theme(&self) -> String {
+        cursor::cursor_theme_name(self.cursor_theme.as_deref())
+    }
+
+    /// the logical size of the cursor: the one set with [WindowState::with_cursor_size], else
+    /// `XCURSOR_SIZE`, else [DEFAULT_CURSOR_SIZE](crate::DEFAULT_CURSOR_SIZE)
+    pub fn cursor_size(&self) -> u32 {
+        cursor::cursor_size(self.cursor_size)
+    }
+
+    pub(crate) fn set_cursor_shape(&mut self, shape_name: &str, pointer: &WlPointer, serial: u32) {
+        cursor::set_cursor_shape(self, shape_name, pointer, serial);
+        if let Some(custom) = self.custom_cursor_buffer.take() {
+            custom.buffer.destroy();
+        }
+    }
+
+    pub(crate) fn set_custom_cursor(
+        &mut self,
+        cursor: &CustomCursor,
+        pointer: &WlPointer,
+        serial: u32,
+    ) {
+        let scale = cursor::cursor_scale(self);
+        let cached = matches!(
+            &self.custom_cursor_buffer,
+            Some(custom) if custom.cursor == *cursor && custom.scale == scale
+        );
+        let mut old_buffer = None;
+        if !cached {
+            let buffer = match self.create_cursor_buffer(cursor, scale) {
+                Ok(buffer) => buffer,
+                Err(e) => {
+                    log::error!("Cannot create the custom cursor: {e}");
+                    return;
+                }
+            };
+            let custom = CustomCursorBuffer {
+                cursor: cursor.clone(),
+                scale,
+                buffer,
+            };
+            old_buffer = self
+                .custom_cursor_buffer
+                .replace(custom)
+                .map(|old| old.buffer);
+        }
+        let Some(buffer) = self
+            .custom_cursor_buffer
+            .as_ref()
+            .map(|custom| custom.buffer.clone())
+        else {
+            return;
+        };
+        cursor::set_cursor_buffer(
+            self,
+            &buffer,
+            scale,
+            (cursor.width * scale, cursor.height * scale),
+            cursor.hotspot,
+            pointer,
+            serial,
+        );
+        // NOTE: the old buffer is replaced on the cursor surface before it is destroyed
+        if let Some(old_buffer) = old_buffer {
+            old_buffer.destroy();
+        }
+    }
+
+    fn create_cursor_buffer(
+        &self,
+        cursor: &CustomCursor,
+        scale: u32,
+    ) -> Result<WlBuffer, LayerEventError> {
+        let CustomCursor {
+            rgba,
+            width,
+            height,
+            hotspot,
+        } = cursor;
+        // NOTE: the pool size is an i32, so larger images cannot be shared
+        let size = |width: u32, height: u32| {
+            (width as usize)
+                .checked_mul(height as usize)
+                .and_then(|n| n.checked_mul(4))
+                .filter(|size| i32::try_from(*size).is_ok())
+        };
+        if *width == 0
+            || *height == 0
+            || size(*width, *height) != Some(rgba.len())
+            || hotspot.0 >= *width
+            || hotspot.1 >= *height
+        {
+            return Err(LayerEventError::InvalidCursorImage);
+        }
+        let Some((scaled_width, scaled_height)) = width
+            .checked_mul(scale)
+            .zip(height.checked_mul(scale))
+            .filter(|(width, height)| size(*width, *height).is_some())
+        else {
+            return Err(LayerEventError::InvalidCursorImage);
+        };
+
+        // NOTE: Argb8888 is premultiplied, and little endian
+        let argb: Vec<u8> = rgba
+            .chunks_exact(4)
+            .flat_map(|pixel| {
+                let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+                let premultiply = |c: u8| ((c as u16 * a as u16 + 127) / 255) as u8;
+                [premultiply(b), premultiply(g), premultiply(r), a]
+            })
+            .collect();
+        let argb = scale_pixels(&argb, *width, scale);
+        let mut file = tempfile::tempfile()?;
+        file.write_all(&argb)?;
+        file.flush()?;
+
+        let qh = self.queue_handle.as_ref().unwrap();
+        let pool = self
+            .shm
+            .as_ref()
+            .unwrap()
+            .create_pool(file.as_fd(), argb.len() as i32, qh, ());
+        let buffer = pool.create_buffer(
+            0,
+            scaled_width as i32,
+            scaled_height as i32,
+            (scaled_width * 4) as i32,
+            wl_shm::Format::Argb8888,
+            qh,
+            (),
+        );
+        pool.destroy();
+        Ok(buffer)
+    }
+}
+
+/// the buffer of the last custom cursor, drawn for the scale. It is used again while the cursor
+/// and the scale stay the same.
+#[derive(Debug)]
+pub(crate) struct CustomCursorBuffer {
+    cursor: CustomCursor,
+    scale: u32,
+    buffer: WlBuffer,
+}
+
+/// repeat every pixel of the image `scale` times on both axes
+fn scale_pixels(pixels: &[u8], width: u32, scale: u32) -> Vec<u8> {
+    if scale == 1 {
+        return pixels.to_vec();
+    }
+    let scale = scale as usize;
+    pixels
+        .chunks_exact(width as usize * 4)
+        .flat_map(|row| {
+            let scaled_row: Vec<u8> = row
+                .chunks_exact(4)
+                .flat_map(|pixel| pixel.repeat(scale))
+                .collect();
+            scaled_row.repeat(scale)
+        })
+        .collect()
+}
+
+impl<T: 'static> CursorHandler for WindowState<T> {
+    fn themed_cursor_mut(&mut self) -> &mut ThemedCursor {
+        &mut self.themed_cursor
+    }
+
+    fn cursor_theme(&self) -> String {
+        WindowState::cursor_theme(self)
+    }
+
+    fn cursor_size(&self) -> u32 {
+        WindowState::cursor_size(self)
+    }
+
+    fn cursor_manager(&self) -> Option<&WpCursorShapeManagerV1> {
+        self.cursor_manager.as_ref()
+    }
+
+    fn connection(&self) -> Option<&Connection> {
+        self.connection.as_ref()
+    }
+
+    fn shm(&self) -> Option<&WlShm> {
+        self.shm.as_ref()
+    }
+
+    fn compositor(&self) -> Option<&WlCompositor> {
+        self.wl_compositor.as_ref()
+    }
+
+    fn queue_handle(&self) -> Option<&QueueHandle<Self>> {
+        self.queue_handle.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_pixels() {
+        let pixels = [1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4];
+        assert_eq!(scale_pixels(&pixels, 2, 1), pixels);
+        let scaled: Vec<u8> = [1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]
+            .iter()
+            .flat_map(|pixel| [*pixel; 4])
+            .collect();
+        assert_eq!(scale_pixels(&pixels, 2, 2), scaled);
+    }
+}
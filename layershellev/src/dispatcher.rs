@@ -0,0 +1,199 @@
+use std::{
+    cell::RefCell,
+    os::fd::{AsFd, BorrowedFd},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use calloop::{
+    channel::{self, Channel},
+    ping::make_ping,
+    timer::{TimeoutAction, Timer},
+    Error as CallLoopError, EventLoop, LoopHandle, RegistrationToken,
+};
+use calloop_wayland_source::WaylandSource;
+
+use crate::{id, LayerEvent, LayerEventError, ReturnData, WindowState};
+
+impl<T: 'static> WindowState<T> {
+    /// Insert the wayland source and the internal timers, like key repeat, into an event loop
+    /// owned by the caller, instead of letting [WindowState::running] own the loop. This way
+    /// layershellev can share the loop with other sources, like D-Bus or inotify.
+    ///
+    /// The init requests are handled before this function returns, then the handler is called
+    /// from the loop every time the wayland source or the timers produce events. If a channel is
+    /// given, every message received will be passed to the handler as [LayerEvent::UserEvent].
+    ///
+    /// [ReturnData::RequestExit] cannot stop a loop which is not owned by layershellev, so it is
+    /// recorded instead, check it with [WindowState::is_exit_requested] after dispatching. The
+    /// errors of the handling are recorded the same way, take them with
+    /// [WindowState::take_dispatch_error].
+    ///
+    /// The state given to [EventLoop::dispatch] must be this one.
+    pub fn insert_into_loop<F, Message>(
+        &mut self,
+        loop_handle: &LoopHandle<'static, Self>,
+        message_channel: Option<Channel<Message>>,
+        mut event_handler: F,
+    ) -> Result<(), LayerEventError>
+    where
+        Message: 'static,
+        F: FnMut(LayerEvent<T, Message>, &mut WindowState<T>, Option<id::Id>) -> ReturnData<T>
+            + 'static,
+    {
+        self.handle_init_requests(&mut event_handler);
+
+        let event_handler = Rc::new(RefCell::new(event_handler));
+
+        let event_queue = self.event_queue.take().unwrap();
+        let connection = self.connection.clone().unwrap();
+        let handler = event_handler.clone();
+        loop_handle
+            .insert_source(
+                WaylandSource::new(connection, event_queue),
+                move |_, queue, state| {
+                    let count = queue.dispatch_pending(state)?;
+                    state.dispatch_from_loop(&mut *handler.borrow_mut(), Vec::new());
+                    Ok(count)
+                },
+            )
+            .map_err(|e| e.error)?;
+
+        let (waker, waker_source) = make_ping().map_err(CallLoopError::IoError)?;
+        let handler = event_handler.clone();
+        loop_handle
+            .insert_source(waker_source, move |_, _, state| {
+                state.dispatch_from_loop(&mut *handler.borrow_mut(), Vec::new());
+            })
+            .map_err(|e| e.error)?;
+
+        if let Some(message_channel) = message_channel {
+            let handler = event_handler;
+            loop_handle
+                .insert_source(message_channel, move |event, _, state| {
+                    let channel::Event::Msg(message) = event else {
+                        return;
+                    };
+                    state.dispatch_from_loop(&mut *handler.borrow_mut(), vec![message]);
+                })
+                .map_err(|e| e.error)?;
+        }
+
+        self.loop_handler = Some(loop_handle.clone());
+        self.dispatch_waker = Some(waker);
+        Ok(())
+    }
+
+    /// Create a [WindowStateDispatcher], which owns the event loop and can be dispatched without
+    /// blocking from another main loop.
+    pub fn into_dispatcher<F, Message>(
+        mut self,
+        message_channel: Option<Channel<Message>>,
+        event_handler: F,
+    ) -> Result<WindowStateDispatcher<T>, LayerEventError>
+    where
+        Message: 'static,
+        F: FnMut(LayerEvent<T, Message>, &mut WindowState<T>, Option<id::Id>) -> ReturnData<T>
+            + 'static,
+    {
+        let event_loop: EventLoop<'static, Self> = EventLoop::try_new()?;
+        self.insert_into_loop(&event_loop.handle(), message_channel, event_handler)?;
+        Ok(WindowStateDispatcher {
+            event_loop,
+            state: self,
+        })
+    }
+
+    /// Insert a timer into the event loop of the state. Unlike a timer inserted with the loop
+    /// handle, its deadline is known by [WindowStateDispatcher::next_timer_deadline] and by the
+    /// stream of the `async` feature, so they wake up for it. It is None before the state is in a
+    /// loop.
+    pub fn insert_timer<F>(&self, timer: Timer, callback: F) -> Option<RegistrationToken>
+    where
+        F: FnMut(Instant, &mut (), &mut Self) -> TimeoutAction + 'static,
+    {
+        let loop_handle = self.loop_handler.as_ref()?;
+        self.timer_deadlines
+            .insert(loop_handle, timer, callback)
+            .ok()
+    }
+
+    /// remove a timer inserted with [WindowState::insert_timer]
+    pub fn remove_timer(&self, token: RegistrationToken) {
+        if let Some(loop_handle) = self.loop_handler.as_ref() {
+            self.timer_deadlines.remove(loop_handle, token);
+        }
+    }
+
+    fn dispatch_from_loop<F, Message>(&mut self, event_handler: &mut F, user_events: Vec<Message>)
+    where
+        F: FnMut(LayerEvent<T, Message>, &mut WindowState<T>, Option<id::Id>) -> ReturnData<T>,
+    {
+        match self.dispatch_messages(event_handler, user_events) {
+            Ok(true) => self.exit_requested = true,
+            Ok(false) => {}
+            // NOTE: keep the first error, the later ones are likely caused by it
+            Err(e) => {
+                self.dispatch_error.get_or_insert(e);
+            }
+        }
+    }
+}
+
+/// A [WindowState] together with the event loop driving it, for programs whose main loop is not
+/// calloop. Watch the fd of the dispatcher in the main loop, and call
+/// [WindowStateDispatcher::dispatch_pending] when it is readable, or when
+/// [WindowStateDispatcher::next_timer_deadline] is reached.
+///
+/// The fd alone is not enough: calloop timers, like the key repeat or the animated cursors, have
+/// no fd, they fire only when the loop is dispatched after their deadline. The timers of your own
+/// should be inserted with [WindowState::insert_timer], else wake up with a bounded timeout.
+pub struct WindowStateDispatcher<T: 'static> {
+    event_loop: EventLoop<'static, WindowState<T>>,
+    state: WindowState<T>,
+}
+
+impl<T: 'static> WindowStateDispatcher<T> {
+    /// dispatch the pending events, waiting at most `timeout` for new ones. Pass
+    /// `Some(Duration::ZERO)` to never block. The errors raised by the handling of the events are
+    /// returned too.
+    pub fn dispatch_pending(&mut self, timeout: Option<Duration>) -> Result<(), LayerEventError> {
+        let dispatch_start = Instant::now();
+        self.event_loop.dispatch(timeout, &mut self.state)?;
+        self.state.timer_deadlines.expire(dispatch_start);
+        match self.state.take_dispatch_error() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// the deadline of the next timer of the loop, the loop has to be dispatched then even if its
+    /// fd is not readable
+    pub fn next_timer_deadline(&self) -> Option<Instant> {
+        self.state.timer_deadlines.next()
+    }
+
+    /// if the handler has returned [ReturnData::RequestExit]
+    pub fn is_exit_requested(&self) -> bool {
+        self.state.is_exit_requested()
+    }
+
+    pub fn state(&self) -> &WindowState<T> {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut WindowState<T> {
+        &mut self.state
+    }
+
+    /// the handle of the owned event loop, to insert more sources
+    pub fn loop_handle(&self) -> LoopHandle<'static, WindowState<T>> {
+        self.event_loop.handle()
+    }
+}
+
+impl<T: 'static> AsFd for WindowStateDispatcher<T> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.event_loop.as_fd()
+    }
+}
@@ -25,6 +25,8 @@ use crate::id::Id;
 
 use std::{fmt::Debug, fs::File};
 
+use waycrate_shellcore::{SeatId, SeatMessage};
+
 /// tell program what event is happened
 ///
 /// InitRequest will tell the program is inited, you can request to Bind other wayland-protocols
@@ -83,6 +85,9 @@ pub struct NewLayerShellSettings {
     /// wl_output.
     pub use_last_output: bool,
     pub events_transparent: bool,
+    /// compute the exclusive zone from the configured size and the anchored edge, and recompute
+    /// it when they change. `exclusive_zone` is ignored when it is enabled.
+    pub auto_exclusive_zone: bool,
 }
 
 /// be used to create a new popup
@@ -107,10 +112,23 @@ impl Default for NewLayerShellSettings {
             keyboard_interactivity: KeyboardInteractivity::OnDemand,
             use_last_output: false,
             events_transparent: false,
+            auto_exclusive_zone: false,
         }
     }
 }
 
+/// an image used as the pointer cursor, see [ReturnData::RequestSetCustomCursor]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomCursor {
+    /// the pixels, row by row, four bytes per pixel in RGBA order, not premultiplied
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    /// the point of the image which is at the position of the pointer, in pixels from the top
+    /// left corner
+    pub hotspot: (u32, u32),
+}
+
 /// the return data
 /// Note: when event is RequestBuffer, you must return WlBuffer
 /// Note: when receive InitRequest, you can request to bind extra wayland-protocols. this time you
@@ -123,6 +141,11 @@ impl Default for NewLayerShellSettings {
 /// When send RequestSetCursorShape, you can set current pointer shape. please take
 /// [cursor-shape](https://wayland.app/protocols/cursor-shape-v1#wp_cursor_shape_device_v1:enum:shape) as reference.
 ///
+/// When send RequestSetCustomCursor, the pointer will show the image of the [CustomCursor].
+///
+/// When send RequestSessionLock, the session will be locked with ext-session-lock, and a lock
+/// surface is created on every output. Use [WindowState::unlock_session] to unlock it.
+///
 /// None means nothing will happened, no request, and no return data
 #[derive(Debug, PartialEq, Eq)]
 pub enum ReturnData<INFO> {
@@ -133,110 +156,29 @@ pub enum ReturnData<INFO> {
     RedrawAllRequest,
     RedrawIndexRequest(Id),
     RequestSetCursorShape((String, WlPointer, u32)),
+    RequestSetCustomCursor((CustomCursor, WlPointer, u32)),
     NewLayerShell((NewLayerShellSettings, id::Id, Option<INFO>)),
     NewPopUp((NewPopUpSettings, id::Id, Option<INFO>)),
+    RequestSessionLock,
+    /// set the opacity of the surface with wp_alpha_modifier_v1, as the multiplier of the
+    /// protocol, see [crate::alpha_multiplier]. The compositor applies it without redrawing, and it
+    /// does nothing if the protocol is not supported.
+    RequestSetOpacity((id::Id, u32)),
+    /// request a frame callback of the surface, [DispatchMessage::FrameDone] is sent with its id
+    /// when the compositor is ready for a new frame. It is used to step the animations.
+    RequestFrame(id::Id),
     None,
 }
 
-/// this tell the what kind of information passed by [LayerEvent::XdgInfoChanged]
-#[derive(Debug, Clone, Copy)]
-pub enum XdgInfoChangedType {
-    Position,
-    Size,
-    Name,
-    Description,
-}
-
-/// Describes a scroll along one axis
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
-pub struct AxisScroll {
-    /// The scroll measured in pixels.
-    pub absolute: f64,
-
-    /// The scroll measured in steps.
-    ///
-    /// Note: this might always be zero if the scrolling is due to a touchpad or other continuous
-    /// source.
-    pub discrete: i32,
-
-    /// The scroll was stopped.
-    ///
-    /// Generally this is encountered when hardware indicates the end of some continuous scrolling.
-    pub stop: bool,
-}
+pub use waycrate_shellcore::{AxisScroll, XdgInfoChangedType};
 
 #[allow(unused)]
 #[derive(Debug, Clone)]
 pub(crate) enum DispatchMessageInner {
+    /// the input of a seat, the seat is None if it has been removed
+    Seat(Option<SeatId>, SeatMessage<Id>),
     NewDisplay(WlOutput),
-    MouseButton {
-        state: WEnum<ButtonState>,
-        serial: u32,
-        button: u32,
-        time: u32,
-    },
-    MouseLeave,
-    MouseEnter {
-        pointer: WlPointer,
-        serial: u32,
-        surface_x: f64,
-        surface_y: f64,
-    },
-    MouseMotion {
-        time: u32,
-        surface_x: f64,
-        surface_y: f64,
-    },
-    Axis {
-        time: u32,
-        scale: f64,
-        horizontal: AxisScroll,
-        vertical: AxisScroll,
-        source: Option<wl_pointer::AxisSource>,
-    },
-    TouchDown {
-        serial: u32,
-        time: u32,
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    TouchUp {
-        serial: u32,
-        time: u32,
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    TouchMotion {
-        time: u32,
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    TouchCancel {
-        id: i32,
-        x: f64,
-        y: f64,
-    },
 
-    ModifiersChanged(ModifiersState),
-    Focused(Id),
-    Unfocus,
-    KeyboardInput {
-        event: KeyEvent,
-
-        /// If `true`, the event was generated synthetically by winit
-        /// in one of the following circumstances:
-        ///
-        /// * Synthetic key press events are generated for all keys pressed when a window gains
-        ///   focus. Likewise, synthetic key release events are generated for all keys pressed when
-        ///   a window goes out of focus. ***Currently, this is only functional on X11 and
-        ///   Windows***
-        ///
-        /// Otherwise, this value is always `false`.
-        is_synthetic: bool,
-    },
     RefreshSurface {
         width: u32,
         height: u32,
@@ -252,10 +194,18 @@ pub(crate) enum DispatchMessageInner {
         scale_float: f64,
     },
     XdgInfoChanged(XdgInfoChangedType),
+    SessionLocked,
+    SessionLockFinished,
+    FrameDone {
+        time: u32,
+    },
 }
 
 /// This tell the DispatchMessage by dispatch
-#[derive(Debug)]
+///
+/// The input messages carry the seat which produced them, it is None when the seat has been
+/// removed, like for the last [DispatchMessage::Unfocus] of its keyboard.
+#[derive(Debug, Clone)]
 pub enum DispatchMessage {
     /// forward the event of wayland-mouse
     MouseButton {
@@ -263,21 +213,26 @@ pub enum DispatchMessage {
         serial: u32,
         button: u32,
         time: u32,
+        seat: Option<SeatId>,
     },
     /// Mouse leave the surface
-    MouseLeave,
+    MouseLeave {
+        seat: Option<SeatId>,
+    },
     /// forward the event of wayland-mouse
     MouseEnter {
         pointer: WlPointer,
         serial: u32,
         surface_x: f64,
         surface_y: f64,
+        seat: Option<SeatId>,
     },
     /// forward the event of wayland-mouse
     MouseMotion {
         time: u32,
         surface_x: f64,
         surface_y: f64,
+        seat: Option<SeatId>,
     },
     /// About the scroll
     Axis {
@@ -286,6 +241,7 @@ pub enum DispatchMessage {
         horizontal: AxisScroll,
         vertical: AxisScroll,
         source: Option<wl_pointer::AxisSource>,
+        seat: Option<SeatId>,
     },
     /// forward the event of wayland-touch
     TouchDown {
@@ -294,6 +250,7 @@ pub enum DispatchMessage {
         id: i32,
         x: f64,
         y: f64,
+        seat: Option<SeatId>,
     },
     /// forward the event of wayland-touch
     TouchUp {
@@ -302,6 +259,7 @@ pub enum DispatchMessage {
         id: i32,
         x: f64,
         y: f64,
+        seat: Option<SeatId>,
     },
     /// forward the event of wayland-touch
     TouchMotion {
@@ -309,17 +267,28 @@ pub enum DispatchMessage {
         id: i32,
         x: f64,
         y: f64,
+        seat: Option<SeatId>,
     },
     /// TouchEvent is cancelled
     TouchCancel {
         id: i32,
         x: f64,
         y: f64,
+        seat: Option<SeatId>,
+    },
+    // NOTE: sent when the first keyboard enters the surface, and Unfocus when the last one leaves
+    Focused {
+        id: Id,
+        seat: Option<SeatId>,
+    },
+    Unfocus {
+        seat: Option<SeatId>,
     },
-    Focused(Id),
-    Unfocus,
     /// Keyboard ModifiersChanged.
-    ModifiersChanged(ModifiersState),
+    ModifiersChanged {
+        modifiers: ModifiersState,
+        seat: Option<SeatId>,
+    },
     /// Keyboard Event about input.
     KeyboardInput {
         event: KeyEvent,
@@ -334,6 +303,7 @@ pub enum DispatchMessage {
         ///
         /// Otherwise, this value is always `false`.
         is_synthetic: bool,
+        seat: Option<SeatId>,
     },
     /// this will request to do refresh the whole screen, because the layershell tell that a new
     /// configure happened
@@ -348,121 +318,171 @@ pub enum DispatchMessage {
         scale_u32: u32,
         scale_float: f64,
     },
+    /// the compositor has locked the session, after [ReturnData::RequestSessionLock]
+    SessionLocked,
+    /// the compositor has ended the session lock, or denied it. The lock surfaces will not be
+    /// shown anymore, call [WindowState::unlock_session] to destroy them.
+    SessionLockFinished,
+    /// the compositor is ready for a new frame of the surface, after [ReturnData::RequestFrame].
+    /// The time is in milliseconds, with an undefined base.
+    FrameDone {
+        time: u32,
+    },
 }
 
 impl From<DispatchMessageInner> for DispatchMessage {
     fn from(val: DispatchMessageInner) -> Self {
         match val {
+            DispatchMessageInner::Seat(seat, message) => Self::from_seat_message(message, seat),
             DispatchMessageInner::NewDisplay(_) => unimplemented!(),
-            DispatchMessageInner::MouseButton {
+            DispatchMessageInner::RequestRefresh {
+                width,
+                height,
+                scale_float,
+                is_created,
+            } => DispatchMessage::RequestRefresh {
+                width,
+                height,
+                scale_float,
+                is_created,
+            },
+            DispatchMessageInner::PreferredScale {
+                scale_u32,
+                scale_float,
+            } => DispatchMessage::PreferredScale {
+                scale_u32,
+                scale_float,
+            },
+            DispatchMessageInner::SessionLocked => DispatchMessage::SessionLocked,
+            DispatchMessageInner::SessionLockFinished => DispatchMessage::SessionLockFinished,
+            DispatchMessageInner::FrameDone { time } => DispatchMessage::FrameDone { time },
+            DispatchMessageInner::RefreshSurface { .. } => unimplemented!(),
+            DispatchMessageInner::XdgInfoChanged(_) => unimplemented!(),
+        }
+    }
+}
+
+impl DispatchMessage {
+    /// the seat which produced an input message, None for the other messages or if the seat
+    /// has been removed
+    pub fn seat(&self) -> Option<SeatId> {
+        match self {
+            Self::MouseButton { seat, .. }
+            | Self::MouseLeave { seat }
+            | Self::MouseEnter { seat, .. }
+            | Self::MouseMotion { seat, .. }
+            | Self::Axis { seat, .. }
+            | Self::TouchDown { seat, .. }
+            | Self::TouchUp { seat, .. }
+            | Self::TouchMotion { seat, .. }
+            | Self::TouchCancel { seat, .. }
+            | Self::Focused { seat, .. }
+            | Self::Unfocus { seat }
+            | Self::ModifiersChanged { seat, .. }
+            | Self::KeyboardInput { seat, .. } => *seat,
+            _ => None,
+        }
+    }
+
+    /// tag the message of a seat with the seat
+    fn from_seat_message(message: SeatMessage<Id>, seat: Option<SeatId>) -> Self {
+        match message {
+            SeatMessage::MouseButton {
                 state,
                 serial,
                 button,
                 time,
-            } => DispatchMessage::MouseButton {
+            } => Self::MouseButton {
                 state,
                 serial,
                 button,
                 time,
+                seat,
             },
-            DispatchMessageInner::MouseLeave => DispatchMessage::MouseLeave,
-            DispatchMessageInner::MouseEnter {
+            SeatMessage::MouseLeave => Self::MouseLeave { seat },
+            SeatMessage::MouseEnter {
                 pointer,
                 serial,
                 surface_x,
                 surface_y,
-            } => DispatchMessage::MouseEnter {
+            } => Self::MouseEnter {
                 pointer,
                 serial,
                 surface_x,
                 surface_y,
+                seat,
             },
-            DispatchMessageInner::MouseMotion {
+            SeatMessage::MouseMotion {
                 time,
                 surface_x,
                 surface_y,
-            } => DispatchMessage::MouseMotion {
+            } => Self::MouseMotion {
                 time,
                 surface_x,
                 surface_y,
+                seat,
+            },
+            SeatMessage::Axis {
+                time,
+                scale,
+                horizontal,
+                vertical,
+                source,
+            } => Self::Axis {
+                time,
+                scale,
+                horizontal,
+                vertical,
+                source,
+                seat,
             },
-            DispatchMessageInner::TouchDown {
+            SeatMessage::TouchDown {
                 serial,
                 time,
                 id,
                 x,
                 y,
-            } => DispatchMessage::TouchDown {
+            } => Self::TouchDown {
                 serial,
                 time,
                 id,
                 x,
                 y,
+                seat,
             },
-            DispatchMessageInner::TouchUp {
+            SeatMessage::TouchUp {
                 serial,
                 time,
                 id,
                 x,
                 y,
-            } => DispatchMessage::TouchUp {
+            } => Self::TouchUp {
                 serial,
                 time,
                 id,
                 x,
                 y,
+                seat,
             },
-            DispatchMessageInner::TouchMotion { time, id, x, y } => {
-                DispatchMessage::TouchMotion { time, id, x, y }
-            }
-            DispatchMessageInner::TouchCancel { id, x, y } => {
-                DispatchMessage::TouchCancel { id, x, y }
-            }
-            DispatchMessageInner::RequestRefresh {
-                width,
-                height,
-                scale_float,
-                is_created,
-            } => DispatchMessage::RequestRefresh {
-                width,
-                height,
-                scale_float,
-                is_created,
-            },
-            DispatchMessageInner::Axis {
-                time,
-                scale,
-                horizontal,
-                vertical,
-                source,
-            } => DispatchMessage::Axis {
+            SeatMessage::TouchMotion { time, id, x, y } => Self::TouchMotion {
                 time,
-                scale,
-                horizontal,
-                vertical,
-                source,
+                id,
+                x,
+                y,
+                seat,
             },
-            DispatchMessageInner::Focused(id) => DispatchMessage::Focused(id),
-            DispatchMessageInner::Unfocus => DispatchMessage::Unfocus,
-            DispatchMessageInner::ModifiersChanged(modifier) => {
-                DispatchMessage::ModifiersChanged(modifier)
-            }
-            DispatchMessageInner::KeyboardInput {
+            SeatMessage::TouchCancel { id, x, y } => Self::TouchCancel { id, x, y, seat },
+            SeatMessage::ModifiersChanged(modifiers) => Self::ModifiersChanged { modifiers, seat },
+            SeatMessage::KeyboardInput {
                 event,
                 is_synthetic,
-            } => DispatchMessage::KeyboardInput {
+            } => Self::KeyboardInput {
                 event,
                 is_synthetic,
+                seat,
             },
-            DispatchMessageInner::PreferredScale {
-                scale_u32,
-                scale_float,
-            } => DispatchMessage::PreferredScale {
-                scale_u32,
-                scale_float,
-            },
-            DispatchMessageInner::RefreshSurface { .. } => unimplemented!(),
-            DispatchMessageInner::XdgInfoChanged(_) => unimplemented!(),
+            SeatMessage::Focused(id) => Self::Focused { id, seat },
+            SeatMessage::Unfocus => Self::Unfocus { seat },
         }
     }
 }
@@ -76,6 +76,7 @@
 //!                 time,
 //!                 surface_x,
 //!                 surface_y,
+//!                 ..
 //!             }) => {
 //!                 println!("{time}, {surface_x}, {surface_y}");
 //!                 ReturnData::None
@@ -111,32 +112,44 @@
 //! }
 //! ```
 //!
+pub use capabilities::Capabilities;
+pub use dispatcher::WindowStateDispatcher;
 pub use events::NewLayerShellSettings;
 pub use events::NewPopUpSettings;
+pub use seat::{SeatHandler, SeatId};
+#[cfg(feature = "async")]
+pub use stream::{StreamEvent, StreamRequestSender, WindowStateStream};
+pub use waycrate_shellcore::ZxdgOutputInfo;
+pub use waycrate_shellcore::DEFAULT_CURSOR_SIZE;
 pub use waycrate_xkbkeycode::keyboard;
 pub use waycrate_xkbkeycode::xkb_keyboard;
 
+mod capabilities;
+mod cursor;
+mod dispatcher;
 mod events;
-mod strtoshape;
+mod seat;
+#[cfg(feature = "async")]
+mod stream;
 
+use capabilities::bound_version;
 use events::DispatchMessageInner;
 
 pub mod id;
 
-pub use events::{AxisScroll, DispatchMessage, LayerEvent, ReturnData, XdgInfoChangedType};
-
-use strtoshape::str_to_shape;
-
-use waycrate_xkbkeycode::xkb_keyboard::RepeatInfo;
+pub use events::{
+    AxisScroll, CustomCursor, DispatchMessage, LayerEvent, ReturnData, XdgInfoChangedType,
+};
 
 use wayland_client::{
     delegate_noop,
     globals::{registry_queue_init, BindError, GlobalError, GlobalList, GlobalListContents},
     protocol::{
         wl_buffer::WlBuffer,
+        wl_callback::{self, WlCallback},
         wl_compositor::WlCompositor,
         wl_display::WlDisplay,
-        wl_keyboard::{self, KeyState, KeymapFormat, WlKeyboard},
+        wl_keyboard::{self, WlKeyboard},
         wl_output::{self, WlOutput},
         wl_pointer::{self, WlPointer},
         wl_region::WlRegion,
@@ -147,10 +160,9 @@ use wayland_client::{
         wl_surface::WlSurface,
         wl_touch::{self, WlTouch},
     },
-    ConnectError, Connection, Dispatch, DispatchError, EventQueue, Proxy, QueueHandle, WEnum,
+    ConnectError, Connection, Dispatch, DispatchError, EventQueue, Proxy, QueueHandle,
 };
 
-use wayland_cursor::{CursorImageBuffer, CursorTheme};
 use wayland_protocols_wlr::layer_shell::v1::client::{
     zwlr_layer_shell_v1::{Layer, ZwlrLayerShellV1},
     zwlr_layer_surface_v1::{self, Anchor, ZwlrLayerSurfaceV1},
@@ -163,21 +175,28 @@ use wayland_protocols::xdg::shell::client::{
     xdg_wm_base::XdgWmBase,
 };
 
+use wayland_protocols::ext::session_lock::v1::client::{
+    ext_session_lock_manager_v1::ExtSessionLockManagerV1,
+    ext_session_lock_surface_v1::{self, ExtSessionLockSurfaceV1},
+    ext_session_lock_v1::{self, ExtSessionLockV1},
+};
+
 use wayland_protocols::{
     wp::fractional_scale::v1::client::{
         wp_fractional_scale_manager_v1::WpFractionalScaleManagerV1,
         wp_fractional_scale_v1::{self, WpFractionalScaleV1},
     },
-    xdg::xdg_output::zv1::client::{
-        zxdg_output_manager_v1::ZxdgOutputManagerV1,
-        zxdg_output_v1::{self, ZxdgOutputV1},
-    },
+    xdg::xdg_output::zv1::client::{zxdg_output_manager_v1::ZxdgOutputManagerV1, zxdg_output_v1},
 };
 
 use wayland_protocols::wp::viewporter::client::{
     wp_viewport::WpViewport, wp_viewporter::WpViewporter,
 };
 
+use wayland_protocols::wp::alpha_modifier::v1::client::{
+    wp_alpha_modifier_surface_v1::WpAlphaModifierSurfaceV1, wp_alpha_modifier_v1::WpAlphaModifierV1,
+};
+
 use wayland_protocols::wp::cursor_shape::v1::client::{
     wp_cursor_shape_device_v1::WpCursorShapeDeviceV1,
     wp_cursor_shape_manager_v1::WpCursorShapeManagerV1,
@@ -190,11 +209,9 @@ use wayland_protocols_misc::zwp_virtual_keyboard_v1::client::{
 
 pub use calloop;
 
-use calloop::{
-    timer::{TimeoutAction, Timer},
-    Error as CallLoopError, EventLoop, LoopHandle,
-};
+use calloop::{ping::Ping, Error as CallLoopError, EventLoop, LoopHandle};
 use calloop_wayland_source::WaylandSource;
+use std::cell::Cell;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -214,6 +231,8 @@ pub enum LayerEventError {
     TempFileCreateFailed(#[from] std::io::Error),
     #[error("Event Loop Error")]
     EventLoopInitError(#[from] CallLoopError),
+    #[error("the size of the cursor image does not match its pixels, or the hotspot is outside")]
+    InvalidCursorImage,
 }
 
 pub mod reexport {
@@ -251,7 +270,7 @@ pub mod reexport {
         };
     }
     pub mod wp_cursor_shape_device_v1 {
-        pub use crate::strtoshape::ShapeName;
+        pub use waycrate_shellcore::strtoshape::ShapeName;
         pub use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::Shape;
     }
     pub mod wp_viewport {
@@ -275,38 +294,6 @@ impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for BaseState {
     }
 }
 
-/// this struct store the xdg_output information
-#[derive(Debug, Clone)]
-pub struct ZxdgOutputInfo {
-    name: String,
-    description: String,
-    zxdgoutput: ZxdgOutputV1,
-    logical_size: (i32, i32),
-    position: (i32, i32),
-}
-
-impl ZxdgOutputInfo {
-    fn new(zxdgoutput: ZxdgOutputV1) -> Self {
-        Self {
-            zxdgoutput,
-            name: "".to_owned(),
-            description: "".to_owned(),
-            logical_size: (0, 0),
-            position: (0, 0),
-        }
-    }
-
-    /// you can get the Logic position of the screen current surface in
-    pub fn get_position(&self) -> (i32, i32) {
-        self.position
-    }
-
-    /// you can get the LogicalPosition of the screen current surface in
-    pub fn get_logical_size(&self) -> (i32, i32) {
-        self.logical_size
-    }
-}
-
 /// This is the unit, binding to per screen.
 /// Because layer_shell is so unique, on surface bind to only one
 /// wl_output, only one buffer, only one output, so it will store
@@ -316,9 +303,11 @@ impl ZxdgOutputInfo {
 /// a cario_context, which is binding to the buffer on the wl_surface.
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 enum Shell {
     LayerShell(ZwlrLayerSurfaceV1),
     PopUp((XdgPopup, XdgSurface)),
+    SessionLock(ExtSessionLockSurfaceV1),
 }
 
 impl PartialEq<ZwlrLayerSurfaceV1> for Shell {
@@ -339,6 +328,15 @@ impl PartialEq<XdgPopup> for Shell {
     }
 }
 
+impl PartialEq<ExtSessionLockSurfaceV1> for Shell {
+    fn eq(&self, other: &ExtSessionLockSurfaceV1) -> bool {
+        match self {
+            Self::SessionLock(lock_surface) => lock_surface == other,
+            _ => false,
+        }
+    }
+}
+
 impl Shell {
     fn destroy(&self) {
         match self {
@@ -347,12 +345,17 @@ impl Shell {
                 xdg_surface.destroy();
             }
             Self::LayerShell(shell) => shell.destroy(),
+            Self::SessionLock(lock_surface) => lock_surface.destroy(),
         }
     }
 
     fn is_popup(&self) -> bool {
         matches!(self, Self::PopUp(_))
     }
+
+    fn is_session_lock(&self) -> bool {
+        matches!(self, Self::SessionLock(_))
+    }
 }
 
 #[derive(Debug)]
@@ -366,9 +369,13 @@ pub struct WindowStateUnit<T> {
     zxdgoutput: Option<ZxdgOutputInfo>,
     fractional_scale: Option<WpFractionalScaleV1>,
     viewport: Option<WpViewport>,
+    alpha_modifier_surface: Option<WpAlphaModifierSurfaceV1>,
     wl_output: Option<WlOutput>,
     binding: Option<T>,
     becreated: bool,
+    margin: Cell<(i32, i32, i32, i32)>,
+    anchor: Cell<Anchor>,
+    auto_exclusive_zone: Option<AutoExclusiveZone>,
 
     scale: u32,
 }
@@ -385,6 +392,11 @@ impl<T> WindowStateUnit<T> {
         self.id
     }
 
+    /// if the unit is a lock surface, created after [ReturnData::RequestSessionLock]
+    pub fn is_session_lock(&self) -> bool {
+        self.shell.is_session_lock()
+    }
+
     pub fn try_set_viewport_destination(&self, width: i32, height: i32) -> Option<()> {
         let viewport = self.viewport.as_ref()?;
         viewport.set_destination(width, height);
@@ -485,18 +497,31 @@ impl<T> WindowStateUnit<T> {
     pub fn set_anchor(&self, anchor: Anchor) {
         if let Shell::LayerShell(layer_shell) = &self.shell {
             layer_shell.set_anchor(anchor);
+            self.anchor.set(anchor);
+            self.update_auto_exclusive_zone(layer_shell, Some(anchor), None);
             self.wl_surface.commit();
         }
     }
 
+    /// get the anchor of the current unit
+    pub fn get_anchor(&self) -> Anchor {
+        self.anchor.get()
+    }
+
     /// you can reset the margin which bind to the surface
     pub fn set_margin(&self, (top, right, bottom, left): (i32, i32, i32, i32)) {
         if let Shell::LayerShell(layer_shell) = &self.shell {
             layer_shell.set_margin(top, right, bottom, left);
+            self.margin.set((top, right, bottom, left));
             self.wl_surface.commit();
         }
     }
 
+    /// get the margin of the current unit, in the order of top, right, bottom, left
+    pub fn get_margin(&self) -> (i32, i32, i32, i32) {
+        self.margin.get()
+    }
+
     /// set the layer
     pub fn set_layer(&self, layer: Layer) {
         if let Shell::LayerShell(layer_shell) = &self.shell {
@@ -510,7 +535,9 @@ impl<T> WindowStateUnit<T> {
     pub fn set_anchor_with_size(&self, anchor: Anchor, (width, height): (u32, u32)) {
         if let Shell::LayerShell(layer_shell) = &self.shell {
             layer_shell.set_anchor(anchor);
+            self.anchor.set(anchor);
             layer_shell.set_size(width, height);
+            self.update_auto_exclusive_zone(layer_shell, Some(anchor), None);
             self.wl_surface.commit();
         }
     }
@@ -523,7 +550,56 @@ impl<T> WindowStateUnit<T> {
         }
     }
 
+    /// set the keyboard interactivity of current unit, for example to grab the keyboard only
+    /// while a menu is open. OnDemand falls back to Exclusive if the compositor only supports
+    /// layer shell v3
+    pub fn set_keyboard_interactivity(
+        &self,
+        keyboard_interactivity: zwlr_layer_surface_v1::KeyboardInteractivity,
+    ) {
+        if let Shell::LayerShell(layer_shell) = &self.shell {
+            set_keyboard_interactivity_of(layer_shell, keyboard_interactivity);
+            self.wl_surface.commit();
+        }
+    }
+
+    /// set the edge the exclusive zone applies to, when the unit is anchored to a corner. The edge
+    /// must be one of the anchors of the unit, else it is ignored, as the compositor would raise
+    /// the `invalid_exclusive_edge` protocol error. It needs layer shell v5, and it does nothing
+    /// before.
+    pub fn set_exclusive_edge(&self, edge: Anchor) {
+        let Shell::LayerShell(layer_shell) = &self.shell else {
+            return;
+        };
+        if layer_shell.version() < 5 {
+            log::warn!("set_exclusive_edge needs layer shell v5, it is ignored");
+            return;
+        }
+        if edge.bits().count_ones() != 1 {
+            log::warn!("the exclusive edge should be exactly one edge, {edge:?} is ignored");
+            return;
+        }
+        let anchor = self.anchor.get();
+        if !anchor.contains(edge) {
+            log::warn!(
+                "the exclusive edge {edge:?} is not among the anchors {anchor:?}, it is ignored"
+            );
+            return;
+        }
+        layer_shell.set_exclusive_edge(edge);
+        self.wl_surface.commit();
+    }
+
+    /// set the opaque region of the surface, so the compositor can skip what is below it. None
+    /// means nothing is opaque. It is applied with the next commit of the surface.
+    pub fn set_opaque_region(&self, region: Option<&WlRegion>) {
+        self.wl_surface.set_opaque_region(region);
+    }
+
     /// set current exclusive_zone
+    ///
+    /// If the auto exclusive zone is enabled, the zone will be recomputed on the next change of
+    /// the size or the anchor
     pub fn set_exclusive_zone(&self, zone: i32) {
         if let Shell::LayerShell(layer_shell) = &self.shell {
             layer_shell.set_exclusive_zone(zone);
@@ -531,6 +607,23 @@ impl<T> WindowStateUnit<T> {
         }
     }
 
+    /// if the exclusive zone follows the size and the anchor of the unit, see
+    /// [WindowState::with_auto_exclusive_zone]
+    pub fn is_auto_exclusive_zone(&self) -> bool {
+        self.auto_exclusive_zone.is_some()
+    }
+
+    fn update_auto_exclusive_zone(
+        &self,
+        layer_shell: &ZwlrLayerSurfaceV1,
+        anchor: Option<Anchor>,
+        size: Option<(u32, u32)>,
+    ) {
+        if let Some(auto_exclusive_zone) = &self.auto_exclusive_zone {
+            layer_shell.set_exclusive_zone(auto_exclusive_zone.update(anchor, size));
+        }
+    }
+
     /// you can use this function to set a binding data. the message passed back contain
     /// a index, you can use that to get the unit. It will be very useful, because you can
     /// use the binding data to operate the file binding to the buffer. you can take
@@ -578,9 +671,9 @@ pub struct WindowState<T> {
     current_surface: Option<WlSurface>,
     units: Vec<WindowStateUnit<T>>,
     message: Vec<(Option<id::Id>, DispatchMessageInner)>,
-
     connection: Option<Connection>,
     event_queue: Option<EventQueue<WindowState<T>>>,
+    queue_handle: Option<QueueHandle<WindowState<T>>>,
     wl_compositor: Option<WlCompositor>,
     xdg_output_manager: Option<ZxdgOutputManagerV1>,
     wmbase: Option<XdgWmBase>,
@@ -588,6 +681,8 @@ pub struct WindowState<T> {
     cursor_manager: Option<WpCursorShapeManagerV1>,
     viewporter: Option<WpViewporter>,
     fractional_scale_manager: Option<WpFractionalScaleManagerV1>,
+    alpha_modifier: Option<WpAlphaModifierV1>,
+    session_lock_manager: Option<ExtSessionLockManagerV1>,
     globals: Option<GlobalList>,
 
     // background
@@ -595,12 +690,10 @@ pub struct WindowState<T> {
     display: Option<WlDisplay>,
 
     // base managers
-    seat: Option<WlSeat>,
-    keyboard_state: Option<xkb_keyboard::KeyboardState>,
-
-    pointer: Option<WlPointer>,
-    touch: Option<WlTouch>,
+    seats: Vec<waycrate_shellcore::SeatState>,
     virtual_keyboard: Option<ZwpVirtualKeyboardV1>,
+    session_lock: Option<ExtSessionLockV1>,
+    session_locked: bool,
 
     // states
     namespace: String,
@@ -609,23 +702,32 @@ pub struct WindowState<T> {
     layer: Layer,
     size: Option<(u32, u32)>,
     exclusive_zone: Option<i32>,
+    auto_exclusive_zone: bool,
     margin: Option<(i32, i32, i32, i32)>,
 
     // settings
     use_display_handle: bool,
+    cursor_theme: Option<String>,
+    cursor_size: Option<u32>,
+    themed_cursor: waycrate_shellcore::ThemedCursor,
+    custom_cursor_buffer: Option<cursor::CustomCursorBuffer>,
     loop_handler: Option<LoopHandle<'static, Self>>,
+    timer_deadlines: waycrate_shellcore::TimerDeadlines,
+    // wake the external loop when messages come from timers, see [WindowState::insert_into_loop]
+    dispatch_waker: Option<Ping>,
+    exit_requested: bool,
+    dispatch_error: Option<LayerEventError>,
 
     last_unit_index: usize,
     last_wloutput: Option<WlOutput>,
 
     return_data: Vec<ReturnData<T>>,
-    last_touch_location: (f64, f64),
-    last_touch_id: i32,
 
     xdg_info_cache: Vec<(wl_output::WlOutput, ZxdgOutputInfo)>,
 
     start_mode: StartMode,
     init_finished: bool,
+    capabilities: Capabilities,
     events_transparent: bool,
 }
 
@@ -641,6 +743,9 @@ impl<T> WindowState<T> {
             .position(|unit| unit.id == id && unit.becreated)?;
 
         self.units[index].shell.destroy();
+        if let Some(alpha_modifier_surface) = self.units[index].alpha_modifier_surface.as_ref() {
+            alpha_modifier_surface.destroy();
+        }
         self.units[index].wl_surface.destroy();
 
         if let Some(buffer) = self.units[index].buffer.as_ref() {
@@ -655,6 +760,37 @@ impl<T> WindowState<T> {
     pub fn forget_last_output(&mut self) {
         self.last_wloutput.take();
     }
+
+    /// if the session is locked, or a lock is requested with [ReturnData::RequestSessionLock]
+    pub fn is_session_locked(&self) -> bool {
+        self.session_lock.is_some()
+    }
+
+    /// unlock the session, destroy all the lock surfaces. The normal layer surfaces will be
+    /// shown again. Return None if the session is not locked.
+    pub fn unlock_session(&mut self) -> Option<()> {
+        let lock = self.session_lock.take()?;
+        for unit in self.units.iter().filter(|unit| unit.is_session_lock()) {
+            unit.shell.destroy();
+            if let Some(alpha_modifier_surface) = unit.alpha_modifier_surface.as_ref() {
+                alpha_modifier_surface.destroy();
+            }
+            unit.wl_surface.destroy();
+            if let Some(buffer) = unit.buffer.as_ref() {
+                buffer.destroy()
+            }
+        }
+        self.units.retain(|unit| !unit.is_session_lock());
+        // NOTE: unlock_and_destroy is a protocol error if the compositor has not locked the
+        // session
+        if self.session_locked {
+            lock.unlock_and_destroy();
+        } else {
+            lock.destroy();
+        }
+        self.session_locked = false;
+        Some(())
+    }
 }
 
 /// Simple WindowState, without any data binding or info
@@ -714,6 +850,67 @@ impl StartMode {
     }
 }
 
+/// the exclusive zone which reserves the whole size of a layershell on its anchored edge.
+///
+/// The layershell should be anchored to one edge, or to one edge and the two edges
+/// perpendicular to it, otherwise there is no such edge and it returns None.
+pub fn exclusive_zone_of(anchor: Anchor, (width, height): (u32, u32)) -> Option<i32> {
+    let anchored_vertically = anchor.contains(Anchor::Top) != anchor.contains(Anchor::Bottom);
+    let anchored_horizontally = anchor.contains(Anchor::Left) != anchor.contains(Anchor::Right);
+    match (anchored_vertically, anchored_horizontally) {
+        (true, false) => Some(height as i32),
+        (false, true) => Some(width as i32),
+        _ => None,
+    }
+}
+
+/// set the keyboard interactivity of the layer surface. OnDemand needs the version 4 of
+/// zwlr_layer_surface_v1, before it falls back to Exclusive.
+fn set_keyboard_interactivity_of(
+    layer: &ZwlrLayerSurfaceV1,
+    keyboard_interactivity: zwlr_layer_surface_v1::KeyboardInteractivity,
+) {
+    let keyboard_interactivity = match keyboard_interactivity {
+        zwlr_layer_surface_v1::KeyboardInteractivity::OnDemand if layer.version() < 4 => {
+            log::warn!("OnDemand keyboard interactivity needs layer shell v4, use Exclusive");
+            zwlr_layer_surface_v1::KeyboardInteractivity::Exclusive
+        }
+        keyboard_interactivity => keyboard_interactivity,
+    };
+    layer.set_keyboard_interactivity(keyboard_interactivity);
+}
+
+/// the multiplier of wp_alpha_modifier_v1 for the opacity, from 0.0 to 1.0
+pub fn alpha_multiplier(opacity: f64) -> u32 {
+    (opacity.clamp(0., 1.) * u32::MAX as f64).round() as u32
+}
+
+/// the anchor of a layershell and the size given by the compositor in its last configure, kept
+/// to recompute its exclusive zone with [exclusive_zone_of] when one of them changes. Before the
+/// first configure, the requested size is used.
+#[derive(Debug)]
+struct AutoExclusiveZone(Cell<(Anchor, (u32, u32))>);
+
+impl AutoExclusiveZone {
+    fn new(anchor: Anchor, size: Option<(u32, u32)>) -> Self {
+        Self(Cell::new((anchor, size.unwrap_or((0, 0)))))
+    }
+
+    fn exclusive_zone(&self) -> i32 {
+        let (anchor, size) = self.0.get();
+        // NOTE: without an anchored edge, nothing is reserved, but the layershell is not moved
+        // by the other exclusive zones either
+        exclusive_zone_of(anchor, size).unwrap_or(0)
+    }
+
+    fn update(&self, anchor: Option<Anchor>, size: Option<(u32, u32)>) -> i32 {
+        let (old_anchor, old_size) = self.0.get();
+        self.0
+            .set((anchor.unwrap_or(old_anchor), size.unwrap_or(old_size)));
+        self.exclusive_zone()
+    }
+}
+
 impl WindowWrapper {
     pub fn id(&self) -> id::Id {
         self.id
@@ -721,24 +918,26 @@ impl WindowWrapper {
 }
 
 impl<T> WindowState<T> {
-    /// get a seat from state
-    pub fn get_seat(&self) -> &WlSeat {
-        self.seat.as_ref().unwrap()
+    /// get the first seat from state, see [SeatHandler::get_seat_with_id] for the others
+    ///
+    /// None if the compositor has no seat, or has removed all of them
+    pub fn get_seat(&self) -> Option<&WlSeat> {
+        self.seats.first().map(|seat| &seat.seat)
     }
 
-    /// get the keyboard
+    /// get the keyboard of the first seat
     pub fn get_keyboard(&self) -> Option<&WlKeyboard> {
-        Some(&self.keyboard_state.as_ref()?.keyboard)
+        Some(&self.seats.first()?.keyboard_state.as_ref()?.keyboard)
     }
 
-    /// get the pointer
+    /// get the pointer of the first seat
     pub fn get_pointer(&self) -> Option<&WlPointer> {
-        self.pointer.as_ref()
+        self.seats.first()?.pointer.as_ref()
     }
 
-    /// get the touch
+    /// get the touch of the first seat
     pub fn get_touch(&self) -> Option<&WlTouch> {
-        self.touch.as_ref()
+        self.seats.first()?.touch.as_ref()
     }
 }
 
@@ -940,11 +1139,45 @@ impl<T> WindowState<T> {
         self
     }
 
+    /// compute the exclusive zone from the size and the anchored edge, and recompute it when the
+    /// compositor configures a unit with a new size, or when its anchor changes. It takes the
+    /// place of [WindowState::with_exclusize_zone]
+    pub fn with_auto_exclusive_zone(mut self, auto_exclusive_zone: bool) -> Self {
+        self.auto_exclusive_zone = auto_exclusive_zone;
+        self
+    }
+
+    fn gen_auto_exclusive_zone(&self) -> Option<AutoExclusiveZone> {
+        self.auto_exclusive_zone
+            .then(|| AutoExclusiveZone::new(self.anchor, self.size))
+    }
+
+    fn initial_exclusive_zone(&self) -> Option<i32> {
+        match self.gen_auto_exclusive_zone() {
+            Some(auto_exclusive_zone) => Some(auto_exclusive_zone.exclusive_zone()),
+            None => self.exclusive_zone,
+        }
+    }
+
     /// set layershellev to use display_handle
     pub fn with_use_display_handle(mut self, use_display_handle: bool) -> Self {
         self.use_display_handle = use_display_handle;
         self
     }
+
+    /// set the cursor theme, used when the compositor does not support cursor-shape. If not set,
+    /// `XCURSOR_THEME` is used
+    pub fn with_cursor_theme(mut self, cursor_theme: Option<String>) -> Self {
+        self.cursor_theme = cursor_theme;
+        self
+    }
+
+    /// set the logical size of the cursor, used when the compositor does not support
+    /// cursor-shape. If not set, `XCURSOR_SIZE` is used
+    pub fn with_cursor_size(mut self, cursor_size: Option<u32>) -> Self {
+        self.cursor_size = cursor_size;
+        self
+    }
 }
 
 impl<T> Default for WindowState<T> {
@@ -960,6 +1193,7 @@ impl<T> Default for WindowState<T> {
 
             connection: None,
             event_queue: None,
+            queue_handle: None,
             wl_compositor: None,
             shm: None,
             wmbase: None,
@@ -968,12 +1202,13 @@ impl<T> Default for WindowState<T> {
             xdg_output_manager: None,
             globals: None,
             fractional_scale_manager: None,
+            alpha_modifier: None,
+            session_lock_manager: None,
             virtual_keyboard: None,
+            session_lock: None,
+            session_locked: false,
 
-            seat: None,
-            keyboard_state: None,
-            pointer: None,
-            touch: None,
+            seats: Vec::new(),
 
             namespace: "".to_owned(),
             keyboard_interactivity: zwlr_layer_surface_v1::KeyboardInteractivity::OnDemand,
@@ -981,23 +1216,31 @@ impl<T> Default for WindowState<T> {
             anchor: Anchor::Top | Anchor::Left | Anchor::Right | Anchor::Bottom,
             size: None,
             exclusive_zone: None,
+            auto_exclusive_zone: false,
             margin: None,
 
             use_display_handle: false,
+            cursor_theme: None,
+            cursor_size: None,
+            themed_cursor: waycrate_shellcore::ThemedCursor::default(),
+            custom_cursor_buffer: None,
             loop_handler: None,
+            timer_deadlines: Default::default(),
+            dispatch_waker: None,
+            exit_requested: false,
+            dispatch_error: None,
 
             last_wloutput: None,
             last_unit_index: 0,
 
             return_data: Vec::new(),
-            last_touch_location: (0., 0.),
-            last_touch_id: 0,
             // NOTE: if is some, means it is to be binded, but not now it
             // is not binded
             xdg_info_cache: Vec::new(),
 
             start_mode: StartMode::Active,
             init_finished: false,
+            capabilities: Capabilities::default(),
             events_transparent: false,
         }
     }
@@ -1022,6 +1265,18 @@ impl<T> WindowState<T> {
         self.loop_handler.as_ref()
     }
 
+    /// if the handler has returned [ReturnData::RequestExit] when the state is driven by an
+    /// external event loop, see [WindowState::insert_into_loop]
+    pub fn is_exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    /// take the error raised while dispatching from an external event loop, the loop cannot
+    /// return it, see [WindowState::insert_into_loop]
+    pub fn take_dispatch_error(&mut self) -> Option<LayerEventError> {
+        self.dispatch_error.take()
+    }
+
     /// use [id::Id] to get the mut [WindowStateUnit]
     pub fn get_mut_unit_with_id(&mut self, id: id::Id) -> Option<&mut WindowStateUnit<T>> {
         self.units.iter_mut().find(|unit| unit.id == id)
@@ -1047,12 +1302,6 @@ impl<T> WindowState<T> {
             .iter()
             .position(|unit| Some(&unit.wl_surface) == self.current_surface.as_ref())
     }
-    fn surface_id(&self) -> Option<id::Id> {
-        self.units
-            .iter()
-            .find(|unit| Some(&unit.wl_surface) == self.current_surface.as_ref())
-            .map(|unit| unit.id())
-    }
     /// get the current focused surface id
     pub fn current_surface_id(&self) -> Option<id::Id> {
         self.units
@@ -1083,16 +1332,20 @@ impl<T: 'static> Dispatch<wl_registry::WlRegistry, ()> for WindowState<T> {
                 name,
                 interface,
                 version,
-            } => {
-                if interface == wl_output::WlOutput::interface().name {
-                    let output = proxy.bind::<wl_output::WlOutput, _, _>(name, version, qh, ());
-                    state.outputs.push((name, output.clone()));
-                    state
-                        .message
-                        .push((None, DispatchMessageInner::NewDisplay(output)));
-                }
+            } if interface == wl_output::WlOutput::interface().name => {
+                let output = proxy.bind::<wl_output::WlOutput, _, _>(name, version, qh, ());
+                state.outputs.push((name, output.clone()));
+                state
+                    .message
+                    .push((None, DispatchMessageInner::NewDisplay(output)));
+            }
+            wl_registry::Event::Global {
+                name, interface, ..
+            } if interface == wl_seat::WlSeat::interface().name => {
+                seat::add_seat(state, proxy, name, qh);
             }
             wl_registry::Event::GlobalRemove { name } => {
+                seat::remove_seat(state, name);
                 if state
                     .last_wloutput
                     .as_ref()
@@ -1109,484 +1362,55 @@ impl<T: 'static> Dispatch<wl_registry::WlRegistry, ()> for WindowState<T> {
     }
 }
 
-impl<T: 'static> Dispatch<wl_seat::WlSeat, ()> for WindowState<T> {
+impl<T: 'static> Dispatch<wl_seat::WlSeat, SeatId> for WindowState<T> {
     fn event(
         state: &mut Self,
         seat: &wl_seat::WlSeat,
         event: <wl_seat::WlSeat as Proxy>::Event,
-        _data: &(),
+        seat_id: &SeatId,
         _conn: &Connection,
         qh: &wayland_client::QueueHandle<Self>,
     ) {
-        use xkb_keyboard::KeyboardState;
-        if let wl_seat::Event::Capabilities {
-            capabilities: WEnum::Value(capabilities),
-        } = event
-        {
-            if capabilities.contains(wl_seat::Capability::Keyboard) {
-                state.keyboard_state = Some(KeyboardState::new(seat.get_keyboard(qh, ())));
-            }
-            if capabilities.contains(wl_seat::Capability::Pointer) {
-                state.pointer = Some(seat.get_pointer(qh, ()));
-            }
-            if capabilities.contains(wl_seat::Capability::Touch) {
-                state.touch = Some(seat.get_touch(qh, ()));
-            }
-        }
+        seat::seat_event(state, seat, event, *seat_id, qh);
     }
 }
 
-impl<T> Dispatch<wl_keyboard::WlKeyboard, ()> for WindowState<T> {
+impl<T: 'static> Dispatch<wl_keyboard::WlKeyboard, SeatId> for WindowState<T> {
     fn event(
         state: &mut Self,
-        _wl_keyboard: &wl_keyboard::WlKeyboard,
+        _proxy: &wl_keyboard::WlKeyboard,
         event: <wl_keyboard::WlKeyboard as Proxy>::Event,
-        _data: &(),
+        seat_id: &SeatId,
         _conn: &Connection,
-        _qhandle: &QueueHandle<Self>,
+        _qhandle: &wayland_client::QueueHandle<Self>,
     ) {
-        use keyboard::*;
-        use xkb_keyboard::ElementState;
-        let surface_id = state.surface_id();
-        let keyboard_state = state.keyboard_state.as_mut().unwrap();
-        match event {
-            wl_keyboard::Event::Keymap { format, fd, size } => match format {
-                WEnum::Value(KeymapFormat::XkbV1) => {
-                    let context = &mut keyboard_state.xkb_context;
-                    context.set_keymap_from_fd(fd, size as usize)
-                }
-                WEnum::Value(KeymapFormat::NoKeymap) => {
-                    log::warn!("non-xkb compatible keymap")
-                }
-                _ => unreachable!(),
-            },
-            wl_keyboard::Event::Enter { .. } => {
-                if let (Some(token), Some(loop_handle)) = (
-                    keyboard_state.repeat_token.take(),
-                    state.loop_handler.as_ref(),
-                ) {
-                    loop_handle.remove(token);
-                }
-            }
-            wl_keyboard::Event::Leave { .. } => {
-                keyboard_state.current_repeat = None;
-                state.message.push((
-                    surface_id,
-                    DispatchMessageInner::ModifiersChanged(ModifiersState::empty()),
-                ));
-                state
-                    .message
-                    .push((surface_id, DispatchMessageInner::Unfocus));
-                if let (Some(token), Some(loop_handle)) = (
-                    keyboard_state.repeat_token.take(),
-                    state.loop_handler.as_ref(),
-                ) {
-                    loop_handle.remove(token);
-                }
-            }
-            wl_keyboard::Event::Key {
-                state: keystate,
-                key,
-                ..
-            } => {
-                let pressed_state = match keystate {
-                    WEnum::Value(KeyState::Pressed) => ElementState::Pressed,
-                    WEnum::Value(KeyState::Released) => ElementState::Released,
-                    _ => {
-                        return;
-                    }
-                };
-                let key = key + 8;
-                if let Some(mut key_context) = keyboard_state.xkb_context.key_context() {
-                    let event = key_context.process_key_event(key, pressed_state, false);
-                    let event = DispatchMessageInner::KeyboardInput {
-                        event,
-                        is_synthetic: false,
-                    };
-                    state.message.push((surface_id, event));
-                }
-
-                match pressed_state {
-                    ElementState::Pressed => {
-                        let delay = match keyboard_state.repeat_info {
-                            RepeatInfo::Repeat { delay, .. } => delay,
-                            RepeatInfo::Disable => return,
-                        };
-                        if !keyboard_state
-                            .xkb_context
-                            .keymap_mut()
-                            .unwrap()
-                            .key_repeats(key)
-                        {
-                            return;
-                        }
-
-                        keyboard_state.current_repeat = Some(key);
-
-                        if let (Some(token), Some(loop_handle)) = (
-                            keyboard_state.repeat_token.take(),
-                            state.loop_handler.as_ref(),
-                        ) {
-                            loop_handle.remove(token);
-                        }
-                        let timer = Timer::from_duration(delay);
-
-                        if let Some(looph) = state.loop_handler.as_ref() {
-                            keyboard_state.repeat_token = looph
-                                .insert_source(timer, move |_, _, state| {
-                                    let keyboard_state = match state.keyboard_state.as_mut() {
-                                        Some(keyboard_state) => keyboard_state,
-                                        None => return TimeoutAction::Drop,
-                                    };
-                                    let repeat_keycode = match keyboard_state.current_repeat {
-                                        Some(repeat_keycode) => repeat_keycode,
-                                        None => return TimeoutAction::Drop,
-                                    };
-                                    // NOTE: not the same key
-                                    if repeat_keycode != key {
-                                        return TimeoutAction::Drop;
-                                    }
-                                    if let Some(mut key_context) =
-                                        keyboard_state.xkb_context.key_context()
-                                    {
-                                        let event = key_context.process_key_event(
-                                            repeat_keycode,
-                                            pressed_state,
-                                            false,
-                                        );
-                                        let event = DispatchMessageInner::KeyboardInput {
-                                            event,
-                                            is_synthetic: false,
-                                        };
-                                        state.message.push((surface_id, event));
-                                    }
-                                    match keyboard_state.repeat_info {
-                                        RepeatInfo::Repeat { gap, .. } => {
-                                            TimeoutAction::ToDuration(gap)
-                                        }
-                                        RepeatInfo::Disable => TimeoutAction::Drop,
-                                    }
-                                })
-                                .ok();
-                        }
-                    }
-                    ElementState::Released => {
-                        if keyboard_state.repeat_info != RepeatInfo::Disable
-                            && keyboard_state
-                                .xkb_context
-                                .keymap_mut()
-                                .unwrap()
-                                .key_repeats(key)
-                            && Some(key) == keyboard_state.current_repeat
-                        {
-                            keyboard_state.current_repeat = None;
-                            if let (Some(token), Some(loop_handle)) = (
-                                keyboard_state.repeat_token.take(),
-                                state.loop_handler.as_ref(),
-                            ) {
-                                loop_handle.remove(token);
-                            }
-                        }
-                    }
-                }
-            }
-            wl_keyboard::Event::Modifiers {
-                mods_depressed,
-                mods_locked,
-                mods_latched,
-                group,
-                ..
-            } => {
-                let xkb_context = &mut keyboard_state.xkb_context;
-                let xkb_state = match xkb_context.state_mut() {
-                    Some(state) => state,
-                    None => return,
-                };
-                xkb_state.update_modifiers(mods_depressed, mods_latched, mods_locked, 0, 0, group);
-                let modifiers = xkb_state.modifiers();
-
-                state.message.push((
-                    state.surface_id(),
-                    DispatchMessageInner::ModifiersChanged(modifiers.into()),
-                ))
-            }
-            wl_keyboard::Event::RepeatInfo { rate, delay } => {
-                keyboard_state.repeat_info = if rate == 0 {
-                    // Stop the repeat once we get a disable event.
-                    keyboard_state.current_repeat = None;
-                    if let (Some(token), Some(loop_handle)) = (
-                        keyboard_state.repeat_token.take(),
-                        state.loop_handler.as_ref(),
-                    ) {
-                        loop_handle.remove(token);
-                    }
-                    RepeatInfo::Disable
-                } else {
-                    let gap = Duration::from_micros(1_000_000 / rate as u64);
-                    let delay = Duration::from_millis(delay as u64);
-                    RepeatInfo::Repeat { gap, delay }
-                };
-            }
-            _ => {}
-        }
+        seat::keyboard_event(state, event, *seat_id);
     }
 }
 
-impl<T> Dispatch<wl_touch::WlTouch, ()> for WindowState<T> {
+impl<T: 'static> Dispatch<wl_touch::WlTouch, SeatId> for WindowState<T> {
     fn event(
         state: &mut Self,
         _proxy: &wl_touch::WlTouch,
         event: <wl_touch::WlTouch as Proxy>::Event,
-        _data: &(),
+        seat_id: &SeatId,
         _conn: &Connection,
-        _qhandle: &QueueHandle<Self>,
+        _qhandle: &wayland_client::QueueHandle<Self>,
     ) {
-        match event {
-            wl_touch::Event::Down {
-                serial,
-                time,
-                surface,
-                id,
-                x,
-                y,
-            } => {
-                state.last_touch_location = (x, y);
-                state.message.push((
-                    state.get_id_from_surface(&surface),
-                    DispatchMessageInner::TouchDown {
-                        serial,
-                        time,
-                        id,
-                        x,
-                        y,
-                    },
-                ))
-            }
-            wl_touch::Event::Cancel => {
-                let (x, y) = state.last_touch_location;
-                let id = state.last_touch_id;
-                state
-                    .message
-                    .push((None, DispatchMessageInner::TouchCancel { id, x, y }))
-            }
-            wl_touch::Event::Up { serial, time, id } => {
-                let (x, y) = state.last_touch_location;
-                state.message.push((
-                    None,
-                    DispatchMessageInner::TouchUp {
-                        serial,
-                        time,
-                        id,
-                        x,
-                        y,
-                    },
-                ));
-            }
-            wl_touch::Event::Motion { time, id, x, y } => {
-                state.last_touch_location = (x, y);
-                state
-                    .message
-                    .push((None, DispatchMessageInner::TouchMotion { time, id, x, y }));
-            }
-            _ => {}
-        }
+        seat::touch_event(state, event, *seat_id);
     }
 }
 
-impl<T> Dispatch<wl_pointer::WlPointer, ()> for WindowState<T> {
+impl<T: 'static> Dispatch<wl_pointer::WlPointer, SeatId> for WindowState<T> {
     fn event(
         state: &mut Self,
         pointer: &wl_pointer::WlPointer,
         event: <wl_pointer::WlPointer as Proxy>::Event,
-        _data: &(),
+        seat_id: &SeatId,
         _conn: &Connection,
         _qhandle: &wayland_client::QueueHandle<Self>,
     ) {
-        let scale = state
-            .surface_id()
-            .and_then(|id| state.get_unit_with_id(id))
-            .map(|unit| unit.scale_float())
-            .unwrap_or(1.0);
-        match event {
-            wl_pointer::Event::Axis { time, axis, value } => match axis {
-                WEnum::Value(axis) => {
-                    let (mut horizontal, mut vertical) = <(AxisScroll, AxisScroll)>::default();
-                    match axis {
-                        wl_pointer::Axis::VerticalScroll => {
-                            vertical.absolute = value;
-                        }
-                        wl_pointer::Axis::HorizontalScroll => {
-                            horizontal.absolute = value;
-                        }
-                        _ => unreachable!(),
-                    };
-
-                    state.message.push((
-                        state.surface_id(),
-                        DispatchMessageInner::Axis {
-                            time,
-                            scale,
-                            horizontal,
-                            vertical,
-                            source: None,
-                        },
-                    ))
-                }
-                WEnum::Unknown(unknown) => {
-                    log::warn!(target: "layershellev", "{}: invalid pointer axis: {:x}", pointer.id(), unknown);
-                }
-            },
-            wl_pointer::Event::AxisStop { time, axis } => match axis {
-                WEnum::Value(axis) => {
-                    let (mut horizontal, mut vertical) = <(AxisScroll, AxisScroll)>::default();
-                    match axis {
-                        wl_pointer::Axis::VerticalScroll => vertical.stop = true,
-                        wl_pointer::Axis::HorizontalScroll => horizontal.stop = true,
-
-                        _ => unreachable!(),
-                    }
-
-                    state.message.push((
-                        state.surface_id(),
-                        DispatchMessageInner::Axis {
-                            time,
-                            scale,
-                            horizontal,
-                            vertical,
-                            source: None,
-                        },
-                    ));
-                }
-
-                WEnum::Unknown(unknown) => {
-                    log::warn!(target: "layershellev", "{}: invalid pointer axis: {:x}", pointer.id(), unknown);
-                }
-            },
-            wl_pointer::Event::AxisSource { axis_source } => match axis_source {
-                WEnum::Value(source) => state.message.push((
-                    state.surface_id(),
-                    DispatchMessageInner::Axis {
-                        horizontal: AxisScroll::default(),
-                        vertical: AxisScroll::default(),
-                        scale,
-                        source: Some(source),
-                        time: 0,
-                    },
-                )),
-                WEnum::Unknown(unknown) => {
-                    log::warn!(target: "layershellev", "unknown pointer axis source: {:x}", unknown);
-                }
-            },
-            wl_pointer::Event::AxisDiscrete { axis, discrete } => match axis {
-                WEnum::Value(axis) => {
-                    let (mut horizontal, mut vertical) = <(AxisScroll, AxisScroll)>::default();
-                    match axis {
-                        wl_pointer::Axis::VerticalScroll => {
-                            vertical.discrete = discrete;
-                        }
-
-                        wl_pointer::Axis::HorizontalScroll => {
-                            horizontal.discrete = discrete;
-                        }
-
-                        _ => unreachable!(),
-                    };
-
-                    state.message.push((
-                        state.surface_id(),
-                        DispatchMessageInner::Axis {
-                            time: 0,
-                            scale,
-                            horizontal,
-                            vertical,
-                            source: None,
-                        },
-                    ));
-                }
-
-                WEnum::Unknown(unknown) => {
-                    log::warn!(target: "layershellev", "{}: invalid pointer axis: {:x}", pointer.id(), unknown);
-                }
-            },
-            wl_pointer::Event::Button {
-                state: btnstate,
-                serial,
-                button,
-                time,
-            } => {
-                state.message.push((
-                    state.surface_id(),
-                    DispatchMessageInner::MouseButton {
-                        state: btnstate,
-                        serial,
-                        button,
-                        time,
-                    },
-                ));
-            }
-            wl_pointer::Event::Leave { .. } => {
-                state.current_surface = None;
-                state
-                    .message
-                    .push((state.surface_id(), DispatchMessageInner::MouseLeave));
-                if let Some(keyboard_state) = state.keyboard_state.as_mut() {
-                    keyboard_state.current_repeat = None;
-                }
-            }
-            wl_pointer::Event::Enter {
-                serial,
-                surface,
-                surface_x,
-                surface_y,
-            } => {
-                state.current_surface = Some(surface.clone());
-                let surface_id = state.surface_id();
-
-                if let Some(unit) = surface_id.and_then(|id| state.get_unit_with_id(id)) {
-                    state.last_unit_index = state
-                        .outputs
-                        .iter()
-                        .position(|(_, output)| {
-                            unit.wl_output
-                                .as_ref()
-                                .is_some_and(|uoutput| uoutput == output)
-                        })
-                        .unwrap_or(0);
-                }
-
-                state.message.push((
-                    surface_id,
-                    DispatchMessageInner::MouseEnter {
-                        pointer: pointer.clone(),
-                        serial,
-                        surface_x,
-                        surface_y,
-                    },
-                ));
-                if let Some(id) = surface_id {
-                    state
-                        .message
-                        .push((Some(id), DispatchMessageInner::Focused(id)));
-                }
-            }
-            wl_pointer::Event::Motion {
-                time,
-                surface_x,
-                surface_y,
-            } => {
-                state.message.push((
-                    state.surface_id(),
-                    DispatchMessageInner::MouseMotion {
-                        time,
-                        surface_x,
-                        surface_y,
-                    },
-                ));
-            }
-            _ => {
-                // TODO: not now
-            }
-        }
+        seat::pointer_event(state, pointer, event, *seat_id);
     }
 }
 
@@ -1625,7 +1449,10 @@ impl<T> Dispatch<zwlr_layer_surface_v1::ZwlrLayerSurfaceV1, ()> for WindowState<
             else {
                 return;
             };
-            state.units[unit_index].size = (width, height);
+            let unit = &mut state.units[unit_index];
+            unit.size = (width, height);
+            // NOTE: the exclusive zone is applied with the commit of the next frame
+            unit.update_auto_exclusive_zone(surface, None, Some((width, height)));
 
             state.message.push((
                 Some(state.units[unit_index].id),
@@ -1663,69 +1490,94 @@ impl<T> Dispatch<xdg_popup::XdgPopup, ()> for WindowState<T> {
     }
 }
 
-impl<T> Dispatch<zxdg_output_v1::ZxdgOutputV1, ()> for WindowState<T> {
+impl<T> Dispatch<ext_session_lock_v1::ExtSessionLockV1, ()> for WindowState<T> {
     fn event(
         state: &mut Self,
-        proxy: &zxdg_output_v1::ZxdgOutputV1,
-        event: <zxdg_output_v1::ZxdgOutputV1 as Proxy>::Event,
+        _proxy: &ext_session_lock_v1::ExtSessionLockV1,
+        event: <ext_session_lock_v1::ExtSessionLockV1 as Proxy>::Event,
         _data: &(),
         _conn: &Connection,
         _qhandle: &QueueHandle<Self>,
     ) {
-        if state.is_with_target() && !state.init_finished {
-            let Some((_, xdg_info)) = state
-                .xdg_info_cache
-                .iter_mut()
-                .find(|(_, info)| info.zxdgoutput == *proxy)
-            else {
-                return;
-            };
-            match event {
-                zxdg_output_v1::Event::LogicalSize { width, height } => {
-                    xdg_info.logical_size = (width, height);
-                }
-                zxdg_output_v1::Event::LogicalPosition { x, y } => {
-                    xdg_info.position = (x, y);
-                }
-                zxdg_output_v1::Event::Name { name } => {
-                    xdg_info.name = name;
-                }
-                zxdg_output_v1::Event::Description { description } => {
-                    xdg_info.description = description;
-                }
-                _ => {}
-            };
-            return;
-        }
-        let Some(index) = state.units.iter().position(|info| {
-            info.zxdgoutput
-                .as_ref()
-                .is_some_and(|zxdgoutput| zxdgoutput.zxdgoutput == *proxy)
+        match event {
+            ext_session_lock_v1::Event::Locked => {
+                state.session_locked = true;
+                state
+                    .message
+                    .push((None, DispatchMessageInner::SessionLocked));
+            }
+            ext_session_lock_v1::Event::Finished => {
+                state
+                    .message
+                    .push((None, DispatchMessageInner::SessionLockFinished));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<T> Dispatch<ext_session_lock_surface_v1::ExtSessionLockSurfaceV1, ()> for WindowState<T> {
+    fn event(
+        state: &mut Self,
+        surface: &ext_session_lock_surface_v1::ExtSessionLockSurfaceV1,
+        event: <ext_session_lock_surface_v1::ExtSessionLockSurfaceV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let ext_session_lock_surface_v1::Event::Configure {
+            serial,
+            width,
+            height,
+        } = event
+        {
+            surface.ack_configure(serial);
+
+            let Some(unit_index) = state.units.iter().position(|unit| unit.shell == *surface)
+            else {
+                return;
+            };
+            state.units[unit_index].size = (width, height);
+
+            state.message.push((
+                Some(state.units[unit_index].id),
+                DispatchMessageInner::RefreshSurface { width, height },
+            ));
+        }
+    }
+}
+
+impl<T> Dispatch<zxdg_output_v1::ZxdgOutputV1, ()> for WindowState<T> {
+    fn event(
+        state: &mut Self,
+        proxy: &zxdg_output_v1::ZxdgOutputV1,
+        event: <zxdg_output_v1::ZxdgOutputV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if state.is_with_target() && !state.init_finished {
+            let Some((_, xdg_info)) = state
+                .xdg_info_cache
+                .iter_mut()
+                .find(|(_, info)| info.zxdgoutput() == proxy)
+            else {
+                return;
+            };
+            xdg_info.update(event);
+            return;
+        }
+        let Some(index) = state.units.iter().position(|info| {
+            info.zxdgoutput
+                .as_ref()
+                .is_some_and(|zxdgoutput| zxdgoutput.zxdgoutput() == proxy)
         }) else {
             return;
         };
         let info = &mut state.units[index];
         let xdg_info = info.zxdgoutput.as_mut().unwrap();
-        let change_type = match event {
-            zxdg_output_v1::Event::LogicalSize { width, height } => {
-                xdg_info.logical_size = (width, height);
-                XdgInfoChangedType::Size
-            }
-            zxdg_output_v1::Event::LogicalPosition { x, y } => {
-                xdg_info.position = (x, y);
-                XdgInfoChangedType::Position
-            }
-            zxdg_output_v1::Event::Name { name } => {
-                xdg_info.name = name;
-                XdgInfoChangedType::Name
-            }
-            zxdg_output_v1::Event::Description { description } => {
-                xdg_info.description = description;
-                XdgInfoChangedType::Description
-            }
-            _ => {
-                return;
-            }
+        let Some(change_type) = xdg_info.update(event) else {
+            return;
         };
         state.message.push((
             Some(state.units[index].id),
@@ -1763,6 +1615,26 @@ impl<T> Dispatch<wp_fractional_scale_v1::WpFractionalScaleV1, ()> for WindowStat
     }
 }
 
+impl<T> Dispatch<WlCallback, id::Id> for WindowState<T> {
+    fn event(
+        state: &mut Self,
+        _proxy: &WlCallback,
+        event: <WlCallback as Proxy>::Event,
+        id: &id::Id,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let wl_callback::Event::Done { callback_data } = event {
+            state.message.push((
+                Some(*id),
+                DispatchMessageInner::FrameDone {
+                    time: callback_data,
+                },
+            ));
+        }
+    }
+}
+
 delegate_noop!(@<T> WindowState<T>: ignore WlCompositor); // WlCompositor is need to create a surface
 delegate_noop!(@<T> WindowState<T>: ignore WlSurface); // surface is the base needed to show buffer
 delegate_noop!(@<T> WindowState<T>: ignore WlOutput); // output is need to place layer_shell, although here
@@ -1787,6 +1659,9 @@ delegate_noop!(@<T> WindowState<T>: ignore ZxdgOutputManagerV1);
 delegate_noop!(@<T> WindowState<T>: ignore WpFractionalScaleManagerV1);
 delegate_noop!(@<T> WindowState<T>: ignore XdgPositioner);
 delegate_noop!(@<T> WindowState<T>: ignore XdgWmBase);
+delegate_noop!(@<T> WindowState<T>: ignore ExtSessionLockManagerV1);
+delegate_noop!(@<T> WindowState<T>: ignore WpAlphaModifierV1);
+delegate_noop!(@<T> WindowState<T>: ignore WpAlphaModifierSurfaceV1);
 
 impl<T: 'static> WindowState<T> {
     /// build a new WindowState
@@ -1812,10 +1687,8 @@ impl<T: 'static> WindowState<T> {
 
         let shm = globals.bind::<WlShm, _, _>(&qh, 1..=1, ())?;
         self.shm = Some(shm);
-        self.seat = Some(globals.bind::<WlSeat, _, _>(&qh, 1..=1, ())?);
 
-        let wmbase = globals.bind::<XdgWmBase, _, _>(&qh, 2..=6, ())?;
-        self.wmbase = Some(wmbase);
+        let wmbase = globals.bind::<XdgWmBase, _, _>(&qh, 2..=6, ()).ok();
 
         let cursor_manager = globals
             .bind::<WpCursorShapeManagerV1, _, _>(&qh, 1..=1, ())
@@ -1825,13 +1698,58 @@ impl<T: 'static> WindowState<T> {
         let _ = connection.display().get_registry(&qh, ()); // so if you want WlOutput, you need to
                                                             // register this
 
-        let xdg_output_manager = globals.bind::<ZxdgOutputManagerV1, _, _>(&qh, 1..=3, ())?; // bind
-                                                                                             // xdg_output_manager
+        let xdg_output_manager = globals
+            .bind::<ZxdgOutputManagerV1, _, _>(&qh, 1..=3, ())
+            .ok();
 
         let fractional_scale_manager = globals
             .bind::<WpFractionalScaleManagerV1, _, _>(&qh, 1..=1, ())
             .ok();
 
+        let session_lock_manager = globals
+            .bind::<ExtSessionLockManagerV1, _, _>(&qh, 1..=1, ())
+            .ok();
+
+        let alpha_modifier = globals.bind::<WpAlphaModifierV1, _, _>(&qh, 1..=1, ()).ok();
+
+        // NOTE: the layer shell is bound for every surface, so only its version is checked here
+        let layer_shell_global_version = globals.contents().with_list(|list| {
+            list.iter()
+                .find(|global| global.interface == ZwlrLayerShellV1::interface().name)
+                .map(|global| global.version)
+        });
+        let layer_shell_version =
+            layer_shell_global_version.and_then(|version| bound_version(version, 3..=5));
+        let virtual_keyboard_version = globals.contents().with_list(|list| {
+            list.iter()
+                .find(|global| global.interface == ZwpVirtualKeyboardManagerV1::interface().name)
+                .and_then(|global| bound_version(global.version, 1..=1))
+        });
+        self.capabilities = Capabilities {
+            layer_shell: layer_shell_version,
+            xdg_wm_base: wmbase.as_ref().map(Proxy::version),
+            xdg_output: xdg_output_manager.as_ref().map(Proxy::version),
+            cursor_shape: cursor_manager.as_ref().map(Proxy::version),
+            viewporter: viewporter.as_ref().map(Proxy::version),
+            fractional_scale: fractional_scale_manager.as_ref().map(Proxy::version),
+            session_lock: session_lock_manager.as_ref().map(Proxy::version),
+            alpha_modifier: alpha_modifier.as_ref().map(Proxy::version),
+            virtual_keyboard: virtual_keyboard_version,
+        };
+        log::info!(
+            "the capabilities of the compositor: {:?}",
+            self.capabilities
+        );
+        if layer_shell_version.is_none() && !self.is_background() {
+            return Err(LayerEventError::BindError(
+                if layer_shell_global_version.is_some() {
+                    BindError::UnsupportedVersion
+                } else {
+                    BindError::NotPresent
+                },
+            ));
+        }
+
         event_queue.blocking_dispatch(&mut self)?; // then make a dispatch
 
         // do the step before, you get empty list
@@ -1854,7 +1772,15 @@ impl<T: 'static> WindowState<T> {
             let mut output = None;
 
             if let StartMode::TargetScreen(name) = self.start_mode.clone() {
+                // NOTE: without xdg_output the names are unknown, so the compositor picks the
+                // output
+                if xdg_output_manager.is_none() {
+                    log::warn!("zxdg_output_manager_v1 is not supported, cannot find {name}");
+                }
                 for (_, output_display) in &self.outputs {
+                    let Some(xdg_output_manager) = xdg_output_manager.as_ref() else {
+                        break;
+                    };
                     let zxdgoutput = xdg_output_manager.get_xdg_output(output_display, &qh, ());
                     self.xdg_info_cache
                         .push((output_display.clone(), ZxdgOutputInfo::new(zxdgoutput)));
@@ -1863,7 +1789,7 @@ impl<T: 'static> WindowState<T> {
                 if let Some(cache) = self
                     .xdg_info_cache
                     .iter()
-                    .find(|(_, info)| info.name == *name)
+                    .find(|(_, info)| info.get_name() == name)
                     .cloned()
                 {
                     output = Some(cache.clone());
@@ -1877,7 +1803,7 @@ impl<T: 'static> WindowState<T> {
 
             let wl_surface = wmcompositer.create_surface(&qh, ()); // and create a surface. if two or more,
             let layer_shell = globals
-                .bind::<ZwlrLayerShellV1, _, _>(&qh, 3..=4, ())
+                .bind::<ZwlrLayerShellV1, _, _>(&qh, 3..=5, ())
                 .unwrap();
             let layer = layer_shell.get_layer_surface(
                 &wl_surface,
@@ -1888,12 +1814,12 @@ impl<T: 'static> WindowState<T> {
                 (),
             );
             layer.set_anchor(self.anchor);
-            layer.set_keyboard_interactivity(self.keyboard_interactivity);
+            set_keyboard_interactivity_of(&layer, self.keyboard_interactivity);
             if let Some((init_w, init_h)) = self.size {
                 layer.set_size(init_w, init_h);
             }
 
-            if let Some(zone) = self.exclusive_zone {
+            if let Some(zone) = self.initial_exclusive_zone() {
                 layer.set_exclusive_zone(zone);
             }
 
@@ -1934,6 +1860,10 @@ impl<T: 'static> WindowState<T> {
                 binding: None,
                 becreated: false,
                 wl_output: None,
+                alpha_modifier_surface: None,
+                margin: Cell::new(self.margin.unwrap_or_default()),
+                anchor: Cell::new(self.anchor),
+                auto_exclusive_zone: self.gen_auto_exclusive_zone(),
                 scale: 120,
             });
         } else {
@@ -1941,7 +1871,7 @@ impl<T: 'static> WindowState<T> {
             for (_, output_display) in displays.iter() {
                 let wl_surface = wmcompositer.create_surface(&qh, ()); // and create a surface. if two or more,
                 let layer_shell = globals
-                    .bind::<ZwlrLayerShellV1, _, _>(&qh, 3..=4, ())
+                    .bind::<ZwlrLayerShellV1, _, _>(&qh, 3..=5, ())
                     .unwrap();
                 let layer = layer_shell.get_layer_surface(
                     &wl_surface,
@@ -1952,12 +1882,12 @@ impl<T: 'static> WindowState<T> {
                     (),
                 );
                 layer.set_anchor(self.anchor);
-                layer.set_keyboard_interactivity(self.keyboard_interactivity);
+                set_keyboard_interactivity_of(&layer, self.keyboard_interactivity);
                 if let Some((init_w, init_h)) = self.size {
                     layer.set_size(init_w, init_h);
                 }
 
-                if let Some(zone) = self.exclusive_zone {
+                if let Some(zone) = self.initial_exclusive_zone() {
                     layer.set_exclusive_zone(zone);
                 }
 
@@ -1972,7 +1902,9 @@ impl<T: 'static> WindowState<T> {
                 }
                 wl_surface.commit();
 
-                let zxdgoutput = xdg_output_manager.get_xdg_output(output_display, &qh, ());
+                let zxdgoutput = xdg_output_manager.as_ref().map(|manager| {
+                    ZxdgOutputInfo::new(manager.get_xdg_output(output_display, &qh, ()))
+                });
                 let mut fractional_scale = None;
                 if let Some(ref fractional_scale_manager) = fractional_scale_manager {
                     fractional_scale =
@@ -1993,12 +1925,16 @@ impl<T: 'static> WindowState<T> {
                     size: (0, 0),
                     buffer: None,
                     shell: Shell::LayerShell(layer),
-                    zxdgoutput: Some(ZxdgOutputInfo::new(zxdgoutput)),
+                    zxdgoutput,
                     fractional_scale,
                     viewport,
                     binding: None,
                     becreated: false,
                     wl_output: Some(output_display.clone()),
+                    alpha_modifier_surface: None,
+                    margin: Cell::new(self.margin.unwrap_or_default()),
+                    anchor: Cell::new(self.anchor),
+                    auto_exclusive_zone: self.gen_auto_exclusive_zone(),
                     scale: 120,
                 });
             }
@@ -2010,8 +1946,11 @@ impl<T: 'static> WindowState<T> {
         self.globals = Some(globals);
         self.wl_compositor = Some(wmcompositer);
         self.fractional_scale_manager = fractional_scale_manager;
+        self.session_lock_manager = session_lock_manager;
+        self.alpha_modifier = alpha_modifier;
         self.cursor_manager = cursor_manager;
-        self.xdg_output_manager = Some(xdg_output_manager);
+        self.xdg_output_manager = xdg_output_manager;
+        self.wmbase = wmbase;
         self.connection = Some(connection);
 
         Ok(self)
@@ -2055,46 +1994,14 @@ impl<T: 'static> WindowState<T> {
         Message: std::marker::Send + 'static,
         F: FnMut(LayerEvent<T, Message>, &mut WindowState<T>, Option<id::Id>) -> ReturnData<T>,
     {
-        let globals = self.globals.take().unwrap();
-        let event_queue = self.event_queue.take().unwrap();
-        let qh = event_queue.handle();
-        let wmcompositer = self.wl_compositor.take().unwrap();
-        let shm = self.shm.take().unwrap();
-        let fractional_scale_manager = self.fractional_scale_manager.take();
-        let cursor_manager: Option<WpCursorShapeManagerV1> = self.cursor_manager.take();
-        let xdg_output_manager = self.xdg_output_manager.take().unwrap();
-        let connection = self.connection.take().unwrap();
-        let mut init_event = None;
-        let wmbase = self.wmbase.take().unwrap();
-        let viewporter = self.viewporter.take();
-
-        while !matches!(init_event, Some(ReturnData::None)) {
-            match init_event {
-                None => {
-                    init_event = Some(event_handler(LayerEvent::InitRequest, &mut self, None));
-                }
-                Some(ReturnData::RequestBind) => {
-                    init_event = Some(event_handler(
-                        LayerEvent::BindProvide(&globals, &qh),
-                        &mut self,
-                        None,
-                    ));
-                }
-                Some(ReturnData::RequestCompositor) => {
-                    init_event = Some(event_handler(
-                        LayerEvent::CompositorProvide(&wmcompositer, &qh),
-                        &mut self,
-                        None,
-                    ));
-                }
-                _ => panic!("Not provide server here"),
-            }
-        }
+        self.handle_init_requests(&mut event_handler);
 
         let mut event_loop: EventLoop<Self> =
             EventLoop::try_new().expect("Failed to initialize the event loop");
 
-        WaylandSource::new(connection.clone(), event_queue)
+        let event_queue = self.event_queue.take().unwrap();
+        let connection = self.connection.clone().unwrap();
+        WaylandSource::new(connection, event_queue)
             .insert(event_loop.handle())
             .expect("Failed to init wayland source");
 
@@ -2120,257 +2027,289 @@ impl<T: 'static> WindowState<T> {
                 events_local.push(message);
             }
         });
-        'out: loop {
+        loop {
             event_loop.dispatch(Duration::from_millis(1), &mut self)?;
 
-            let mut messages = Vec::new();
-            std::mem::swap(&mut messages, &mut self.message);
-            for msg in messages.iter() {
-                match msg {
-                    (Some(unit_index), DispatchMessageInner::RefreshSurface { width, height }) => {
-                        let Some(index) = self.units.iter().position(|unit| unit.id == *unit_index)
-                        else {
-                            continue;
-                        };
-                        if self.units[index].buffer.is_none() && !self.use_display_handle {
-                            let mut file = tempfile::tempfile()?;
-                            let ReturnData::WlBuffer(buffer) = event_handler(
-                                LayerEvent::RequestBuffer(&mut file, &shm, &qh, *width, *height),
-                                &mut self,
-                                Some(*unit_index),
-                            ) else {
-                                panic!("You cannot return this one");
-                            };
-                            let surface = &self.units[index].wl_surface;
-                            surface.attach(Some(&buffer), 0, 0);
-                            self.units[index].buffer = Some(buffer);
-                        } else {
-                            event_handler(
-                                LayerEvent::RequestMessages(&DispatchMessage::RequestRefresh {
-                                    width: *width,
-                                    height: *height,
-                                    is_created: self.units[index].becreated,
-                                    scale_float: self.units[index].scale_float(),
-                                }),
-                                &mut self,
-                                Some(*unit_index),
-                            );
-                        }
+            let mut local_events = events.lock().unwrap();
+            let mut swapped_events: Vec<Message> = vec![];
+            std::mem::swap(&mut *local_events, &mut swapped_events);
+            drop(local_events);
 
-                        if let Some(unit) = self.get_unit_with_id(*unit_index) {
-                            unit.wl_surface.commit();
-                        }
-                    }
-                    (index_info, DispatchMessageInner::XdgInfoChanged(change_type)) => {
-                        event_handler(
-                            LayerEvent::XdgInfoChanged(*change_type),
-                            &mut self,
-                            *index_info,
-                        );
-                    }
-                    (_, DispatchMessageInner::NewDisplay(output_display)) => {
-                        if !self.is_allscreens() {
-                            continue;
-                        }
-                        let wl_surface = wmcompositer.create_surface(&qh, ()); // and create a surface. if two or more,
-                        let layer_shell = globals
-                            .bind::<ZwlrLayerShellV1, _, _>(&qh, 3..=4, ())
-                            .unwrap();
-                        let layer = layer_shell.get_layer_surface(
-                            &wl_surface,
-                            Some(output_display),
-                            self.layer,
-                            self.namespace.clone(),
-                            &qh,
-                            (),
-                        );
-                        layer.set_anchor(self.anchor);
-                        layer.set_keyboard_interactivity(self.keyboard_interactivity);
-                        if let Some((init_w, init_h)) = self.size {
-                            layer.set_size(init_w, init_h);
-                        }
+            if self.dispatch_messages(&mut event_handler, swapped_events)? {
+                break;
+            }
+        }
+        to_exit.store(true, Ordering::Relaxed);
+        let _ = thread.join();
+        Ok(())
+    }
 
-                        if let Some(zone) = self.exclusive_zone {
-                            layer.set_exclusive_zone(zone);
-                        }
+    /// set the opacity of the surface with wp_alpha_modifier_v1, see [ReturnData::RequestSetOpacity]
+    fn set_opacity(&mut self, id: id::Id, multiplier: u32) {
+        let Some(alpha_modifier) = self.alpha_modifier.as_ref() else {
+            log::warn!("wp_alpha_modifier_v1 is not supported by the compositor");
+            return;
+        };
+        let qh = self.queue_handle.as_ref().unwrap();
+        let Some(unit) = self.units.iter_mut().find(|unit| unit.id == id) else {
+            return;
+        };
+        // NOTE: a surface can only have one alpha modifier surface, so it is kept in the unit
+        let alpha_modifier_surface = unit
+            .alpha_modifier_surface
+            .get_or_insert_with(|| alpha_modifier.get_surface(&unit.wl_surface, qh, ()));
+        alpha_modifier_surface.set_multiplier(multiplier);
+        unit.wl_surface.commit();
+    }
+
+    /// request a frame callback of the surface, see [ReturnData::RequestFrame]
+    fn request_frame(&self, id: id::Id) {
+        let qh = self.queue_handle.as_ref().unwrap();
+        let Some(unit) = self.get_unit_with_id(id) else {
+            return;
+        };
+        unit.wl_surface.frame(qh, id);
+        unit.wl_surface.commit();
+    }
 
-                        if let Some((top, right, bottom, left)) = self.margin {
-                            layer.set_margin(top, right, bottom, left);
-                        }
+    /// if the compositor supports wp_alpha_modifier_v1, which is needed by
+    /// [ReturnData::RequestSetOpacity]
+    pub fn is_alpha_modifier_supported(&self) -> bool {
+        self.alpha_modifier.is_some()
+    }
 
-                        if self.events_transparent {
-                            let region = wmcompositer.create_region(&qh, ());
-                            wl_surface.set_input_region(Some(&region));
-                            region.destroy();
-                        }
-                        wl_surface.commit();
+    /// the protocols supported by the compositor, known after the build
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
 
-                        let zxdgoutput = xdg_output_manager.get_xdg_output(output_display, &qh, ());
-                        let mut fractional_scale = None;
-                        if let Some(ref fractional_scale_manager) = fractional_scale_manager {
-                            fractional_scale = Some(fractional_scale_manager.get_fractional_scale(
-                                &wl_surface,
-                                &qh,
-                                (),
-                            ));
-                        }
-                        let viewport = viewporter
-                            .as_ref()
-                            .map(|viewport| viewport.get_viewport(&wl_surface, &qh, ()));
-                        // so during the init Configure of the shell, a buffer, atleast a buffer is needed.
-                        // and if you need to reconfigure it, you need to commit the wl_surface again
-                        // so because this is just an example, so we just commit it once
-                        // like if you want to reset anchor or KeyboardInteractivity or resize, commit is needed
+    /// create a lock surface on the output, for the current session lock
+    fn push_lock_surface(&mut self, output_display: &WlOutput) {
+        let Some(lock) = self.session_lock.as_ref() else {
+            return;
+        };
+        let qh = self.queue_handle.as_ref().unwrap();
+        let wl_surface = self.wl_compositor.as_ref().unwrap().create_surface(qh, ());
+        let lock_surface = lock.get_lock_surface(&wl_surface, output_display, qh, ());
+
+        let zxdgoutput = self
+            .xdg_output_manager
+            .as_ref()
+            .map(|manager| ZxdgOutputInfo::new(manager.get_xdg_output(output_display, qh, ())));
+        let fractional_scale = self
+            .fractional_scale_manager
+            .as_ref()
+            .map(|manager| manager.get_fractional_scale(&wl_surface, qh, ()));
+        let viewport = self
+            .viewporter
+            .as_ref()
+            .map(|viewport| viewport.get_viewport(&wl_surface, qh, ()));
+
+        self.units.push(WindowStateUnit {
+            id: id::Id::unique(),
+            display: self.connection.as_ref().unwrap().display(),
+            wl_surface,
+            size: (0, 0),
+            buffer: None,
+            shell: Shell::SessionLock(lock_surface),
+            zxdgoutput,
+            fractional_scale,
+            viewport,
+            becreated: true,
+            wl_output: Some(output_display.clone()),
+            binding: None,
+            alpha_modifier_surface: None,
+            margin: Cell::new((0, 0, 0, 0)),
+            anchor: Cell::new(Anchor::empty()),
+            auto_exclusive_zone: None,
+            scale: 120,
+        });
+    }
 
-                        self.units.push(WindowStateUnit {
-                            id: id::Id::unique(),
-                            display: connection.display(),
-                            wl_surface,
-                            size: (0, 0),
-                            buffer: None,
-                            shell: Shell::LayerShell(layer),
-                            zxdgoutput: Some(ZxdgOutputInfo::new(zxdgoutput)),
-                            fractional_scale,
-                            viewport,
-                            binding: None,
-                            becreated: false,
-                            wl_output: Some(output_display.clone()),
-                            scale: 120,
-                        });
+    /// Send the init requests to the handler, until it returns [ReturnData::None]
+    fn handle_init_requests<F, Message>(&mut self, event_handler: &mut F)
+    where
+        F: FnMut(LayerEvent<T, Message>, &mut WindowState<T>, Option<id::Id>) -> ReturnData<T>,
+    {
+        let globals = self.globals.take().unwrap();
+        let qh = self.event_queue.as_ref().unwrap().handle();
+        let wmcompositer = self.wl_compositor.clone().unwrap();
+        let mut init_event = None;
+
+        while !matches!(init_event, Some(ReturnData::None)) {
+            match init_event {
+                None => {
+                    init_event = Some(event_handler(LayerEvent::InitRequest, self, None));
+                }
+                Some(ReturnData::RequestBind) => {
+                    init_event = Some(event_handler(
+                        LayerEvent::BindProvide(&globals, &qh),
+                        self,
+                        None,
+                    ));
+                }
+                Some(ReturnData::RequestCompositor) => {
+                    init_event = Some(event_handler(
+                        LayerEvent::CompositorProvide(&wmcompositer, &qh),
+                        self,
+                        None,
+                    ));
+                }
+                _ => panic!("Not provide server here"),
+            }
+        }
+        self.globals = Some(globals);
+        self.queue_handle = Some(qh);
+    }
+
+    /// Handle the messages collected during the last dispatch of the event loop, and the user
+    /// events. Returns true when the handler requested to exit.
+    fn dispatch_messages<F, Message>(
+        &mut self,
+        event_handler: &mut F,
+        user_events: Vec<Message>,
+    ) -> Result<bool, LayerEventError>
+    where
+        F: FnMut(LayerEvent<T, Message>, &mut WindowState<T>, Option<id::Id>) -> ReturnData<T>,
+    {
+        let qh = self.queue_handle.clone().unwrap();
+        let wmcompositer = self.wl_compositor.clone().unwrap();
+        let shm = self.shm.clone().unwrap();
+        let fractional_scale_manager = self.fractional_scale_manager.clone();
+        let xdg_output_manager = self.xdg_output_manager.clone();
+        let connection = self.connection.clone().unwrap();
+        let wmbase = self.wmbase.clone();
+        let viewporter = self.viewporter.clone();
+        let mut messages = Vec::new();
+        std::mem::swap(&mut messages, &mut self.message);
+        for msg in messages.iter() {
+            match msg {
+                (Some(unit_index), DispatchMessageInner::RefreshSurface { width, height }) => {
+                    let Some(index) = self.units.iter().position(|unit| unit.id == *unit_index)
+                    else {
+                        continue;
+                    };
+                    if self.units[index].buffer.is_none() && !self.use_display_handle {
+                        let mut file = tempfile::tempfile()?;
+                        let ReturnData::WlBuffer(buffer) = event_handler(
+                            LayerEvent::RequestBuffer(&mut file, &shm, &qh, *width, *height),
+                            self,
+                            Some(*unit_index),
+                        ) else {
+                            panic!("You cannot return this one");
+                        };
+                        let surface = &self.units[index].wl_surface;
+                        surface.attach(Some(&buffer), 0, 0);
+                        self.units[index].buffer = Some(buffer);
+                    } else {
+                        event_handler(
+                            LayerEvent::RequestMessages(&DispatchMessage::RequestRefresh {
+                                width: *width,
+                                height: *height,
+                                is_created: self.units[index].becreated,
+                                scale_float: self.units[index].scale_float(),
+                            }),
+                            self,
+                            Some(*unit_index),
+                        );
                     }
-                    _ => {
-                        let (index_message, msg) = msg;
-
-                        let msg: DispatchMessage = msg.clone().into();
-                        match event_handler(
-                            LayerEvent::RequestMessages(&msg),
-                            &mut self,
-                            *index_message,
-                        ) {
-                            ReturnData::RedrawAllRequest => {
-                                let idlist = self.get_id_list();
-                                for id in idlist {
-                                    if let Some(unit) = self.get_unit_with_id(id) {
-                                        if unit.size.0 == 0 || unit.size.1 == 0 {
-                                            continue;
-                                        }
-                                        event_handler(
-                                            LayerEvent::RequestMessages(
-                                                &DispatchMessage::RequestRefresh {
-                                                    width: unit.size.0,
-                                                    height: unit.size.1,
-                                                    is_created: unit.becreated,
-                                                    scale_float: unit.scale_float(),
-                                                },
-                                            ),
-                                            &mut self,
-                                            Some(id),
-                                        );
-                                    }
-                                }
-                            }
-                            ReturnData::RedrawIndexRequest(id) => {
-                                if let Some(unit) = self.get_unit_with_id(id) {
-                                    event_handler(
-                                        LayerEvent::RequestMessages(
-                                            &DispatchMessage::RequestRefresh {
-                                                width: unit.size.0,
-                                                height: unit.size.1,
-                                                is_created: unit.becreated,
-                                                scale_float: unit.scale_float(),
-                                            },
-                                        ),
-                                        &mut self,
-                                        Some(id),
-                                    );
-                                }
-                            }
-                            ReturnData::RequestExit => {
-                                break 'out;
-                            }
-                            ReturnData::RequestSetCursorShape((shape_name, pointer, serial)) => {
-                                if let Some(ref cursor_manager) = cursor_manager {
-                                    let Some(shape) = str_to_shape(&shape_name) else {
-                                        log::error!("Not supported shape");
-                                        continue;
-                                    };
-                                    let device = cursor_manager.get_pointer(&pointer, &qh, ());
-                                    device.set_shape(serial, shape);
-                                    device.destroy();
-                                } else {
-                                    let Some(cursor_buffer) =
-                                        get_cursor_buffer(&shape_name, &connection, &shm)
-                                    else {
-                                        log::error!("Cannot find cursor {shape_name}");
-                                        continue;
-                                    };
-                                    let cursor_surface = wmcompositer.create_surface(&qh, ());
-                                    cursor_surface.attach(Some(&cursor_buffer), 0, 0);
-                                    // and create a surface. if two or more,
-                                    let (hotspot_x, hotspot_y) = cursor_buffer.hotspot();
-                                    pointer.set_cursor(
-                                        serial,
-                                        Some(&cursor_surface),
-                                        hotspot_x as i32,
-                                        hotspot_y as i32,
-                                    );
-                                    cursor_surface.commit();
-                                }
-                            }
-                            _ => {}
-                        }
+
+                    if let Some(unit) = self.get_unit_with_id(*unit_index) {
+                        unit.wl_surface.commit();
                     }
                 }
-            }
+                (index_info, DispatchMessageInner::XdgInfoChanged(change_type)) => {
+                    event_handler(LayerEvent::XdgInfoChanged(*change_type), self, *index_info);
+                }
+                (_, DispatchMessageInner::NewDisplay(output_display)) => {
+                    if self.is_session_locked() {
+                        self.push_lock_surface(output_display);
+                    }
+                    if !self.is_allscreens() {
+                        continue;
+                    }
+                    let wl_surface = wmcompositer.create_surface(&qh, ()); // and create a surface. if two or more,
+                    let layer_shell = self
+                        .globals
+                        .as_ref()
+                        .unwrap()
+                        .bind::<ZwlrLayerShellV1, _, _>(&qh, 3..=5, ())
+                        .unwrap();
+                    let layer = layer_shell.get_layer_surface(
+                        &wl_surface,
+                        Some(output_display),
+                        self.layer,
+                        self.namespace.clone(),
+                        &qh,
+                        (),
+                    );
+                    layer.set_anchor(self.anchor);
+                    set_keyboard_interactivity_of(&layer, self.keyboard_interactivity);
+                    if let Some((init_w, init_h)) = self.size {
+                        layer.set_size(init_w, init_h);
+                    }
 
-            let mut local_events = events.lock().unwrap();
-            let mut swapped_events: Vec<Message> = vec![];
-            std::mem::swap(&mut *local_events, &mut swapped_events);
-            drop(local_events);
-            for event in swapped_events {
-                match event_handler(LayerEvent::UserEvent(event), &mut self, None) {
-                    ReturnData::RequestExit => {
-                        break 'out;
+                    if let Some(zone) = self.initial_exclusive_zone() {
+                        layer.set_exclusive_zone(zone);
                     }
-                    ReturnData::RequestSetCursorShape((shape_name, pointer, serial)) => {
-                        if let Some(ref cursor_manager) = cursor_manager {
-                            let Some(shape) = str_to_shape(&shape_name) else {
-                                log::error!("Not supported shape");
-                                continue;
-                            };
-                            let device = cursor_manager.get_pointer(&pointer, &qh, ());
-                            device.set_shape(serial, shape);
-                            device.destroy();
-                        } else {
-                            let Some(cursor_buffer) =
-                                get_cursor_buffer(&shape_name, &connection, &shm)
-                            else {
-                                log::error!("Cannot find cursor {shape_name}");
-                                continue;
-                            };
-                            let cursor_surface = wmcompositer.create_surface(&qh, ());
-                            cursor_surface.attach(Some(&cursor_buffer), 0, 0);
-                            // and create a surface. if two or more,
-                            let (hotspot_x, hotspot_y) = cursor_buffer.hotspot();
-                            pointer.set_cursor(
-                                serial,
-                                Some(&cursor_surface),
-                                hotspot_x as i32,
-                                hotspot_y as i32,
-                            );
-                            cursor_surface.commit();
-                        }
+
+                    if let Some((top, right, bottom, left)) = self.margin {
+                        layer.set_margin(top, right, bottom, left);
                     }
-                    _ => {}
+
+                    if self.events_transparent {
+                        let region = wmcompositer.create_region(&qh, ());
+                        wl_surface.set_input_region(Some(&region));
+                        region.destroy();
+                    }
+                    wl_surface.commit();
+
+                    let zxdgoutput = xdg_output_manager.as_ref().map(|manager| {
+                        ZxdgOutputInfo::new(manager.get_xdg_output(output_display, &qh, ()))
+                    });
+                    let mut fractional_scale = None;
+                    if let Some(ref fractional_scale_manager) = fractional_scale_manager {
+                        fractional_scale = Some(fractional_scale_manager.get_fractional_scale(
+                            &wl_surface,
+                            &qh,
+                            (),
+                        ));
+                    }
+                    let viewport = viewporter
+                        .as_ref()
+                        .map(|viewport| viewport.get_viewport(&wl_surface, &qh, ()));
+                    // so during the init Configure of the shell, a buffer, atleast a buffer is needed.
+                    // and if you need to reconfigure it, you need to commit the wl_surface again
+                    // so because this is just an example, so we just commit it once
+                    // like if you want to reset anchor or KeyboardInteractivity or resize, commit is needed
+
+                    self.units.push(WindowStateUnit {
+                        id: id::Id::unique(),
+                        display: connection.display(),
+                        wl_surface,
+                        size: (0, 0),
+                        buffer: None,
+                        shell: Shell::LayerShell(layer),
+                        zxdgoutput,
+                        fractional_scale,
+                        viewport,
+                        binding: None,
+                        becreated: false,
+                        wl_output: Some(output_display.clone()),
+                        alpha_modifier_surface: None,
+                        margin: Cell::new(self.margin.unwrap_or_default()),
+                        anchor: Cell::new(self.anchor),
+                        auto_exclusive_zone: self.gen_auto_exclusive_zone(),
+                        scale: 120,
+                    });
                 }
-            }
-            let mut return_data = vec![event_handler(LayerEvent::NormalDispatch, &mut self, None)];
-            loop {
-                return_data.append(&mut self.return_data);
+                _ => {
+                    let (index_message, msg) = msg;
 
-                let mut replace_data = Vec::new();
-                for data in return_data {
-                    match data {
+                    let msg: DispatchMessage = msg.clone().into();
+                    let return_data =
+                        event_handler(LayerEvent::RequestMessages(&msg), self, *index_message);
+                    match return_data {
                         ReturnData::RedrawAllRequest => {
                             let idlist = self.get_id_list();
                             for id in idlist {
@@ -2387,7 +2326,7 @@ impl<T: 'static> WindowState<T> {
                                                 scale_float: unit.scale_float(),
                                             },
                                         ),
-                                        &mut self,
+                                        self,
                                         Some(id),
                                     );
                                 }
@@ -2395,227 +2334,360 @@ impl<T: 'static> WindowState<T> {
                         }
                         ReturnData::RedrawIndexRequest(id) => {
                             if let Some(unit) = self.get_unit_with_id(id) {
-                                replace_data.push(event_handler(
+                                event_handler(
                                     LayerEvent::RequestMessages(&DispatchMessage::RequestRefresh {
                                         width: unit.size.0,
                                         height: unit.size.1,
                                         is_created: unit.becreated,
                                         scale_float: unit.scale_float(),
                                     }),
-                                    &mut self,
+                                    self,
                                     Some(id),
-                                ));
+                                );
                             }
                         }
                         ReturnData::RequestExit => {
-                            break 'out;
+                            return Ok(true);
                         }
                         ReturnData::RequestSetCursorShape((shape_name, pointer, serial)) => {
-                            if let Some(ref cursor_manager) = cursor_manager {
-                                let Some(shape) = str_to_shape(&shape_name) else {
-                                    log::error!("Not supported shape");
-                                    continue;
-                                };
-                                let device = cursor_manager.get_pointer(&pointer, &qh, ());
-                                device.set_shape(serial, shape);
-                                device.destroy();
-                            } else {
-                                let Some(cursor_buffer) =
-                                    get_cursor_buffer(&shape_name, &connection, &shm)
-                                else {
-                                    log::error!("Cannot find cursor {shape_name}");
+                            self.set_cursor_shape(&shape_name, &pointer, serial);
+                        }
+                        ReturnData::RequestSetCustomCursor((cursor, pointer, serial)) => {
+                            self.set_custom_cursor(&cursor, &pointer, serial);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        for event in user_events {
+            match event_handler(LayerEvent::UserEvent(event), self, None) {
+                ReturnData::RequestExit => {
+                    return Ok(true);
+                }
+                ReturnData::RequestSetCursorShape((shape_name, pointer, serial)) => {
+                    self.set_cursor_shape(&shape_name, &pointer, serial);
+                }
+                ReturnData::RequestSetCustomCursor((cursor, pointer, serial)) => {
+                    self.set_custom_cursor(&cursor, &pointer, serial);
+                }
+                _ => {}
+            }
+        }
+        let mut return_data = vec![event_handler(LayerEvent::NormalDispatch, self, None)];
+        loop {
+            return_data.append(&mut self.return_data);
+
+            let mut replace_data = Vec::new();
+            for data in return_data {
+                match data {
+                    ReturnData::RedrawAllRequest => {
+                        let idlist = self.get_id_list();
+                        for id in idlist {
+                            if let Some(unit) = self.get_unit_with_id(id) {
+                                if unit.size.0 == 0 || unit.size.1 == 0 {
                                     continue;
-                                };
-                                let cursor_surface = wmcompositer.create_surface(&qh, ());
-                                cursor_surface.attach(Some(&cursor_buffer), 0, 0);
-                                // and create a surface. if two or more,
-                                let (hotspot_x, hotspot_y) = cursor_buffer.hotspot();
-                                pointer.set_cursor(
-                                    serial,
-                                    Some(&cursor_surface),
-                                    hotspot_x as i32,
-                                    hotspot_y as i32,
+                                }
+                                event_handler(
+                                    LayerEvent::RequestMessages(&DispatchMessage::RequestRefresh {
+                                        width: unit.size.0,
+                                        height: unit.size.1,
+                                        is_created: unit.becreated,
+                                        scale_float: unit.scale_float(),
+                                    }),
+                                    self,
+                                    Some(id),
                                 );
-                                cursor_surface.commit();
                             }
                         }
-                        ReturnData::NewLayerShell((
-                            NewLayerShellSettings {
-                                size,
-                                layer,
-                                anchor,
-                                exclusive_zone,
-                                margin,
-                                keyboard_interactivity,
-                                use_last_output,
-                                events_transparent,
-                            },
-                            id,
-                            info,
-                        )) => {
-                            let pos = self.surface_pos();
+                    }
+                    ReturnData::RedrawIndexRequest(id) => {
+                        if let Some(unit) = self.get_unit_with_id(id) {
+                            replace_data.push(event_handler(
+                                LayerEvent::RequestMessages(&DispatchMessage::RequestRefresh {
+                                    width: unit.size.0,
+                                    height: unit.size.1,
+                                    is_created: unit.becreated,
+                                    scale_float: unit.scale_float(),
+                                }),
+                                self,
+                                Some(id),
+                            ));
+                        }
+                    }
+                    ReturnData::RequestExit => {
+                        return Ok(true);
+                    }
+                    ReturnData::RequestSetCursorShape((shape_name, pointer, serial)) => {
+                        self.set_cursor_shape(&shape_name, &pointer, serial);
+                    }
+                    ReturnData::RequestSetCustomCursor((cursor, pointer, serial)) => {
+                        self.set_custom_cursor(&cursor, &pointer, serial);
+                    }
+                    ReturnData::NewLayerShell((
+                        NewLayerShellSettings {
+                            size,
+                            layer,
+                            anchor,
+                            exclusive_zone,
+                            margin,
+                            keyboard_interactivity,
+                            use_last_output,
+                            events_transparent,
+                            auto_exclusive_zone,
+                        },
+                        id,
+                        info,
+                    )) => {
+                        let pos = self.surface_pos();
 
-                            let mut output = pos.and_then(|p| self.units[p].wl_output.as_ref());
+                        let mut output = pos.and_then(|p| self.units[p].wl_output.as_ref());
 
-                            if self.last_wloutput.is_none()
-                                && self.outputs.len() > self.last_unit_index
-                            {
-                                self.last_wloutput =
-                                    Some(self.outputs[self.last_unit_index].1.clone());
-                            }
+                        if self.last_wloutput.is_none() && self.outputs.len() > self.last_unit_index
+                        {
+                            self.last_wloutput = Some(self.outputs[self.last_unit_index].1.clone());
+                        }
 
-                            if use_last_output {
-                                output = self.last_wloutput.as_ref();
-                            }
+                        if use_last_output {
+                            output = self.last_wloutput.as_ref();
+                        }
+
+                        let wl_surface = wmcompositer.create_surface(&qh, ()); // and create a surface. if two or more,
+                        let layer_shell = self
+                            .globals
+                            .as_ref()
+                            .unwrap()
+                            .bind::<ZwlrLayerShellV1, _, _>(&qh, 3..=5, ())
+                            .unwrap();
+                        let layer = layer_shell.get_layer_surface(
+                            &wl_surface,
+                            output,
+                            layer,
+                            self.namespace.clone(),
+                            &qh,
+                            (),
+                        );
+                        layer.set_anchor(anchor);
+                        set_keyboard_interactivity_of(&layer, keyboard_interactivity);
+                        if let Some((init_w, init_h)) = size {
+                            layer.set_size(init_w, init_h);
+                        }
+
+                        let exclusive_zone = if auto_exclusive_zone {
+                            Some(AutoExclusiveZone::new(anchor, size).exclusive_zone())
+                        } else {
+                            exclusive_zone
+                        };
+                        if let Some(zone) = exclusive_zone {
+                            layer.set_exclusive_zone(zone);
+                        }
+
+                        if let Some((top, right, bottom, left)) = margin {
+                            layer.set_margin(top, right, bottom, left);
+                        }
+
+                        if events_transparent {
+                            let region = wmcompositer.create_region(&qh, ());
+                            wl_surface.set_input_region(Some(&region));
+                            region.destroy();
+                        }
 
-                            let wl_surface = wmcompositer.create_surface(&qh, ()); // and create a surface. if two or more,
-                            let layer_shell = globals
-                                .bind::<ZwlrLayerShellV1, _, _>(&qh, 3..=4, ())
-                                .unwrap();
-                            let layer = layer_shell.get_layer_surface(
+                        wl_surface.commit();
+
+                        let mut fractional_scale = None;
+                        if let Some(ref fractional_scale_manager) = fractional_scale_manager {
+                            fractional_scale = Some(fractional_scale_manager.get_fractional_scale(
                                 &wl_surface,
-                                output,
-                                layer,
-                                self.namespace.clone(),
                                 &qh,
                                 (),
-                            );
-                            layer.set_anchor(anchor);
-                            layer.set_keyboard_interactivity(keyboard_interactivity);
-                            if let Some((init_w, init_h)) = size {
-                                layer.set_size(init_w, init_h);
-                            }
-
-                            if let Some(zone) = exclusive_zone {
-                                layer.set_exclusive_zone(zone);
-                            }
-
-                            if let Some((top, right, bottom, left)) = margin {
-                                layer.set_margin(top, right, bottom, left);
-                            }
+                            ));
+                        }
+                        let viewport = viewporter
+                            .as_ref()
+                            .map(|viewport| viewport.get_viewport(&wl_surface, &qh, ()));
+                        // so during the init Configure of the shell, a buffer, atleast a buffer is needed.
+                        // and if you need to reconfigure it, you need to commit the wl_surface again
+                        // so because this is just an example, so we just commit it once
+                        // like if you want to reset anchor or KeyboardInteractivity or resize, commit is needed
 
-                            if events_transparent {
-                                let region = wmcompositer.create_region(&qh, ());
-                                wl_surface.set_input_region(Some(&region));
-                                region.destroy();
-                            }
+                        self.units.push(WindowStateUnit {
+                            id,
+                            display: connection.display(),
+                            wl_surface,
+                            size: (0, 0),
+                            buffer: None,
+                            shell: Shell::LayerShell(layer),
+                            zxdgoutput: None,
+                            fractional_scale,
+                            viewport,
+                            becreated: true,
+                            wl_output: output.cloned(),
+                            binding: info,
+                            alpha_modifier_surface: None,
+                            margin: Cell::new(margin.unwrap_or_default()),
+                            anchor: Cell::new(anchor),
+                            auto_exclusive_zone: auto_exclusive_zone
+                                .then(|| AutoExclusiveZone::new(anchor, size)),
+                            scale: 120,
+                        });
+                    }
+                    ReturnData::NewPopUp((
+                        NewPopUpSettings {
+                            size: (width, height),
+                            position: (x, y),
+                            id,
+                        },
+                        targetid,
+                        info,
+                    )) => {
+                        let Some(index) = self
+                            .units
+                            .iter()
+                            .position(|unit| !unit.is_popup() && unit.id == id)
+                        else {
+                            continue;
+                        };
+                        let Some(wmbase) = wmbase.as_ref() else {
+                            log::warn!("xdg_wm_base is not supported, the popup is not created");
+                            continue;
+                        };
+                        let wl_surface = wmcompositer.create_surface(&qh, ());
+                        let positioner = wmbase.create_positioner(&qh, ());
+                        positioner.set_size(width as i32, height as i32);
+                        positioner.set_anchor_rect(x, y, width as i32, height as i32);
+                        let wl_xdg_surface = wmbase.get_xdg_surface(&wl_surface, &qh, ());
+                        let popup = wl_xdg_surface.get_popup(None, &positioner, &qh, ());
+
+                        let Shell::LayerShell(shell) = &self.units[index].shell else {
+                            unreachable!()
+                        };
+                        shell.get_popup(&popup);
 
-                            wl_surface.commit();
+                        let mut fractional_scale = None;
+                        if let Some(ref fractional_scale_manager) = fractional_scale_manager {
+                            fractional_scale = Some(fractional_scale_manager.get_fractional_scale(
+                                &wl_surface,
+                                &qh,
+                                (),
+                            ));
+                        }
+                        wl_surface.commit();
 
-                            let mut fractional_scale = None;
-                            if let Some(ref fractional_scale_manager) = fractional_scale_manager {
-                                fractional_scale =
-                                    Some(fractional_scale_manager.get_fractional_scale(
-                                        &wl_surface,
-                                        &qh,
-                                        (),
-                                    ));
-                            }
-                            let viewport = viewporter
-                                .as_ref()
-                                .map(|viewport| viewport.get_viewport(&wl_surface, &qh, ()));
-                            // so during the init Configure of the shell, a buffer, atleast a buffer is needed.
-                            // and if you need to reconfigure it, you need to commit the wl_surface again
-                            // so because this is just an example, so we just commit it once
-                            // like if you want to reset anchor or KeyboardInteractivity or resize, commit is needed
-
-                            self.units.push(WindowStateUnit {
-                                id,
-                                display: connection.display(),
-                                wl_surface,
-                                size: (0, 0),
-                                buffer: None,
-                                shell: Shell::LayerShell(layer),
-                                zxdgoutput: None,
-                                fractional_scale,
-                                viewport,
-                                becreated: true,
-                                wl_output: output.cloned(),
-                                binding: info,
-                                scale: 120,
-                            });
+                        let viewport = viewporter
+                            .as_ref()
+                            .map(|viewport| viewport.get_viewport(&wl_surface, &qh, ()));
+                        self.units.push(WindowStateUnit {
+                            id: targetid,
+                            display: connection.display(),
+                            wl_surface,
+                            size: (width, height),
+                            buffer: None,
+                            shell: Shell::PopUp((popup, wl_xdg_surface)),
+                            zxdgoutput: None,
+                            fractional_scale,
+                            viewport,
+                            becreated: true,
+                            wl_output: None,
+                            binding: info,
+                            alpha_modifier_surface: None,
+                            margin: Cell::new((0, 0, 0, 0)),
+                            anchor: Cell::new(Anchor::empty()),
+                            auto_exclusive_zone: None,
+                            scale: 120,
+                        });
+                    }
+                    ReturnData::RequestSetOpacity((id, multiplier)) => {
+                        self.set_opacity(id, multiplier);
+                    }
+                    ReturnData::RequestFrame(id) => {
+                        self.request_frame(id);
+                    }
+                    ReturnData::RequestSessionLock => {
+                        if self.is_session_locked() {
+                            continue;
                         }
-                        ReturnData::NewPopUp((
-                            NewPopUpSettings {
-                                size: (width, height),
-                                position: (x, y),
-                                id,
-                            },
-                            targetid,
-                            info,
-                        )) => {
-                            let Some(index) = self
-                                .units
-                                .iter()
-                                .position(|unit| !unit.is_popup() && unit.id == id)
-                            else {
-                                continue;
-                            };
-                            let wl_surface = wmcompositer.create_surface(&qh, ());
-                            let positioner = wmbase.create_positioner(&qh, ());
-                            positioner.set_size(width as i32, height as i32);
-                            positioner.set_anchor_rect(x, y, width as i32, height as i32);
-                            let wl_xdg_surface = wmbase.get_xdg_surface(&wl_surface, &qh, ());
-                            let popup = wl_xdg_surface.get_popup(None, &positioner, &qh, ());
-
-                            let Shell::LayerShell(shell) = &self.units[index].shell else {
-                                unreachable!()
-                            };
-                            shell.get_popup(&popup);
-
-                            let mut fractional_scale = None;
-                            if let Some(ref fractional_scale_manager) = fractional_scale_manager {
-                                fractional_scale =
-                                    Some(fractional_scale_manager.get_fractional_scale(
-                                        &wl_surface,
-                                        &qh,
-                                        (),
-                                    ));
-                            }
-                            wl_surface.commit();
-
-                            let viewport = viewporter
-                                .as_ref()
-                                .map(|viewport| viewport.get_viewport(&wl_surface, &qh, ()));
-                            self.units.push(WindowStateUnit {
-                                id: targetid,
-                                display: connection.display(),
-                                wl_surface,
-                                size: (width, height),
-                                buffer: None,
-                                shell: Shell::PopUp((popup, wl_xdg_surface)),
-                                zxdgoutput: None,
-                                fractional_scale,
-                                viewport,
-                                becreated: true,
-                                wl_output: None,
-                                binding: info,
-                                scale: 120,
-                            });
+                        let Some(session_lock_manager) = self.session_lock_manager.as_ref() else {
+                            log::warn!("ext-session-lock is not supported by the compositor");
+                            continue;
+                        };
+                        self.session_lock = Some(session_lock_manager.lock(&qh, ()));
+                        let displays = self.outputs.clone();
+                        for (_, output_display) in displays.iter() {
+                            self.push_lock_surface(output_display);
                         }
-                        _ => {}
                     }
+                    _ => {}
                 }
-                replace_data.retain(|x| !matches!(x, ReturnData::None));
-                if replace_data.is_empty() {
-                    break;
-                }
-                return_data = replace_data;
             }
-            continue;
+            replace_data.retain(|x| !matches!(x, ReturnData::None));
+            if replace_data.is_empty() {
+                break;
+            }
+            return_data = replace_data;
         }
-        to_exit.store(true, Ordering::Relaxed);
-        let _ = thread.join();
-        Ok(())
+        Ok(false)
     }
 }
 
-fn get_cursor_buffer(
-    shape: &str,
-    connection: &Connection,
-    shm: &WlShm,
-) -> Option<CursorImageBuffer> {
-    let mut cursor_theme = CursorTheme::load(connection, shm.clone(), 23).ok()?;
-    let cursor = cursor_theme.get_cursor(shape);
-    Some(cursor?[0].clone())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclusive_zone_of_single_edge() {
+        assert_eq!(exclusive_zone_of(Anchor::Top, (300, 40)), Some(40));
+        assert_eq!(exclusive_zone_of(Anchor::Bottom, (300, 40)), Some(40));
+        assert_eq!(exclusive_zone_of(Anchor::Left, (60, 500)), Some(60));
+        assert_eq!(exclusive_zone_of(Anchor::Right, (60, 500)), Some(60));
+    }
+
+    #[test]
+    fn test_exclusive_zone_of_edge_with_perpendicular_edges() {
+        assert_eq!(
+            exclusive_zone_of(Anchor::Top | Anchor::Left | Anchor::Right, (1920, 40)),
+            Some(40)
+        );
+        assert_eq!(
+            exclusive_zone_of(Anchor::Left | Anchor::Top | Anchor::Bottom, (60, 1080)),
+            Some(60)
+        );
+    }
+
+    #[test]
+    fn test_exclusive_zone_of_corner() {
+        assert_eq!(
+            exclusive_zone_of(Anchor::Top | Anchor::Left, (300, 40)),
+            None
+        );
+        assert_eq!(
+            exclusive_zone_of(Anchor::Bottom | Anchor::Right, (300, 40)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_exclusive_zone_of_centered() {
+        assert_eq!(exclusive_zone_of(Anchor::empty(), (300, 40)), None);
+        assert_eq!(exclusive_zone_of(Anchor::all(), (1920, 1080)), None);
+        assert_eq!(
+            exclusive_zone_of(Anchor::Left | Anchor::Right, (1920, 40)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_alpha_multiplier_bounds() {
+        assert_eq!(alpha_multiplier(0.), 0);
+        assert_eq!(alpha_multiplier(1.), u32::MAX);
+        assert_eq!(alpha_multiplier(0.5), u32::MAX / 2 + 1);
+    }
+
+    #[test]
+    fn test_alpha_multiplier_clamped() {
+        assert_eq!(alpha_multiplier(-0.5), 0);
+        assert_eq!(alpha_multiplier(1.5), u32::MAX);
+        assert_eq!(alpha_multiplier(f64::INFINITY), u32::MAX);
+        assert_eq!(alpha_multiplier(f64::NEG_INFINITY), 0);
+    }
 }
@@ -0,0 +1,238 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    future::Future,
+    io,
+    os::fd::{AsFd, BorrowedFd, OwnedFd},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use async_io::{Async, Timer};
+use calloop::{channel::Channel, Error as CallLoopError};
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    Stream, StreamExt,
+};
+
+use crate::{
+    id, DispatchMessage, LayerEvent, LayerEventError, ReturnData, WindowState,
+    WindowStateDispatcher, XdgInfoChangedType,
+};
+
+/// the events produced by [WindowStateStream]
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    /// same as [LayerEvent::RequestMessages], with the id of the surface
+    Message(Option<id::Id>, DispatchMessage),
+    /// same as [LayerEvent::XdgInfoChanged], with the id of the surface
+    XdgInfoChanged(Option<id::Id>, XdgInfoChangedType),
+}
+
+/// the sender used to pass requests to a [WindowStateStream]. The requests are handled like the
+/// [ReturnData] appended with [WindowState::append_return_data].
+pub type StreamRequestSender<T> = UnboundedSender<ReturnData<T>>;
+
+impl<T: 'static> WindowState<T> {
+    /// Turn the state into a [Stream] of events, so it can be awaited together with other
+    /// futures, on any executor. The stream ends after [ReturnData::RequestExit] is requested.
+    ///
+    /// Surfaces are expected to be drawn with the display handle, so
+    /// [WindowState::with_use_display_handle] is always enabled here, and
+    /// [DispatchMessage::RequestRefresh] is received instead of [LayerEvent::RequestBuffer].
+    pub fn into_stream(mut self) -> Result<WindowStateStream<T>, LayerEventError> {
+        self.use_display_handle = true;
+
+        let events: Rc<RefCell<VecDeque<StreamEvent>>> = Rc::new(RefCell::new(VecDeque::new()));
+        let events_in = events.clone();
+        let dispatcher = self.into_dispatcher(None::<Channel<()>>, move |event, _, id| {
+            match event {
+                LayerEvent::RequestMessages(message) => events_in
+                    .borrow_mut()
+                    .push_back(StreamEvent::Message(id, message.clone())),
+                LayerEvent::XdgInfoChanged(change_type) => events_in
+                    .borrow_mut()
+                    .push_back(StreamEvent::XdgInfoChanged(id, change_type)),
+                _ => {}
+            }
+            ReturnData::None
+        })?;
+
+        let loop_waker = LoopWaker::new(dispatcher.as_fd()).map_err(CallLoopError::IoError)?;
+        let (request_sender, requests) = mpsc::unbounded();
+
+        Ok(WindowStateStream {
+            dispatcher,
+            loop_waker,
+            events,
+            request_sender,
+            requests,
+        })
+    }
+}
+
+/// wakes the task when the event loop has to be dispatched: when its fd is readable, or when the
+/// deadline of its next timer is reached, as the calloop timers have no fd
+struct LoopWaker {
+    loop_fd: Async<OwnedFd>,
+    timer: Timer,
+}
+
+impl LoopWaker {
+    fn new(loop_fd: BorrowedFd<'_>) -> io::Result<Self> {
+        Ok(Self {
+            loop_fd: Async::new(loop_fd.try_clone_to_owned()?)?,
+            timer: Timer::never(),
+        })
+    }
+
+    fn poll_wake(
+        &mut self,
+        cx: &mut Context<'_>,
+        next_deadline: Option<Instant>,
+    ) -> Poll<io::Result<()>> {
+        if let Some(deadline) = next_deadline {
+            self.timer.set_at(deadline);
+            if Pin::new(&mut self.timer).poll(cx).is_ready() {
+                return Poll::Ready(Ok(()));
+            }
+        }
+        self.loop_fd.poll_readable(cx)
+    }
+}
+
+/// A [WindowState] as a [Stream] of [StreamEvent], created by [WindowState::into_stream]
+///
+/// The stream is not `Send`: the handler shares the events with it through `Rc` and `RefCell`,
+/// so it has to be polled on the thread which created it, with a local executor.
+pub struct WindowStateStream<T: 'static> {
+    dispatcher: WindowStateDispatcher<T>,
+    loop_waker: LoopWaker,
+    events: Rc<RefCell<VecDeque<StreamEvent>>>,
+    request_sender: StreamRequestSender<T>,
+    requests: UnboundedReceiver<ReturnData<T>>,
+}
+
+impl<T: 'static> WindowStateStream<T> {
+    /// get a sender to pass requests, like [ReturnData::RedrawAllRequest] or
+    /// [ReturnData::NewLayerShell]
+    pub fn request_sender(&self) -> StreamRequestSender<T> {
+        self.request_sender.clone()
+    }
+
+    pub fn state(&self) -> &WindowState<T> {
+        self.dispatcher.state()
+    }
+
+    pub fn state_mut(&mut self) -> &mut WindowState<T> {
+        self.dispatcher.state_mut()
+    }
+}
+
+// NOTE: poll_next only takes the stream with Pin::get_mut and never pins its fields, so moving it
+// after a poll is fine, even if T is not Unpin
+impl<T: 'static> Unpin for WindowStateStream<T> {}
+
+impl<T: 'static> Stream for WindowStateStream<T> {
+    type Item = StreamEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(event) = this.events.borrow_mut().pop_front() {
+                return Poll::Ready(Some(event));
+            }
+            if this.dispatcher.is_exit_requested() {
+                return Poll::Ready(None);
+            }
+
+            let mut has_request = false;
+            while let Poll::Ready(Some(request)) = this.requests.poll_next_unpin(cx) {
+                this.dispatcher.state_mut().append_return_data(request);
+                has_request = true;
+            }
+            if has_request {
+                // NOTE: the requests are handled by the next dispatch of the handler
+                if let Some(waker) = this.dispatcher.state().dispatch_waker.as_ref() {
+                    waker.ping();
+                }
+            }
+
+            if let Err(e) = this.dispatcher.dispatch_pending(Some(Duration::ZERO)) {
+                log::error!("failed to dispatch the event loop: {e}");
+                return Poll::Ready(None);
+            }
+            // requests sent by the handler are flushed only before the loop sleeps, and the
+            // loop never sleeps here
+            if let Some(connection) = this.dispatcher.state().connection.as_ref() {
+                connection.flush().ok();
+            }
+
+            if !this.events.borrow().is_empty() || this.dispatcher.is_exit_requested() {
+                continue;
+            }
+
+            // NOTE: the timers, like the key repeat, fire only if the loop is dispatched after
+            // their deadline
+            let next_deadline = this.dispatcher.next_timer_deadline();
+            match this.loop_waker.poll_wake(cx, next_deadline) {
+                Poll::Ready(Ok(())) => continue,
+                Poll::Ready(Err(e)) => {
+                    log::error!("failed to wait for the event loop: {e}");
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use calloop::{
+        timer::{TimeoutAction, Timer as LoopTimer},
+        EventLoop,
+    };
+    use futures::future::poll_fn;
+    use waycrate_shellcore::TimerDeadlines;
+
+    use super::*;
+
+    #[test]
+    fn test_loop_timer_fires_through_the_waker() {
+        let mut event_loop: EventLoop<'static, u32> = EventLoop::try_new().unwrap();
+        let deadlines = TimerDeadlines::default();
+        deadlines
+            .insert(
+                &event_loop.handle(),
+                LoopTimer::from_duration(Duration::from_millis(20)),
+                |_, _, fired| {
+                    *fired += 1;
+                    TimeoutAction::Drop
+                },
+            )
+            .unwrap();
+        let mut loop_waker = LoopWaker::new(event_loop.as_fd()).unwrap();
+
+        // NOTE: the same steps as WindowStateStream::poll_next, the fd of the loop stays quiet
+        let mut fired = 0;
+        futures::executor::block_on(poll_fn(|cx| loop {
+            let dispatch_start = Instant::now();
+            event_loop
+                .dispatch(Some(Duration::ZERO), &mut fired)
+                .unwrap();
+            deadlines.expire(dispatch_start);
+            if fired > 0 {
+                return Poll::Ready(());
+            }
+            match loop_waker.poll_wake(cx, deadlines.next()) {
+                Poll::Ready(result) => result.unwrap(),
+                Poll::Pending => return Poll::Pending,
+            }
+        }));
+        assert_eq!(fired, 1);
+        assert_eq!(deadlines.next(), None);
+    }
+}
@@ -72,6 +72,7 @@ fn main() {
                 time,
                 surface_x,
                 surface_y,
+                ..
             }) => {
                 println!("{time}, {surface_x}, {surface_y}");
                 ReturnData::None
@@ -0,0 +1,50 @@
+use waycrate_shellcore::cursor::{self, CursorHandler, ThemedCursor};
+use wayland_client::{
+    protocol::{wl_compositor::WlCompositor, wl_pointer::WlPointer, wl_shm::WlShm},
+    Connection, QueueHandle,
+};
+use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_manager_v1::WpCursorShapeManagerV1;
+
+use crate::WindowState;
+
+impl<T: 'static> WindowState<T> {
+    pub(crate) fn set_cursor_shape(&mut self, shape_name: &str, pointer: &WlPointer, serial: u32) {
+        cursor::set_cursor_shape(self, shape_name, pointer, serial);
+    }
+}
+
+impl<T: 'static> CursorHandler for WindowState<T> {
+    fn themed_cursor_mut(&mut self) -> &mut ThemedCursor {
+        &mut self.themed_cursor
+    }
+
+    // NOTE: the lock screen has no settings for the theme, it follows XCURSOR_THEME and
+    // XCURSOR_SIZE
+    fn cursor_theme(&self) -> String {
+        cursor::cursor_theme_name(None)
+    }
+
+    fn cursor_size(&self) -> u32 {
+        cursor::cursor_size(None)
+    }
+
+    fn cursor_manager(&self) -> Option<&WpCursorShapeManagerV1> {
+        self.cursor_manager.as_ref()
+    }
+
+    fn connection(&self) -> Option<&Connection> {
+        self.connection.as_ref()
+    }
+
+    fn shm(&self) -> Option<&WlShm> {
+        self.shm.as_ref()
+    }
+
+    fn compositor(&self) -> Option<&WlCompositor> {
+        self.wl_compositor.as_ref()
+    }
+
+    fn queue_handle(&self) -> Option<&QueueHandle<Self>> {
+        self.queue_handle.as_ref()
+    }
+}
@@ -17,6 +17,8 @@ use super::WindowState;
 use crate::keyboard::ModifiersState;
 use std::{fmt::Debug, fs::File};
 
+use waycrate_shellcore::{SeatId, SeatMessage};
+
 /// tell program what event is happened
 ///
 /// InitRequest will tell the program is inited, you can request to Bind other wayland-protocols
@@ -53,8 +55,28 @@ pub enum SessionLockEvent<'a, T, Message> {
     NormalDispatch,
     /// It return the event you passed with message_receiver, and return it back.
     UserEvent(Message),
+    /// The compositor has locked the session, every output is covered by a lock surface now. Its
+    /// return data is handled like the one of [SessionLockEvent::NormalDispatch], so you can
+    /// return [ReturnData::RequestUnlockAndExist] or request a redraw here.
+    Locked,
+    /// The compositor refused to lock the session, or finished the lock. The lock cannot be used
+    /// anymore, so the loop ends after this event, with [SessonLockEventError::LockRefused] if the
+    /// session was never locked, else with [SessonLockEventError::LockFinished].
+    ///
+    /// [SessonLockEventError::LockRefused]: crate::SessonLockEventError::LockRefused
+    /// [SessonLockEventError::LockFinished]: crate::SessonLockEventError::LockFinished
+    Finished,
+    /// The output of the lock surface with the id was removed, so the surface is destroyed. The
+    /// lock surfaces of the outputs added later are created automatically, and are refreshed
+    /// like the others.
+    SurfaceRemoved(Id),
+    /// if the info of the XdgOutput of the lock surface is changed, it will send the event, see
+    /// [WindowStateUnit::get_xdgoutput_info](crate::WindowStateUnit::get_xdgoutput_info)
+    XdgInfoChanged(XdgInfoChangedType),
 }
 
+pub use waycrate_shellcore::{AxisScroll, XdgInfoChangedType};
+
 /// the return data
 /// Note: when event is RequestBuffer, you must return WlBuffer
 /// Note: when receive InitRequest, you can request to bind extra wayland-protocols. this time you
@@ -80,93 +102,17 @@ pub enum ReturnData {
     RequestSetCursorShape((String, WlPointer, u32)),
     None,
 }
-/// Describes a scroll along one axis
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
-pub struct AxisScroll {
-    /// The scroll measured in pixels.
-    pub absolute: f64,
-
-    /// The scroll measured in steps.
-    ///
-    /// Note: this might always be zero if the scrolling is due to a touchpad or other continuous
-    /// source.
-    pub discrete: i32,
-
-    /// The scroll was stopped.
-    ///
-    /// Generally this is encountered when hardware indicates the end of some continuous scrolling.
-    pub stop: bool,
-}
 
 #[allow(unused)]
 #[derive(Debug, Clone)]
 pub(crate) enum DispatchMessageInner {
+    /// the input of a seat, the seat is None if it has been removed
+    Seat(Option<SeatId>, SeatMessage<Id>),
     NewDisplay(WlOutput),
-    MouseButton {
-        state: WEnum<ButtonState>,
-        serial: u32,
-        button: u32,
-        time: u32,
-    },
-    MouseLeave,
-    MouseEnter {
-        pointer: WlPointer,
-        serial: u32,
-        surface_x: f64,
-        surface_y: f64,
-    },
-    MouseMotion {
-        time: u32,
-        surface_x: f64,
-        surface_y: f64,
-    },
-    Axis {
-        time: u32,
-        scale: f64,
-        horizontal: AxisScroll,
-        vertical: AxisScroll,
-        source: Option<wl_pointer::AxisSource>,
-    },
-    TouchDown {
-        serial: u32,
-        time: u32,
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    TouchUp {
-        serial: u32,
-        time: u32,
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    TouchMotion {
-        time: u32,
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    TouchCancel {
-        id: i32,
-        x: f64,
-        y: f64,
-    },
-    ModifiersChanged(ModifiersState),
-    KeyboardInput {
-        event: KeyEvent,
-
-        /// If `true`, the event was generated synthetically by winit
-        /// in one of the following circumstances:
-        ///
-        /// * Synthetic key press events are generated for all keys pressed when a window gains
-        ///   focus. Likewise, synthetic key release events are generated for all keys pressed when
-        ///   a window goes out of focus. ***Currently, this is only functional on X11 and
-        ///   Windows***
-        ///
-        /// Otherwise, this value is always `false`.
-        is_synthetic: bool,
-    },
+    Locked,
+    Finished,
+    SurfaceRemoved,
+    XdgInfoChanged(XdgInfoChangedType),
     RefreshSurface {
         width: u32,
         height: u32,
@@ -183,6 +129,9 @@ pub(crate) enum DispatchMessageInner {
 }
 
 /// This tell the DispatchMessage by dispatch
+///
+/// The input messages carry the seat which produced them, it is None when the seat has been
+/// removed, like for the last [DispatchMessage::Unfocus] of its keyboard.
 #[derive(Debug)]
 pub enum DispatchMessage {
     /// forward the event of wayland-mouse
@@ -191,20 +140,25 @@ pub enum DispatchMessage {
         serial: u32,
         button: u32,
         time: u32,
+        seat: Option<SeatId>,
+    },
+    MouseLeave {
+        seat: Option<SeatId>,
     },
-    MouseLeave,
     /// forward the event of wayland-mouse
     MouseEnter {
         pointer: WlPointer,
         serial: u32,
         surface_x: f64,
         surface_y: f64,
+        seat: Option<SeatId>,
     },
     /// forward the event of wayland-mouse
     MouseMotion {
         time: u32,
         surface_x: f64,
         surface_y: f64,
+        seat: Option<SeatId>,
     },
     /// About the scroll
     Axis {
@@ -213,6 +167,7 @@ pub enum DispatchMessage {
         horizontal: AxisScroll,
         vertical: AxisScroll,
         source: Option<wl_pointer::AxisSource>,
+        seat: Option<SeatId>,
     },
     /// forward the event of wayland-touch
     TouchDown {
@@ -221,6 +176,7 @@ pub enum DispatchMessage {
         id: i32,
         x: f64,
         y: f64,
+        seat: Option<SeatId>,
     },
     /// forward the event of wayland-touch
     TouchUp {
@@ -229,6 +185,7 @@ pub enum DispatchMessage {
         id: i32,
         x: f64,
         y: f64,
+        seat: Option<SeatId>,
     },
     /// forward the event of wayland-touch
     TouchMotion {
@@ -236,15 +193,29 @@ pub enum DispatchMessage {
         id: i32,
         x: f64,
         y: f64,
+        seat: Option<SeatId>,
     },
     /// TouchEvent is cancelled
     TouchCancel {
         id: i32,
         x: f64,
         y: f64,
+        seat: Option<SeatId>,
+    },
+    /// The keyboard of a seat entered the surface, and no other keyboard is on it.
+    Focused {
+        id: Id,
+        seat: Option<SeatId>,
+    },
+    /// The last keyboard on the surface left it.
+    Unfocus {
+        seat: Option<SeatId>,
     },
     /// Keyboard ModifiersChanged.
-    ModifiersChanged(ModifiersState),
+    ModifiersChanged {
+        modifiers: ModifiersState,
+        seat: Option<SeatId>,
+    },
     /// Keyboard Event about input.
     KeyboardInput {
         event: KeyEvent,
@@ -259,6 +230,7 @@ pub enum DispatchMessage {
         ///
         /// Otherwise, this value is always `false`.
         is_synthetic: bool,
+        seat: Option<SeatId>,
     },
     /// this will request to do refresh the whole screen, because the layershell tell that a new
     /// configure happened
@@ -277,113 +249,156 @@ pub enum DispatchMessage {
 impl From<DispatchMessageInner> for DispatchMessage {
     fn from(val: DispatchMessageInner) -> Self {
         match val {
+            DispatchMessageInner::Seat(seat, message) => Self::from_seat_message(message, seat),
             DispatchMessageInner::NewDisplay(_) => unimplemented!(),
-            DispatchMessageInner::MouseButton {
+            DispatchMessageInner::Locked
+            | DispatchMessageInner::Finished
+            | DispatchMessageInner::SurfaceRemoved
+            | DispatchMessageInner::XdgInfoChanged(_) => {
+                unreachable!("the lock and surface events are handled by the loop")
+            }
+            DispatchMessageInner::RequestRefresh {
+                width,
+                height,
+                scale_float,
+            } => DispatchMessage::RequestRefresh {
+                width,
+                height,
+                scale_float,
+            },
+            DispatchMessageInner::PreferredScale {
+                scale_float,
+                scale_u32,
+            } => DispatchMessage::PreferredScale {
+                scale_float,
+                scale_u32,
+            },
+            DispatchMessageInner::RefreshSurface { .. } => unimplemented!(),
+        }
+    }
+}
+
+impl DispatchMessage {
+    /// the seat which produced an input message, None for the other messages or if the seat
+    /// has been removed
+    pub fn seat(&self) -> Option<SeatId> {
+        match self {
+            Self::MouseButton { seat, .. }
+            | Self::MouseLeave { seat }
+            | Self::MouseEnter { seat, .. }
+            | Self::MouseMotion { seat, .. }
+            | Self::Axis { seat, .. }
+            | Self::TouchDown { seat, .. }
+            | Self::TouchUp { seat, .. }
+            | Self::TouchMotion { seat, .. }
+            | Self::TouchCancel { seat, .. }
+            | Self::Focused { seat, .. }
+            | Self::Unfocus { seat }
+            | Self::ModifiersChanged { seat, .. }
+            | Self::KeyboardInput { seat, .. } => *seat,
+            _ => None,
+        }
+    }
+
+    /// tag the message of a seat with the seat
+    fn from_seat_message(message: SeatMessage<Id>, seat: Option<SeatId>) -> Self {
+        match message {
+            SeatMessage::MouseButton {
                 state,
                 serial,
                 button,
                 time,
-            } => DispatchMessage::MouseButton {
+            } => Self::MouseButton {
                 state,
                 serial,
                 button,
                 time,
+                seat,
             },
-            DispatchMessageInner::MouseLeave => DispatchMessage::MouseLeave,
-            DispatchMessageInner::MouseEnter {
+            SeatMessage::MouseLeave => Self::MouseLeave { seat },
+            SeatMessage::MouseEnter {
                 pointer,
                 serial,
                 surface_x,
                 surface_y,
-            } => DispatchMessage::MouseEnter {
+            } => Self::MouseEnter {
                 pointer,
                 serial,
                 surface_x,
                 surface_y,
+                seat,
             },
-            DispatchMessageInner::MouseMotion {
+            SeatMessage::MouseMotion {
                 time,
                 surface_x,
                 surface_y,
-            } => DispatchMessage::MouseMotion {
+            } => Self::MouseMotion {
                 time,
                 surface_x,
                 surface_y,
+                seat,
             },
-            DispatchMessageInner::TouchDown {
+            SeatMessage::Axis {
+                time,
+                scale,
+                horizontal,
+                vertical,
+                source,
+            } => Self::Axis {
+                time,
+                scale,
+                horizontal,
+                vertical,
+                source,
+                seat,
+            },
+            SeatMessage::TouchDown {
                 serial,
                 time,
                 id,
                 x,
                 y,
-            } => DispatchMessage::TouchDown {
+            } => Self::TouchDown {
                 serial,
                 time,
                 id,
                 x,
                 y,
+                seat,
             },
-
-            DispatchMessageInner::TouchUp {
+            SeatMessage::TouchUp {
                 serial,
                 time,
                 id,
                 x,
                 y,
-            } => DispatchMessage::TouchUp {
+            } => Self::TouchUp {
                 serial,
                 time,
                 id,
                 x,
                 y,
+                seat,
             },
-            DispatchMessageInner::TouchMotion { time, id, x, y } => {
-                DispatchMessage::TouchMotion { time, id, x, y }
-            }
-            DispatchMessageInner::TouchCancel { id, x, y } => {
-                DispatchMessage::TouchCancel { id, x, y }
-            }
-
-            DispatchMessageInner::RequestRefresh {
-                width,
-                height,
-                scale_float,
-            } => DispatchMessage::RequestRefresh {
-                width,
-                height,
-                scale_float,
-            },
-            DispatchMessageInner::Axis {
-                time,
-                scale,
-                horizontal,
-                vertical,
-                source,
-            } => DispatchMessage::Axis {
+            SeatMessage::TouchMotion { time, id, x, y } => Self::TouchMotion {
                 time,
-                scale,
-                horizontal,
-                vertical,
-                source,
+                id,
+                x,
+                y,
+                seat,
             },
-            DispatchMessageInner::ModifiersChanged(modifier) => {
-                DispatchMessage::ModifiersChanged(modifier)
-            }
-            DispatchMessageInner::KeyboardInput {
+            SeatMessage::TouchCancel { id, x, y } => Self::TouchCancel { id, x, y, seat },
+            SeatMessage::ModifiersChanged(modifiers) => Self::ModifiersChanged { modifiers, seat },
+            SeatMessage::KeyboardInput {
                 event,
                 is_synthetic,
-            } => DispatchMessage::KeyboardInput {
+            } => Self::KeyboardInput {
                 event,
                 is_synthetic,
+                seat,
             },
-            DispatchMessageInner::PreferredScale {
-                scale_float,
-                scale_u32,
-            } => DispatchMessage::PreferredScale {
-                scale_float,
-                scale_u32,
-            },
-            DispatchMessageInner::RefreshSurface { .. } => unimplemented!(),
+            SeatMessage::Focused(id) => Self::Focused { id, seat },
+            SeatMessage::Unfocus => Self::Unfocus { seat },
         }
     }
 }
@@ -71,6 +71,7 @@
 //!                 time,
 //!                 surface_x,
 //!                 surface_y,
+//!                 ..
 //!             }) => {
 //!                 println!("{time}, {surface_x}, {surface_y}");
 //!                 ReturnData::None
@@ -99,21 +100,20 @@
 //! }
 //! ```
 
+mod cursor;
 mod events;
+mod seat;
 
 pub use waycrate_xkbkeycode::keyboard;
 pub use waycrate_xkbkeycode::xkb_keyboard;
-use waycrate_xkbkeycode::xkb_keyboard::RepeatInfo;
-
-mod strtoshape;
 
 pub mod id;
 
-use strtoshape::str_to_shape;
-
-use events::{AxisScroll, DispatchMessageInner};
+use events::DispatchMessageInner;
 
-pub use events::{DispatchMessage, ReturnData, SessionLockEvent};
+pub use events::{DispatchMessage, ReturnData, SessionLockEvent, XdgInfoChangedType};
+pub use seat::{SeatHandler, SeatId};
+pub use waycrate_shellcore::ZxdgOutputInfo;
 
 use wayland_client::{
     delegate_noop,
@@ -122,7 +122,7 @@ use wayland_client::{
         wl_buffer::WlBuffer,
         wl_compositor::WlCompositor,
         wl_display::WlDisplay,
-        wl_keyboard::{self, KeyState, KeymapFormat, WlKeyboard},
+        wl_keyboard::{self, WlKeyboard},
         wl_output::{self, WlOutput},
         wl_pointer::{self, WlPointer},
         wl_registry,
@@ -132,18 +132,20 @@ use wayland_client::{
         wl_surface::WlSurface,
         wl_touch::{self, WlTouch},
     },
-    ConnectError, Connection, Dispatch, DispatchError, EventQueue, Proxy, QueueHandle, WEnum,
+    ConnectError, Connection, Dispatch, DispatchError, EventQueue, Proxy, QueueHandle,
 };
 use wayland_protocols::ext::session_lock::v1::client::{
     ext_session_lock_manager_v1::ExtSessionLockManagerV1,
     ext_session_lock_surface_v1::{self, ExtSessionLockSurfaceV1},
-    ext_session_lock_v1::ExtSessionLockV1,
+    ext_session_lock_v1::{self, ExtSessionLockV1},
 };
 use wayland_protocols::wp::viewporter::client::{
     wp_viewport::WpViewport, wp_viewporter::WpViewporter,
 };
+use wayland_protocols::xdg::xdg_output::zv1::client::{
+    zxdg_output_manager_v1::ZxdgOutputManagerV1, zxdg_output_v1,
+};
 
-use wayland_cursor::{CursorImageBuffer, CursorTheme};
 use wayland_protocols::wp::cursor_shape::v1::client::{
     wp_cursor_shape_device_v1::WpCursorShapeDeviceV1,
     wp_cursor_shape_manager_v1::WpCursorShapeManagerV1,
@@ -167,10 +169,7 @@ use std::time::Duration;
 
 pub use calloop;
 
-use calloop::{
-    timer::{TimeoutAction, Timer},
-    Error as CallLoopError, EventLoop, LoopHandle,
-};
+use calloop::{Error as CallLoopError, EventLoop, LoopHandle};
 use calloop_wayland_source::WaylandSource;
 
 use wayland_client::backend::WaylandError;
@@ -192,6 +191,10 @@ pub enum SessonLockEventError {
     EventLoopInitError(#[from] CallLoopError),
     #[error("roundtrip Error")]
     RoundTripError(#[from] WaylandError),
+    #[error("the compositor refused to lock the session")]
+    LockRefused,
+    #[error("the compositor finished the session lock")]
+    LockFinished,
 }
 
 /// reexport the wayland objects which are needed
@@ -228,7 +231,7 @@ pub mod reexport {
         };
     }
     pub mod wp_cursor_shape_device_v1 {
-        pub use crate::strtoshape::ShapeName;
+        pub use waycrate_shellcore::strtoshape::ShapeName;
         pub use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::Shape;
     }
     pub mod wp_viewport {
@@ -314,6 +317,8 @@ pub struct WindowStateUnit<T> {
     size: (u32, u32),
     buffer: Option<WlBuffer>,
     session_shell: ExtSessionLockSurfaceV1,
+    wl_output: WlOutput,
+    zxdgoutput: Option<ZxdgOutputInfo>,
     fractional_scale: Option<WpFractionalScaleV1>,
     viewport: Option<WpViewport>,
     binding: Option<T>,
@@ -381,6 +386,35 @@ impl<T> WindowStateUnit<T> {
     pub fn get_wlsurface(&self) -> &WlSurface {
         &self.wl_surface
     }
+
+    /// get the output the lock surface is shown on
+    pub fn get_output(&self) -> &WlOutput {
+        &self.wl_output
+    }
+
+    /// get the xdg_output info of the output the lock surface is shown on. It is None if the
+    /// compositor does not support `zxdg_output_manager_v1`
+    pub fn get_xdgoutput_info(&self) -> Option<&ZxdgOutputInfo> {
+        self.zxdgoutput.as_ref()
+    }
+
+    /// destroy the lock surface and the objects bound to it
+    fn destroy(&self) {
+        self.session_shell.destroy();
+        if let Some(viewport) = self.viewport.as_ref() {
+            viewport.destroy();
+        }
+        if let Some(fractional_scale) = self.fractional_scale.as_ref() {
+            fractional_scale.destroy();
+        }
+        if let Some(xdg_info) = self.zxdgoutput.as_ref() {
+            xdg_info.zxdgoutput().destroy();
+        }
+        self.wl_surface.destroy();
+        if let Some(buffer) = self.buffer.as_ref() {
+            buffer.destroy();
+        }
+    }
     /// set the data binding to the unit
     pub fn set_binding(&mut self, binding: T) {
         self.binding = Some(binding);
@@ -418,54 +452,66 @@ pub struct WindowState<T> {
     current_surface: Option<WlSurface>,
     units: Vec<WindowStateUnit<T>>,
     message: Vec<(Option<id::Id>, DispatchMessageInner)>,
-
     connection: Option<Connection>,
     event_queue: Option<EventQueue<WindowState<T>>>,
+    queue_handle: Option<QueueHandle<WindowState<T>>>,
     wl_compositor: Option<WlCompositor>,
     shm: Option<WlShm>,
     cursor_manager: Option<WpCursorShapeManagerV1>,
     viewporter: Option<WpViewporter>,
     lock: Option<ExtSessionLockV1>,
     fractional_scale_manager: Option<WpFractionalScaleManagerV1>,
+    xdg_output_manager: Option<ZxdgOutputManagerV1>,
+    virtual_keyboard: Option<ZwpVirtualKeyboardV1>,
     globals: Option<GlobalList>,
 
     // base managers
-    seat: Option<WlSeat>,
-    keyboard_state: Option<xkb_keyboard::KeyboardState>,
-    pointer: Option<WlPointer>,
-    touch: Option<WlTouch>,
+    seats: Vec<waycrate_shellcore::SeatState>,
 
     // keyboard
     use_display_handle: bool,
+    // if the locked event is received
+    locked: bool,
+    themed_cursor: waycrate_shellcore::ThemedCursor,
     loop_handler: Option<LoopHandle<'static, Self>>,
-
-    last_touch_location: (f64, f64),
-    last_touch_id: i32,
+    timer_deadlines: waycrate_shellcore::TimerDeadlines,
 }
 
 impl<T> WindowState<T> {
+    /// You can save the virtual_keyboard here
+    pub fn set_virtual_keyboard(&mut self, keyboard: ZwpVirtualKeyboardV1) {
+        self.virtual_keyboard = Some(keyboard);
+    }
+
+    /// get the saved virtual_keyboard
+    pub fn get_virtual_keyboard(&self) -> Option<&ZwpVirtualKeyboardV1> {
+        self.virtual_keyboard.as_ref()
+    }
+
     /// with loop_handler you can do more thing
     pub fn get_loop_handler(&self) -> Option<&LoopHandle<'static, Self>> {
         self.loop_handler.as_ref()
     }
-    /// get a seat from state
-    pub fn get_seat(&self) -> &WlSeat {
-        self.seat.as_ref().unwrap()
+    /// get the first seat from state, see [SeatHandler::get_seat_with_id] for the others
+    ///
+    /// None if the compositor has no seat, or has removed all of them
+    pub fn get_seat(&self) -> Option<&WlSeat> {
+        self.seats.first().map(|seat| &seat.seat)
     }
 
-    /// get the keyboard
+    /// get the keyboard of the first seat
     pub fn get_keyboard(&self) -> Option<&WlKeyboard> {
-        Some(&self.keyboard_state.as_ref()?.keyboard)
+        Some(&self.seats.first()?.keyboard_state.as_ref()?.keyboard)
     }
 
-    /// get the pointer
+    /// get the pointer of the first seat
     pub fn get_pointer(&self) -> Option<&WlPointer> {
-        self.pointer.as_ref()
+        self.seats.first()?.pointer.as_ref()
     }
 
-    /// get the touch
+    /// get the touch of the first seat
     pub fn get_touch(&self) -> Option<&WlTouch> {
-        self.touch.as_ref()
+        self.seats.first()?.touch.as_ref()
     }
 }
 
@@ -505,24 +551,24 @@ impl<T> Default for WindowState<T> {
 
             connection: None,
             event_queue: None,
+            queue_handle: None,
             wl_compositor: None,
             shm: None,
             cursor_manager: None,
             viewporter: None,
             fractional_scale_manager: None,
+            xdg_output_manager: None,
+            virtual_keyboard: None,
             lock: None,
             globals: None,
 
-            seat: None,
-            keyboard_state: None,
-            pointer: None,
-            touch: None,
+            seats: Vec::new(),
 
             use_display_handle: false,
+            locked: false,
+            themed_cursor: waycrate_shellcore::ThemedCursor::default(),
             loop_handler: None,
-
-            last_touch_location: (0., 0.),
-            last_touch_id: 0,
+            timer_deadlines: Default::default(),
         }
     }
 }
@@ -551,13 +597,6 @@ impl<T> WindowState<T> {
         self.units.iter().find(|unit| unit.id == id)
     }
 
-    fn surface_id(&self) -> Option<id::Id> {
-        self.units
-            .iter()
-            .find(|unit| Some(&unit.wl_surface) == self.current_surface.as_ref())
-            .map(|unit| unit.id())
-    }
-
     fn get_id_from_surface(&self, surface: &WlSurface) -> Option<id::Id> {
         self.units
             .iter()
@@ -565,6 +604,11 @@ impl<T> WindowState<T> {
             .map(|unit| unit.id())
     }
 
+    /// if the compositor has locked the session, see [SessionLockEvent::Locked]
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
     /// use display_handle to render surface, not to create buffer yourself
     pub fn with_use_display_handle(mut self, use_display_handle: bool) -> Self {
         self.use_display_handle = use_display_handle;
@@ -586,17 +630,36 @@ impl<T: 'static> Dispatch<wl_registry::WlRegistry, ()> for WindowState<T> {
                 name,
                 interface,
                 version,
-            } => {
-                if interface == wl_output::WlOutput::interface().name {
-                    let output = proxy.bind::<wl_output::WlOutput, _, _>(name, version, qh, ());
-                    state.outputs.push((name, output.clone()));
-                    state
-                        .message
-                        .push((None, DispatchMessageInner::NewDisplay(output)));
-                }
+            } if interface == wl_output::WlOutput::interface().name => {
+                let output = proxy.bind::<wl_output::WlOutput, _, _>(name, version, qh, ());
+                state.outputs.push((name, output.clone()));
+                state
+                    .message
+                    .push((None, DispatchMessageInner::NewDisplay(output)));
+            }
+            wl_registry::Event::Global {
+                name, interface, ..
+            } if interface == wl_seat::WlSeat::interface().name => {
+                seat::add_seat(state, proxy, name, qh);
             }
             wl_registry::Event::GlobalRemove { name } => {
-                state.outputs.retain(|x| x.0 != name);
+                seat::remove_seat(state, name);
+                if let Some(index) = state.outputs.iter().position(|x| x.0 == name) {
+                    let (_, output) = state.outputs.remove(index);
+                    // NOTE: the lock surface of the output is not needed anymore
+                    while let Some(index) =
+                        state.units.iter().position(|unit| unit.wl_output == output)
+                    {
+                        let unit = state.units.remove(index);
+                        unit.destroy();
+                        state
+                            .message
+                            .push((Some(unit.id), DispatchMessageInner::SurfaceRemoved));
+                    }
+                    if output.version() >= 3 {
+                        output.release();
+                    }
+                }
                 state.units.retain(|unit| unit.wl_surface.is_alive());
             }
 
@@ -605,461 +668,76 @@ impl<T: 'static> Dispatch<wl_registry::WlRegistry, ()> for WindowState<T> {
     }
 }
 
-impl<T: 'static> Dispatch<wl_seat::WlSeat, ()> for WindowState<T> {
+impl<T: 'static> Dispatch<wl_seat::WlSeat, SeatId> for WindowState<T> {
     fn event(
         state: &mut Self,
         seat: &wl_seat::WlSeat,
         event: <wl_seat::WlSeat as Proxy>::Event,
-        _data: &(),
+        seat_id: &SeatId,
         _conn: &Connection,
         qh: &wayland_client::QueueHandle<Self>,
     ) {
-        use xkb_keyboard::KeyboardState;
-        if let wl_seat::Event::Capabilities {
-            capabilities: WEnum::Value(capabilities),
-        } = event
-        {
-            if capabilities.contains(wl_seat::Capability::Keyboard) {
-                state.keyboard_state = Some(KeyboardState::new(seat.get_keyboard(qh, ())));
-            }
-            if capabilities.contains(wl_seat::Capability::Pointer) {
-                state.pointer = Some(seat.get_pointer(qh, ()));
-            }
-            if capabilities.contains(wl_seat::Capability::Touch) {
-                state.touch = Some(seat.get_touch(qh, ()));
-            }
-        }
+        seat::seat_event(state, seat, event, *seat_id, qh);
     }
 }
 
-impl<T> Dispatch<wl_keyboard::WlKeyboard, ()> for WindowState<T> {
+impl<T: 'static> Dispatch<wl_keyboard::WlKeyboard, SeatId> for WindowState<T> {
     fn event(
         state: &mut Self,
         _proxy: &wl_keyboard::WlKeyboard,
         event: <wl_keyboard::WlKeyboard as Proxy>::Event,
-        _data: &(),
+        seat_id: &SeatId,
         _conn: &Connection,
-        _qhandle: &QueueHandle<Self>,
+        _qhandle: &wayland_client::QueueHandle<Self>,
     ) {
-        use keyboard::*;
-        use xkb_keyboard::ElementState;
-        let surface_id = state.surface_id();
-        let keyboard_state = state.keyboard_state.as_mut().unwrap();
-        match event {
-            wl_keyboard::Event::Keymap { format, fd, size } => match format {
-                WEnum::Value(KeymapFormat::XkbV1) => {
-                    let context = &mut keyboard_state.xkb_context;
-                    context.set_keymap_from_fd(fd, size as usize)
-                }
-                WEnum::Value(KeymapFormat::NoKeymap) => {
-                    log::warn!("non-xkb compatible keymap")
-                }
-                _ => unreachable!(),
-            },
-            wl_keyboard::Event::Enter { .. } => {
-                if let (Some(token), Some(loop_handle)) = (
-                    keyboard_state.repeat_token.take(),
-                    state.loop_handler.as_ref(),
-                ) {
-                    loop_handle.remove(token);
-                }
-            }
-            wl_keyboard::Event::Leave { .. } => {
-                keyboard_state.current_repeat = None;
-                state.message.push((
-                    surface_id,
-                    DispatchMessageInner::ModifiersChanged(ModifiersState::empty()),
-                ));
-                if let (Some(token), Some(loop_handle)) = (
-                    keyboard_state.repeat_token.take(),
-                    state.loop_handler.as_ref(),
-                ) {
-                    loop_handle.remove(token);
-                }
-            }
-            wl_keyboard::Event::Key {
-                state: keystate,
-                key,
-                ..
-            } => {
-                let pressed_state = match keystate {
-                    WEnum::Value(KeyState::Pressed) => ElementState::Pressed,
-                    WEnum::Value(KeyState::Released) => ElementState::Released,
-                    _ => {
-                        return;
-                    }
-                };
-                let key = key + 8;
-                if let Some(mut key_context) = keyboard_state.xkb_context.key_context() {
-                    let event = key_context.process_key_event(key, pressed_state, false);
-                    let event = DispatchMessageInner::KeyboardInput {
-                        event,
-                        is_synthetic: false,
-                    };
-                    state.message.push((surface_id, event));
-                }
-                match pressed_state {
-                    ElementState::Pressed => {
-                        let delay = match keyboard_state.repeat_info {
-                            RepeatInfo::Repeat { delay, .. } => delay,
-                            RepeatInfo::Disable => return,
-                        };
-                        if !keyboard_state
-                            .xkb_context
-                            .keymap_mut()
-                            .unwrap()
-                            .key_repeats(key)
-                        {
-                            return;
-                        }
-
-                        keyboard_state.current_repeat = Some(key);
-
-                        if let (Some(token), Some(loop_handle)) = (
-                            keyboard_state.repeat_token.take(),
-                            state.loop_handler.as_ref(),
-                        ) {
-                            loop_handle.remove(token);
-                        }
-                        let timer = Timer::from_duration(delay);
-
-                        if let Some(looph) = state.loop_handler.as_ref() {
-                            looph
-                                .insert_source(timer, move |_, _, state| {
-                                    let keyboard_state = match state.keyboard_state.as_mut() {
-                                        Some(keyboard_state) => keyboard_state,
-                                        None => return TimeoutAction::Drop,
-                                    };
-                                    let repeat_keycode = match keyboard_state.current_repeat {
-                                        Some(repeat_keycode) => repeat_keycode,
-                                        None => return TimeoutAction::Drop,
-                                    };
-                                    // NOTE: not the same key
-                                    if repeat_keycode != key {
-                                        return TimeoutAction::Drop;
-                                    }
-                                    if let Some(mut key_context) =
-                                        keyboard_state.xkb_context.key_context()
-                                    {
-                                        let event = key_context.process_key_event(
-                                            repeat_keycode,
-                                            pressed_state,
-                                            false,
-                                        );
-                                        let event = DispatchMessageInner::KeyboardInput {
-                                            event,
-                                            is_synthetic: false,
-                                        };
-                                        state.message.push((surface_id, event));
-                                    }
-                                    match keyboard_state.repeat_info {
-                                        RepeatInfo::Repeat { gap, .. } => {
-                                            TimeoutAction::ToDuration(gap)
-                                        }
-                                        RepeatInfo::Disable => TimeoutAction::Drop,
-                                    }
-                                })
-                                .ok();
-                        }
-                    }
-                    ElementState::Released => {
-                        if keyboard_state.repeat_info != RepeatInfo::Disable
-                            && keyboard_state
-                                .xkb_context
-                                .keymap_mut()
-                                .unwrap()
-                                .key_repeats(key)
-                            && Some(key) == keyboard_state.current_repeat
-                        {
-                            keyboard_state.current_repeat = None;
-                            if let (Some(token), Some(loop_handle)) = (
-                                keyboard_state.repeat_token.take(),
-                                state.loop_handler.as_ref(),
-                            ) {
-                                loop_handle.remove(token);
-                            }
-                        }
-                    }
-                }
-            }
-            wl_keyboard::Event::Modifiers {
-                mods_depressed,
-                mods_locked,
-                mods_latched,
-                group,
-                ..
-            } => {
-                let xkb_context = &mut keyboard_state.xkb_context;
-                let xkb_state = match xkb_context.state_mut() {
-                    Some(state) => state,
-                    None => return,
-                };
-                xkb_state.update_modifiers(mods_depressed, mods_latched, mods_locked, 0, 0, group);
-                let modifiers = xkb_state.modifiers();
-
-                state.message.push((
-                    state.surface_id(),
-                    DispatchMessageInner::ModifiersChanged(modifiers.into()),
-                ))
-            }
-            wl_keyboard::Event::RepeatInfo { rate, delay } => {
-                keyboard_state.repeat_info = if rate == 0 {
-                    // Stop the repeat once we get a disable event.
-                    keyboard_state.current_repeat = None;
-                    if let (Some(token), Some(loop_handle)) = (
-                        keyboard_state.repeat_token.take(),
-                        state.loop_handler.as_ref(),
-                    ) {
-                        loop_handle.remove(token);
-                    }
-                    RepeatInfo::Disable
-                } else {
-                    let gap = Duration::from_micros(1_000_000 / rate as u64);
-                    let delay = Duration::from_millis(delay as u64);
-                    RepeatInfo::Repeat { gap, delay }
-                };
-            }
-            _ => {}
-        }
+        seat::keyboard_event(state, event, *seat_id);
     }
 }
 
-impl<T> Dispatch<wl_touch::WlTouch, ()> for WindowState<T> {
+impl<T: 'static> Dispatch<wl_touch::WlTouch, SeatId> for WindowState<T> {
     fn event(
         state: &mut Self,
         _proxy: &wl_touch::WlTouch,
         event: <wl_touch::WlTouch as Proxy>::Event,
-        _data: &(),
+        seat_id: &SeatId,
         _conn: &Connection,
-        _qhandle: &QueueHandle<Self>,
+        _qhandle: &wayland_client::QueueHandle<Self>,
     ) {
-        match event {
-            wl_touch::Event::Down {
-                serial,
-                time,
-                surface,
-                id,
-                x,
-                y,
-            } => {
-                state.last_touch_location = (x, y);
-                state.message.push((
-                    state.get_id_from_surface(&surface),
-                    DispatchMessageInner::TouchDown {
-                        serial,
-                        time,
-                        id,
-                        x,
-                        y,
-                    },
-                ))
-            }
-            wl_touch::Event::Cancel => {
-                let (x, y) = state.last_touch_location;
-                let id = state.last_touch_id;
-                state
-                    .message
-                    .push((None, DispatchMessageInner::TouchCancel { id, x, y }))
-            }
-            wl_touch::Event::Up { serial, time, id } => {
-                let (x, y) = state.last_touch_location;
-                state.message.push((
-                    None,
-                    DispatchMessageInner::TouchUp {
-                        serial,
-                        time,
-                        id,
-                        x,
-                        y,
-                    },
-                ));
-            }
-            wl_touch::Event::Motion { time, id, x, y } => {
-                state.last_touch_location = (x, y);
-                state
-                    .message
-                    .push((None, DispatchMessageInner::TouchMotion { time, id, x, y }));
-            }
-            _ => {}
-        }
+        seat::touch_event(state, event, *seat_id);
     }
 }
 
-impl<T> Dispatch<wl_pointer::WlPointer, ()> for WindowState<T> {
+impl<T: 'static> Dispatch<wl_pointer::WlPointer, SeatId> for WindowState<T> {
     fn event(
         state: &mut Self,
         pointer: &wl_pointer::WlPointer,
         event: <wl_pointer::WlPointer as Proxy>::Event,
-        _data: &(),
+        seat_id: &SeatId,
         _conn: &Connection,
         _qhandle: &wayland_client::QueueHandle<Self>,
     ) {
-        let scale = state
-            .surface_id()
-            .and_then(|id| state.get_unit_with_id(id))
-            .map(|unit| unit.scale_float())
-            .unwrap_or(1.0);
-        match event {
-            wl_pointer::Event::Axis { time, axis, value } => match axis {
-                WEnum::Value(axis) => {
-                    let (mut horizontal, mut vertical) = <(AxisScroll, AxisScroll)>::default();
-                    match axis {
-                        wl_pointer::Axis::VerticalScroll => {
-                            vertical.absolute = value;
-                        }
-                        wl_pointer::Axis::HorizontalScroll => {
-                            horizontal.absolute = value;
-                        }
-                        _ => unreachable!(),
-                    };
-
-                    state.message.push((
-                        state.surface_id(),
-                        DispatchMessageInner::Axis {
-                            time,
-                            scale,
-                            horizontal,
-                            vertical,
-                            source: None,
-                        },
-                    ))
-                }
-                WEnum::Unknown(unknown) => {
-                    log::warn!(target: "sessionlockev", "{}: invalid pointer axis: {:x}", pointer.id(), unknown);
-                }
-            },
-            wl_pointer::Event::AxisStop { time, axis } => match axis {
-                WEnum::Value(axis) => {
-                    let (mut horizontal, mut vertical) = <(AxisScroll, AxisScroll)>::default();
-                    match axis {
-                        wl_pointer::Axis::VerticalScroll => vertical.stop = true,
-                        wl_pointer::Axis::HorizontalScroll => horizontal.stop = true,
-
-                        _ => unreachable!(),
-                    }
-
-                    state.message.push((
-                        state.surface_id(),
-                        DispatchMessageInner::Axis {
-                            time,
-                            scale,
-                            horizontal,
-                            vertical,
-                            source: None,
-                        },
-                    ));
-                }
-
-                WEnum::Unknown(unknown) => {
-                    log::warn!(target: "sessionlockev", "{}: invalid pointer axis: {:x}", pointer.id(), unknown);
-                }
-            },
-            wl_pointer::Event::AxisSource { axis_source } => match axis_source {
-                WEnum::Value(source) => state.message.push((
-                    state.surface_id(),
-                    DispatchMessageInner::Axis {
-                        horizontal: AxisScroll::default(),
-                        vertical: AxisScroll::default(),
-                        source: Some(source),
-                        time: 0,
-                        scale,
-                    },
-                )),
-                WEnum::Unknown(unknown) => {
-                    log::warn!(target: "sessionlockev", "unknown pointer axis source: {:x}", unknown);
-                }
-            },
-            wl_pointer::Event::AxisDiscrete { axis, discrete } => match axis {
-                WEnum::Value(axis) => {
-                    let (mut horizontal, mut vertical) = <(AxisScroll, AxisScroll)>::default();
-                    match axis {
-                        wl_pointer::Axis::VerticalScroll => {
-                            vertical.discrete = discrete;
-                        }
-
-                        wl_pointer::Axis::HorizontalScroll => {
-                            horizontal.discrete = discrete;
-                        }
-
-                        _ => unreachable!(),
-                    };
-
-                    state.message.push((
-                        state.surface_id(),
-                        DispatchMessageInner::Axis {
-                            time: 0,
-                            scale,
-                            horizontal,
-                            vertical,
-                            source: None,
-                        },
-                    ));
-                }
-
-                WEnum::Unknown(unknown) => {
-                    log::warn!(target: "sessionlockev", "{}: invalid pointer axis: {:x}", pointer.id(), unknown);
-                }
-            },
+        seat::pointer_event(state, pointer, event, *seat_id);
+    }
+}
 
-            wl_pointer::Event::Button {
-                state: btnstate,
-                serial,
-                button,
-                time,
-            } => {
-                state.message.push((
-                    state.surface_id(),
-                    DispatchMessageInner::MouseButton {
-                        state: btnstate,
-                        serial,
-                        button,
-                        time,
-                    },
-                ));
-            }
-            wl_pointer::Event::Enter {
-                serial,
-                surface,
-                surface_x,
-                surface_y,
-            } => {
-                state.current_surface = Some(surface.clone());
-                state.message.push((
-                    state.surface_id(),
-                    DispatchMessageInner::MouseEnter {
-                        pointer: pointer.clone(),
-                        serial,
-                        surface_x,
-                        surface_y,
-                    },
-                ));
-            }
-            wl_pointer::Event::Motion {
-                time,
-                surface_x,
-                surface_y,
-            } => {
-                state.message.push((
-                    state.surface_id(),
-                    DispatchMessageInner::MouseMotion {
-                        time,
-                        surface_x,
-                        surface_y,
-                    },
-                ));
-            }
-            wl_pointer::Event::Leave { .. } => {
-                state.current_surface = None;
-                state
-                    .message
-                    .push((state.surface_id(), DispatchMessageInner::MouseLeave));
-                if let Some(keyboard_state) = state.keyboard_state.as_mut() {
-                    keyboard_state.current_repeat = None;
-                }
+impl<T> Dispatch<ext_session_lock_v1::ExtSessionLockV1, ()> for WindowState<T> {
+    fn event(
+        state: &mut Self,
+        _proxy: &ext_session_lock_v1::ExtSessionLockV1,
+        event: <ext_session_lock_v1::ExtSessionLockV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_session_lock_v1::Event::Locked => {
+                state.locked = true;
+                state.message.push((None, DispatchMessageInner::Locked));
             }
-            _ => {
-                // TODO: not now
+            ext_session_lock_v1::Event::Finished => {
+                state.message.push((None, DispatchMessageInner::Finished));
             }
+            _ => {}
         }
     }
 }
@@ -1098,6 +776,34 @@ impl<T> Dispatch<ext_session_lock_surface_v1::ExtSessionLockSurfaceV1, ()> for W
     }
 }
 
+impl<T> Dispatch<zxdg_output_v1::ZxdgOutputV1, ()> for WindowState<T> {
+    fn event(
+        state: &mut Self,
+        proxy: &zxdg_output_v1::ZxdgOutputV1,
+        event: <zxdg_output_v1::ZxdgOutputV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        let Some(index) = state.units.iter().position(|info| {
+            info.zxdgoutput
+                .as_ref()
+                .is_some_and(|zxdgoutput| zxdgoutput.zxdgoutput() == proxy)
+        }) else {
+            return;
+        };
+        let info = &mut state.units[index];
+        let xdg_info = info.zxdgoutput.as_mut().unwrap();
+        let Some(change_type) = xdg_info.update(event) else {
+            return;
+        };
+        state.message.push((
+            Some(state.units[index].id),
+            DispatchMessageInner::XdgInfoChanged(change_type),
+        ));
+    }
+}
+
 impl<T> Dispatch<wp_fractional_scale_v1::WpFractionalScaleV1, ()> for WindowState<T> {
     fn event(
         state: &mut Self,
@@ -1136,7 +842,6 @@ delegate_noop!(@<T>WindowState<T>: ignore WlShmPool); // so it is pool, created
 delegate_noop!(@<T>WindowState<T>: ignore WlBuffer); // buffer show the picture
                                                      //
 
-delegate_noop!(@<T>WindowState<T>: ignore ExtSessionLockV1); // buffer show the picture
 delegate_noop!(@<T>WindowState<T>: ignore ExtSessionLockManagerV1); // buffer show the picture
 
 delegate_noop!(@<T>WindowState<T>: ignore WpCursorShapeManagerV1);
@@ -1151,6 +856,8 @@ delegate_noop!(@<T>WindowState<T>: ignore ZwpVirtualKeyboardManagerV1);
 // fractional_scale_manager
 delegate_noop!(@<T>WindowState<T>: ignore WpFractionalScaleManagerV1);
 
+delegate_noop!(@<T>WindowState<T>: ignore ZxdgOutputManagerV1);
+
 impl<T: 'static> WindowState<T> {
     pub fn build(mut self) -> Result<Self, SessonLockEventError> {
         let connection = Connection::connect_to_env()?;
@@ -1173,7 +880,6 @@ impl<T: 'static> WindowState<T> {
 
         let shm = globals.bind::<WlShm, _, _>(&qh, 1..=1, ())?;
         self.shm = Some(shm);
-        self.seat = Some(globals.bind::<WlSeat, _, _>(&qh, 1..=1, ())?);
 
         let cursor_manager = globals
             .bind::<WpCursorShapeManagerV1, _, _>(&qh, 1..=1, ())
@@ -1185,6 +891,9 @@ impl<T: 'static> WindowState<T> {
         let fractional_scale_manager = globals
             .bind::<WpFractionalScaleManagerV1, _, _>(&qh, 1..=1, ())
             .ok();
+        let xdg_output_manager = globals
+            .bind::<ZxdgOutputManagerV1, _, _>(&qh, 1..=3, ())
+            .ok();
         let lock_manager = globals.bind::<ExtSessionLockManagerV1, _, _>(&qh, 1..=1, ())?;
         event_queue.blocking_dispatch(&mut self)?; // then make a dispatch
         let lock = lock_manager.lock(&qh, ());
@@ -1207,6 +916,9 @@ impl<T: 'static> WindowState<T> {
             let viewport = viewporter
                 .as_ref()
                 .map(|viewport| viewport.get_viewport(&wl_surface, &qh, ()));
+            let zxdgoutput = xdg_output_manager.as_ref().map(|xdg_output_manager| {
+                ZxdgOutputInfo::new(xdg_output_manager.get_xdg_output(display, &qh, ()))
+            });
             self.units.push(WindowStateUnit {
                 id: id::Id::unique(),
                 display: connection.display(),
@@ -1214,6 +926,8 @@ impl<T: 'static> WindowState<T> {
                 size: (0, 0),
                 buffer: None,
                 session_shell: session_lock_surface,
+                wl_output: display.clone(),
+                zxdgoutput,
                 viewport,
                 fractional_scale,
                 binding: None,
@@ -1226,6 +940,7 @@ impl<T: 'static> WindowState<T> {
         self.cursor_manager = cursor_manager;
         self.lock = Some(lock);
         self.fractional_scale_manager = fractional_scale_manager;
+        self.xdg_output_manager = xdg_output_manager;
         self.globals = Some(globals);
         Ok(self)
     }
@@ -1271,14 +986,16 @@ impl<T: 'static> WindowState<T> {
         let globals = self.globals.take().unwrap();
         let event_queue = self.event_queue.take().unwrap();
         let qh = event_queue.handle();
-        let wmcompositer = self.wl_compositor.take().unwrap();
-        let shm = self.shm.take().unwrap();
+        self.queue_handle = Some(qh.clone());
+        let wmcompositer = self.wl_compositor.clone().unwrap();
+        let shm = self.shm.clone().unwrap();
         let fractional_scale_manager = self.fractional_scale_manager.take();
-        let cursor_manager: Option<WpCursorShapeManagerV1> = self.cursor_manager.take();
+        let xdg_output_manager = self.xdg_output_manager.take();
         let viewporter = self.viewporter.take();
-        let connection = self.connection.take().unwrap();
+        let connection = self.connection.clone().unwrap();
         let lock = self.lock.take().unwrap();
         let mut init_event = None;
+        let mut exit_error = None;
 
         while !matches!(init_event, Some(ReturnData::None)) {
             match init_event {
@@ -1333,14 +1050,15 @@ impl<T: 'static> WindowState<T> {
             event_loop.dispatch(Duration::from_millis(1), &mut self)?;
             let mut messages = Vec::new();
             std::mem::swap(&mut messages, &mut self.message);
+            // NOTE: the return data of Locked is handled with the one of the NormalDispatch
+            let mut return_data = Vec::new();
             for msg in messages.iter() {
                 match msg {
                     (Some(unit_index), DispatchMessageInner::RefreshSurface { width, height }) => {
-                        let index = self
-                            .units
-                            .iter()
-                            .position(|unit| unit.id == *unit_index)
-                            .unwrap();
+                        let Some(index) = self.units.iter().position(|unit| unit.id == *unit_index)
+                        else {
+                            continue;
+                        };
                         if self.units[index].buffer.is_none() && !self.use_display_handle {
                             let mut file = tempfile::tempfile()?;
                             let ReturnData::WlBuffer(buffer) = event_handler(
@@ -1372,6 +1090,35 @@ impl<T: 'static> WindowState<T> {
                             unit.wl_surface.commit();
                         }
                     }
+                    (Some(unit_index), DispatchMessageInner::SurfaceRemoved) => {
+                        event_handler(
+                            SessionLockEvent::SurfaceRemoved(*unit_index),
+                            &mut self,
+                            Some(*unit_index),
+                        );
+                    }
+                    (index_info, DispatchMessageInner::XdgInfoChanged(change_type)) => {
+                        event_handler(
+                            SessionLockEvent::XdgInfoChanged(*change_type),
+                            &mut self,
+                            *index_info,
+                        );
+                    }
+                    (_, DispatchMessageInner::Locked) => {
+                        return_data.push(event_handler(SessionLockEvent::Locked, &mut self, None));
+                    }
+                    (_, DispatchMessageInner::Finished) => {
+                        event_handler(SessionLockEvent::Finished, &mut self, None);
+                        // NOTE: the lock is inert now, it can only be destroyed
+                        lock.destroy();
+                        connection.roundtrip()?;
+                        exit_error = Some(if self.locked {
+                            SessonLockEventError::LockFinished
+                        } else {
+                            SessonLockEventError::LockRefused
+                        });
+                        break 'out;
+                    }
                     (_, DispatchMessageInner::NewDisplay(display)) => {
                         let wl_surface = wmcompositer.create_surface(&qh, ()); // and create a surface. if two or more,
                                                                                //
@@ -1394,6 +1141,9 @@ impl<T: 'static> WindowState<T> {
                         let viewport = viewporter
                             .as_ref()
                             .map(|viewport| viewport.get_viewport(&wl_surface, &qh, ()));
+                        let zxdgoutput = xdg_output_manager.as_ref().map(|xdg_output_manager| {
+                            ZxdgOutputInfo::new(xdg_output_manager.get_xdg_output(display, &qh, ()))
+                        });
                         self.units.push(WindowStateUnit {
                             id: id::Id::unique(),
                             display: connection.display(),
@@ -1401,6 +1151,8 @@ impl<T: 'static> WindowState<T> {
                             size: (0, 0),
                             buffer: None,
                             session_shell: session_lock_surface,
+                            wl_output: display.clone(),
+                            zxdgoutput,
                             viewport,
                             fractional_scale,
                             binding: None,
@@ -1410,44 +1162,19 @@ impl<T: 'static> WindowState<T> {
                     _ => {
                         let (index_message, msg) = msg;
                         let msg: DispatchMessage = msg.clone().into();
-                        match event_handler(
+                        let return_data = event_handler(
                             SessionLockEvent::RequestMessages(&msg),
                             &mut self,
                             *index_message,
-                        ) {
+                        );
+                        match return_data {
                             ReturnData::RequestUnlockAndExist => {
                                 lock.unlock_and_destroy();
                                 connection.roundtrip()?;
                                 break 'out;
                             }
                             ReturnData::RequestSetCursorShape((shape_name, pointer, serial)) => {
-                                if let Some(ref cursor_manager) = cursor_manager {
-                                    let Some(shape) = str_to_shape(&shape_name) else {
-                                        log::error!("Not supported shape");
-                                        continue;
-                                    };
-                                    let device = cursor_manager.get_pointer(&pointer, &qh, ());
-                                    device.set_shape(serial, shape);
-                                    device.destroy();
-                                } else {
-                                    let Some(cursor_buffer) =
-                                        get_cursor_buffer(&shape_name, &connection, &shm)
-                                    else {
-                                        log::error!("Cannot find cursor {shape_name}");
-                                        continue;
-                                    };
-                                    let cursor_surface = wmcompositer.create_surface(&qh, ());
-                                    cursor_surface.attach(Some(&cursor_buffer), 0, 0);
-                                    // and create a surface. if two or more,
-                                    let (hotspot_x, hotspot_y) = cursor_buffer.hotspot();
-                                    pointer.set_cursor(
-                                        serial,
-                                        Some(&cursor_surface),
-                                        hotspot_x as i32,
-                                        hotspot_y as i32,
-                                    );
-                                    cursor_surface.commit();
-                                }
+                                self.set_cursor_shape(&shape_name, &pointer, serial);
                             }
                             _ => {}
                         }
@@ -1466,42 +1193,16 @@ impl<T: 'static> WindowState<T> {
                         break 'out;
                     }
                     ReturnData::RequestSetCursorShape((shape_name, pointer, serial)) => {
-                        if let Some(ref cursor_manager) = cursor_manager {
-                            let Some(shape) = str_to_shape(&shape_name) else {
-                                log::error!("Not supported shape");
-                                continue;
-                            };
-                            let device = cursor_manager.get_pointer(&pointer, &qh, ());
-                            device.set_shape(serial, shape);
-                            device.destroy();
-                        } else {
-                            let Some(cursor_buffer) =
-                                get_cursor_buffer(&shape_name, &connection, &shm)
-                            else {
-                                log::error!("Cannot find cursor {shape_name}");
-                                continue;
-                            };
-                            let cursor_surface = wmcompositer.create_surface(&qh, ());
-                            cursor_surface.attach(Some(&cursor_buffer), 0, 0);
-                            // and create a surface. if two or more,
-                            let (hotspot_x, hotspot_y) = cursor_buffer.hotspot();
-                            pointer.set_cursor(
-                                serial,
-                                Some(&cursor_surface),
-                                hotspot_x as i32,
-                                hotspot_y as i32,
-                            );
-                            cursor_surface.commit();
-                        }
+                        self.set_cursor_shape(&shape_name, &pointer, serial);
                     }
                     _ => {}
                 }
             }
-            let mut return_data = vec![event_handler(
+            return_data.push(event_handler(
                 SessionLockEvent::NormalDispatch,
                 &mut self,
                 None,
-            )];
+            ));
             loop {
                 let mut replace_data = Vec::new();
                 for data in return_data {
@@ -1545,33 +1246,7 @@ impl<T: 'static> WindowState<T> {
                             break 'out;
                         }
                         ReturnData::RequestSetCursorShape((shape_name, pointer, serial)) => {
-                            if let Some(ref cursor_manager) = cursor_manager {
-                                let Some(shape) = str_to_shape(&shape_name) else {
-                                    eprintln!("Not supported shape");
-                                    continue;
-                                };
-                                let device = cursor_manager.get_pointer(&pointer, &qh, ());
-                                device.set_shape(serial, shape);
-                                device.destroy();
-                            } else {
-                                let Some(cursor_buffer) =
-                                    get_cursor_buffer(&shape_name, &connection, &shm)
-                                else {
-                                    eprintln!("Cannot find cursor {shape_name}");
-                                    continue;
-                                };
-                                let cursor_surface = wmcompositer.create_surface(&qh, ());
-                                cursor_surface.attach(Some(&cursor_buffer), 0, 0);
-                                // and create a surface. if two or more,
-                                let (hotspot_x, hotspot_y) = cursor_buffer.hotspot();
-                                pointer.set_cursor(
-                                    serial,
-                                    Some(&cursor_surface),
-                                    hotspot_x as i32,
-                                    hotspot_y as i32,
-                                );
-                                cursor_surface.commit();
-                            }
+                            self.set_cursor_shape(&shape_name, &pointer, serial);
                         }
                         _ => {}
                     }
@@ -1585,16 +1260,9 @@ impl<T: 'static> WindowState<T> {
         }
         to_exit.store(true, Ordering::Relaxed);
         let _ = thread.join();
-        Ok(())
+        match exit_error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
     }
 }
-
-fn get_cursor_buffer(
-    shape: &str,
-    connection: &Connection,
-    shm: &WlShm,
-) -> Option<CursorImageBuffer> {
-    let mut cursor_theme = CursorTheme::load(connection, shm.clone(), 23).ok()?;
-    let cursor = cursor_theme.get_cursor(shape);
-    Some(cursor?[0].clone())
-}
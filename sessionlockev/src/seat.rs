@@ -0,0 +1,53 @@
+use calloop::LoopHandle;
+pub(crate) use waycrate_shellcore::seat::{
+    add_seat, keyboard_event, pointer_event, remove_seat, seat_event, touch_event,
+};
+pub use waycrate_shellcore::{SeatHandler, SeatId};
+use waycrate_shellcore::{SeatMessage, SeatState, TimerDeadlines};
+use wayland_client::protocol::wl_surface::WlSurface;
+
+use crate::{events::DispatchMessageInner, id, WindowState};
+
+impl<T: 'static> SeatHandler for WindowState<T> {
+    type Id = id::Id;
+
+    fn seats(&self) -> &[SeatState] {
+        &self.seats
+    }
+
+    fn seats_mut(&mut self) -> &mut Vec<SeatState> {
+        &mut self.seats
+    }
+
+    fn surface_id(&self, surface: &WlSurface) -> Option<id::Id> {
+        self.get_id_from_surface(surface)
+    }
+
+    fn surface_scale(&self, id: id::Id) -> f64 {
+        self.get_unit_with_id(id)
+            .map(|unit| unit.scale_float())
+            .unwrap_or(1.0)
+    }
+
+    fn current_surface_mut(&mut self) -> &mut Option<WlSurface> {
+        &mut self.current_surface
+    }
+
+    fn loop_handle(&self) -> Option<&LoopHandle<'static, Self>> {
+        self.loop_handler.as_ref()
+    }
+
+    fn timer_deadlines(&self) -> &TimerDeadlines {
+        &self.timer_deadlines
+    }
+
+    fn push_seat_message(
+        &mut self,
+        id: Option<id::Id>,
+        seat_id: Option<SeatId>,
+        message: SeatMessage<id::Id>,
+    ) {
+        self.message
+            .push((id, DispatchMessageInner::Seat(seat_id, message)));
+    }
+}
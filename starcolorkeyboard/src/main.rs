@@ -113,7 +113,10 @@ fn main() {
                     .unwrap(),
             );
             let virtual_keyboard_manager = virtual_keyboard_manager.as_ref().unwrap();
-            let seat = ev.get_seat();
+            let Some(seat) = ev.get_seat() else {
+                eprintln!("the compositor has no wl_seat, cannot create the virtual keyboard");
+                return ReturnData::None;
+            };
             let virtual_keyboard_in =
                 virtual_keyboard_manager.create_virtual_keyboard(seat, qh, ());
             let (file, size) = get_keymap_as_file();
@@ -0,0 +1,244 @@
+use std::{collections::HashMap, time::Duration};
+
+use calloop::{
+    timer::{TimeoutAction, Timer},
+    RegistrationToken,
+};
+use wayland_client::{
+    protocol::{
+        wl_buffer::WlBuffer, wl_compositor::WlCompositor, wl_pointer::WlPointer, wl_shm::WlShm,
+        wl_surface::WlSurface,
+    },
+    Connection, Dispatch, Proxy, QueueHandle,
+};
+use wayland_cursor::{Cursor, CursorTheme};
+use wayland_protocols::wp::cursor_shape::v1::client::{
+    wp_cursor_shape_device_v1::WpCursorShapeDeviceV1,
+    wp_cursor_shape_manager_v1::WpCursorShapeManagerV1,
+};
+
+use crate::{strtoshape::str_to_shape, SeatHandler};
+
+/// the cursor size used when neither the settings nor `XCURSOR_SIZE` set it
+pub const DEFAULT_CURSOR_SIZE: u32 = 24;
+
+/// the name of the cursor theme: the one which is set, else `XCURSOR_THEME`, else `default`
+pub fn cursor_theme_name(cursor_theme: Option<&str>) -> String {
+    cursor_theme
+        .map(str::to_owned)
+        .or_else(|| std::env::var("XCURSOR_THEME").ok())
+        .unwrap_or_else(|| "default".to_owned())
+}
+
+/// the logical size of the cursor: the one which is set, else `XCURSOR_SIZE`, else
+/// [DEFAULT_CURSOR_SIZE]
+pub fn cursor_size(cursor_size: Option<u32>) -> u32 {
+    cursor_size
+        .or_else(|| {
+            std::env::var("XCURSOR_SIZE")
+                .ok()
+                .and_then(|size| size.parse().ok())
+        })
+        .unwrap_or(DEFAULT_CURSOR_SIZE)
+}
+
+/// the cursor theme used as fallback when the compositor does not support `wp_cursor_shape_v1`
+#[derive(Debug, Default)]
+pub struct ThemedCursor {
+    /// the loaded themes, for every buffer scale
+    themes: HashMap<u32, CursorTheme>,
+    surface: Option<WlSurface>,
+    animation: Option<RegistrationToken>,
+}
+
+/// The state of an event loop which can set the cursor of its pointers. The `WindowState` of
+/// layershellev and sessionlockev implements it, and passes the cursor requests to
+/// [set_cursor_shape] and [set_cursor_buffer].
+pub trait CursorHandler:
+    SeatHandler + Dispatch<WlSurface, ()> + Dispatch<WpCursorShapeDeviceV1, ()>
+{
+    fn themed_cursor_mut(&mut self) -> &mut ThemedCursor;
+
+    /// the name of the cursor theme, see [cursor_theme_name]
+    fn cursor_theme(&self) -> String;
+
+    /// the logical size of the cursor, see [cursor_size]
+    fn cursor_size(&self) -> u32;
+
+    fn cursor_manager(&self) -> Option<&WpCursorShapeManagerV1>;
+
+    fn connection(&self) -> Option<&Connection>;
+
+    fn shm(&self) -> Option<&WlShm>;
+
+    fn compositor(&self) -> Option<&WlCompositor>;
+
+    fn queue_handle(&self) -> Option<&QueueHandle<Self>>;
+}
+
+/// set the cursor of the pointer to the shape, with `wp_cursor_shape_v1` if the compositor
+/// supports it, else with the cursor theme
+pub fn set_cursor_shape<S: CursorHandler>(
+    state: &mut S,
+    shape_name: &str,
+    pointer: &WlPointer,
+    serial: u32,
+) {
+    let (Some(cursor_manager), Some(qh)) = (state.cursor_manager(), state.queue_handle()) else {
+        set_themed_cursor(state, shape_name, pointer, serial);
+        return;
+    };
+    let Some(shape) = str_to_shape(shape_name) else {
+        log::error!("Not supported shape");
+        return;
+    };
+    let device = cursor_manager.get_pointer(pointer, qh, ());
+    device.set_shape(serial, shape);
+    device.destroy();
+}
+
+/// the scale of the cursor images, the one of the surface under the pointer. It is 1 when the
+/// cursor surface cannot set its buffer scale.
+pub fn cursor_scale<S: CursorHandler>(state: &mut S) -> u32 {
+    // NOTE: wl_surface.set_buffer_scale is only available since version 3
+    match cursor_surface(state) {
+        Some(cursor_surface) if cursor_surface.version() >= 3 => {}
+        _ => return 1,
+    }
+    state
+        .current_surface_mut()
+        .clone()
+        .and_then(|surface| state.surface_id(&surface))
+        .map(|id| state.surface_scale(id).ceil() as u32)
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// set the cursor of the pointer to the buffer drawn for the scale, see [cursor_scale]. The size
+/// is the one of the buffer, and the hotspot is in logical coordinates. The caller keeps the
+/// buffer until the cursor is changed.
+pub fn set_cursor_buffer<S: CursorHandler>(
+    state: &mut S,
+    buffer: &WlBuffer,
+    scale: u32,
+    (width, height): (u32, u32),
+    (hotspot_x, hotspot_y): (u32, u32),
+    pointer: &WlPointer,
+    serial: u32,
+) {
+    let Some(cursor_surface) = cursor_surface(state) else {
+        return;
+    };
+    stop_cursor_animation(state);
+
+    if cursor_surface.version() >= 3 {
+        cursor_surface.set_buffer_scale(scale as i32);
+    }
+    cursor_surface.attach(Some(buffer), 0, 0);
+    cursor_surface.damage(0, 0, width as i32, height as i32);
+    cursor_surface.commit();
+    pointer.set_cursor(
+        serial,
+        Some(&cursor_surface),
+        hotspot_x as i32,
+        hotspot_y as i32,
+    );
+}
+
+fn stop_cursor_animation<S: CursorHandler>(state: &mut S) {
+    if let Some(token) = state.themed_cursor_mut().animation.take() {
+        if let Some(loop_handle) = state.loop_handle() {
+            state.timer_deadlines().remove(loop_handle, token);
+        }
+    }
+}
+
+fn set_themed_cursor<S: CursorHandler>(
+    state: &mut S,
+    shape_name: &str,
+    pointer: &WlPointer,
+    serial: u32,
+) {
+    let Some(cursor_surface) = cursor_surface(state) else {
+        return;
+    };
+    let scale = cursor_scale(state);
+    let Some(cursor) = load_cursor(state, shape_name, scale) else {
+        log::error!("Cannot find cursor {shape_name}");
+        return;
+    };
+
+    stop_cursor_animation(state);
+
+    let image = &cursor[0];
+    let (hotspot_x, hotspot_y) = image.hotspot();
+    if cursor_surface.version() >= 3 {
+        cursor_surface.set_buffer_scale(scale as i32);
+    }
+    cursor_surface.attach(Some(image), 0, 0);
+    let (width, height) = image.dimensions();
+    cursor_surface.damage(0, 0, width as i32, height as i32);
+    cursor_surface.commit();
+    pointer.set_cursor(
+        serial,
+        Some(&cursor_surface),
+        (hotspot_x / scale) as i32,
+        (hotspot_y / scale) as i32,
+    );
+
+    if cursor.image_count() > 1 {
+        animate_cursor(state, cursor, cursor_surface);
+    }
+}
+
+/// show the frames of an animated cursor one after another, until the cursor is changed
+fn animate_cursor<S: CursorHandler>(state: &mut S, cursor: Cursor, cursor_surface: WlSurface) {
+    let Some(loop_handle) = state.loop_handle() else {
+        return;
+    };
+    let mut frame = 0;
+    let first_delay = cursor[frame].delay();
+    let animation = state
+        .timer_deadlines()
+        .insert(
+            loop_handle,
+            Timer::from_duration(Duration::from_millis(first_delay as u64)),
+            move |_, _, _| {
+                frame = (frame + 1) % cursor.image_count();
+                let image = &cursor[frame];
+                let (width, height) = image.dimensions();
+                cursor_surface.attach(Some(image), 0, 0);
+                cursor_surface.damage(0, 0, width as i32, height as i32);
+                cursor_surface.commit();
+                TimeoutAction::ToDuration(Duration::from_millis(image.delay() as u64))
+            },
+        )
+        .ok();
+    state.themed_cursor_mut().animation = animation;
+}
+
+fn cursor_surface<S: CursorHandler>(state: &mut S) -> Option<WlSurface> {
+    if let Some(surface) = state.themed_cursor_mut().surface.as_ref() {
+        return Some(surface.clone());
+    }
+    let surface = state
+        .compositor()?
+        .create_surface(state.queue_handle()?, ());
+    state.themed_cursor_mut().surface = Some(surface.clone());
+    Some(surface)
+}
+
+fn load_cursor<S: CursorHandler>(state: &mut S, shape_name: &str, scale: u32) -> Option<Cursor> {
+    if !state.themed_cursor_mut().themes.contains_key(&scale) {
+        let theme = CursorTheme::load_from_name(
+            state.connection()?,
+            state.shm()?.clone(),
+            &state.cursor_theme(),
+            state.cursor_size() * scale,
+        )
+        .ok()?;
+        state.themed_cursor_mut().themes.insert(scale, theme);
+    }
+    let theme = state.themed_cursor_mut().themes.get_mut(&scale)?;
+    theme.get_cursor(shape_name).cloned()
+}
@@ -0,0 +1,16 @@
+//! The seat and output handling shared by layershellev and sessionlockev.
+//!
+//! The `WindowState` of both crates implements [SeatHandler], and its `Dispatch` impls for
+//! `wl_seat`, `wl_keyboard`, `wl_pointer` and `wl_touch` forward the events to the functions in
+//! [seat], so the key repeat, the keyboard focus and the pointer cannot drift between them. The
+//! cursor requests of both are handled by [cursor].
+pub mod cursor;
+pub mod seat;
+pub mod strtoshape;
+pub mod timer;
+pub mod xdg_output;
+
+pub use cursor::{CursorHandler, ThemedCursor, DEFAULT_CURSOR_SIZE};
+pub use seat::{AxisScroll, SeatHandler, SeatId, SeatMessage, SeatState};
+pub use timer::TimerDeadlines;
+pub use xdg_output::{XdgInfoChangedType, ZxdgOutputInfo};
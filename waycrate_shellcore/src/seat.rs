@@ -0,0 +1,824 @@
+use std::time::Duration;
+
+use calloop::{
+    timer::{TimeoutAction, Timer},
+    LoopHandle,
+};
+use waycrate_xkbkeycode::{
+    keyboard::ModifiersState,
+    xkb_keyboard::{ElementState, KeyEvent, KeyboardState, RepeatInfo},
+};
+use wayland_client::{
+    protocol::{
+        wl_keyboard::{self, KeyState, KeymapFormat, WlKeyboard},
+        wl_pointer::{self, ButtonState, WlPointer},
+        wl_registry::WlRegistry,
+        wl_seat::{self, WlSeat},
+        wl_surface::WlSurface,
+        wl_touch::{self, WlTouch},
+    },
+    Dispatch, Proxy, QueueHandle, WEnum,
+};
+
+use crate::TimerDeadlines;
+
+/// the id of a seat, it is the name of the `wl_seat` global, so it is unique during the
+/// connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SeatId(u32);
+
+impl SeatId {
+    pub fn new(name: u32) -> Self {
+        Self(name)
+    }
+
+    /// the name of the `wl_seat` global
+    pub fn global_name(&self) -> u32 {
+        self.0
+    }
+}
+
+/// the state of one seat, every seat has its own devices, xkb state and key repeat
+#[derive(Debug)]
+pub struct SeatState {
+    pub id: SeatId,
+    pub seat: WlSeat,
+    pub keyboard_state: Option<KeyboardState>,
+    pub pointer: Option<WlPointer>,
+    pub touch: Option<WlTouch>,
+    /// the surface the pointer of the seat is on
+    pub current_surface: Option<WlSurface>,
+    /// the surface with the keyboard focus of the seat
+    pub keyboard_surface: Option<WlSurface>,
+    pub last_touch_location: (f64, f64),
+    pub last_touch_id: i32,
+}
+
+impl SeatState {
+    pub fn new(id: SeatId, seat: WlSeat) -> Self {
+        Self {
+            id,
+            seat,
+            keyboard_state: None,
+            pointer: None,
+            touch: None,
+            current_surface: None,
+            keyboard_surface: None,
+            last_touch_location: (0., 0.),
+            last_touch_id: 0,
+        }
+    }
+}
+
+/// Describes a scroll along one axis
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct AxisScroll {
+    /// The scroll measured in pixels.
+    pub absolute: f64,
+
+    /// The scroll measured in steps.
+    ///
+    /// Note: this might always be zero if the scrolling is due to a touchpad or other continuous
+    /// source.
+    pub discrete: i32,
+
+    /// The scroll was stopped.
+    ///
+    /// Generally this is encountered when hardware indicates the end of some continuous scrolling.
+    pub stop: bool,
+}
+
+/// the input events of the seats, the event loops turn them into their own messages
+#[derive(Debug, Clone)]
+pub enum SeatMessage<Id> {
+    MouseButton {
+        state: WEnum<ButtonState>,
+        serial: u32,
+        button: u32,
+        time: u32,
+    },
+    MouseLeave,
+    MouseEnter {
+        pointer: WlPointer,
+        serial: u32,
+        surface_x: f64,
+        surface_y: f64,
+    },
+    MouseMotion {
+        time: u32,
+        surface_x: f64,
+        surface_y: f64,
+    },
+    Axis {
+        time: u32,
+        scale: f64,
+        horizontal: AxisScroll,
+        vertical: AxisScroll,
+        source: Option<wl_pointer::AxisSource>,
+    },
+    TouchDown {
+        serial: u32,
+        time: u32,
+        id: i32,
+        x: f64,
+        y: f64,
+    },
+    TouchUp {
+        serial: u32,
+        time: u32,
+        id: i32,
+        x: f64,
+        y: f64,
+    },
+    TouchMotion {
+        time: u32,
+        id: i32,
+        x: f64,
+        y: f64,
+    },
+    TouchCancel {
+        id: i32,
+        x: f64,
+        y: f64,
+    },
+    ModifiersChanged(ModifiersState),
+    KeyboardInput {
+        event: KeyEvent,
+        is_synthetic: bool,
+    },
+    /// the first keyboard entered the surface
+    Focused(Id),
+    /// the last keyboard left the surface
+    Unfocus,
+}
+
+/// The state of an event loop which handles the seats. The `Dispatch` impls of the state for
+/// `wl_seat`, `wl_keyboard`, `wl_pointer` and `wl_touch` forward the events to [seat_event],
+/// [keyboard_event], [pointer_event] and [touch_event], with the [SeatId] as user data.
+pub trait SeatHandler: Sized + 'static {
+    /// the id of the surfaces of the event loop
+    type Id: Copy + PartialEq + 'static;
+
+    fn seats(&self) -> &[SeatState];
+
+    fn seats_mut(&mut self) -> &mut Vec<SeatState>;
+
+    /// the id of the unit of the surface
+    fn surface_id(&self, surface: &WlSurface) -> Option<Self::Id>;
+
+    /// the scale of the unit with the id, the scroll is scaled with it
+    fn surface_scale(&self, id: Self::Id) -> f64;
+
+    /// the surface the pointer of any seat is on
+    fn current_surface_mut(&mut self) -> &mut Option<WlSurface>;
+
+    /// the loop handle, used to repeat the keys
+    fn loop_handle(&self) -> Option<&LoopHandle<'static, Self>>;
+
+    /// the deadlines of the timers inserted into the loop, like the key repeat
+    fn timer_deadlines(&self) -> &TimerDeadlines;
+
+    /// store the message, to pass it to the handler in the next dispatch
+    fn push_seat_message(
+        &mut self,
+        id: Option<Self::Id>,
+        seat_id: Option<SeatId>,
+        message: SeatMessage<Self::Id>,
+    );
+
+    /// called after a message is pushed by a repeated key, which does not come from the wayland
+    /// connection, so the event loop can wake up
+    fn wake(&self) {}
+
+    /// called when the pointer of a seat enters the surface with the id
+    fn pointer_entered(&mut self, _id: Option<Self::Id>) {}
+
+    fn seat_state(&self, seat_id: SeatId) -> Option<&SeatState> {
+        self.seats().iter().find(|seat| seat.id == seat_id)
+    }
+
+    fn seat_state_mut(&mut self, seat_id: SeatId) -> Option<&mut SeatState> {
+        self.seats_mut().iter_mut().find(|seat| seat.id == seat_id)
+    }
+
+    /// the first seat, used by the functions which do not take a seat
+    fn main_seat(&self) -> Option<&SeatState> {
+        self.seats().first()
+    }
+
+    /// get the ids of all seats, the first one is the [main seat](SeatHandler::main_seat)
+    fn get_seat_ids(&self) -> Vec<SeatId> {
+        self.seats().iter().map(|seat| seat.id).collect()
+    }
+
+    /// get the seat with the id
+    fn get_seat_with_id(&self, seat_id: SeatId) -> Option<&WlSeat> {
+        Some(&self.seat_state(seat_id)?.seat)
+    }
+
+    /// get the keyboard of the seat with the id
+    fn get_keyboard_with_seat(&self, seat_id: SeatId) -> Option<&WlKeyboard> {
+        Some(&self.seat_state(seat_id)?.keyboard_state.as_ref()?.keyboard)
+    }
+
+    /// get the pointer of the seat with the id
+    fn get_pointer_with_seat(&self, seat_id: SeatId) -> Option<&WlPointer> {
+        self.seat_state(seat_id)?.pointer.as_ref()
+    }
+
+    /// get the touch of the seat with the id
+    fn get_touch_with_seat(&self, seat_id: SeatId) -> Option<&WlTouch> {
+        self.seat_state(seat_id)?.touch.as_ref()
+    }
+
+    /// the id of the surface the pointer of the seat is on
+    fn seat_surface_id(&self, seat_id: SeatId) -> Option<Self::Id> {
+        let surface = self.seat_state(seat_id)?.current_surface.as_ref()?;
+        self.surface_id(surface)
+    }
+
+    /// the id of the surface with the keyboard focus of the seat
+    fn seat_keyboard_surface_id(&self, seat_id: SeatId) -> Option<Self::Id> {
+        let surface = self.seat_state(seat_id)?.keyboard_surface.as_ref()?;
+        self.surface_id(surface)
+    }
+
+    /// if the keyboard of any seat is focused on the surface with the id
+    fn is_keyboard_focused(&self, id: Self::Id) -> bool {
+        self.seats().iter().any(|seat| {
+            seat.keyboard_surface
+                .as_ref()
+                .is_some_and(|surface| self.surface_id(surface) == Some(id))
+        })
+    }
+}
+
+/// bind the `wl_seat` global with the name, call it with the `Global` event of the registry
+pub fn add_seat<S>(state: &mut S, registry: &WlRegistry, name: u32, qh: &QueueHandle<S>)
+where
+    S: SeatHandler + Dispatch<WlSeat, SeatId>,
+{
+    let seat_id = SeatId::new(name);
+    let seat = registry.bind::<WlSeat, _, _>(name, 1, qh, seat_id);
+    state.seats_mut().push(SeatState::new(seat_id, seat));
+}
+
+/// remove the seat of the global with the name if it is a `wl_seat`, call it with the
+/// `GlobalRemove` event of the registry
+pub fn remove_seat<S: SeatHandler>(state: &mut S, name: u32) {
+    let Some(index) = state
+        .seats()
+        .iter()
+        .position(|seat| seat.id.global_name() == name)
+    else {
+        return;
+    };
+    let seat = state.seats_mut().remove(index);
+    if let (Some(token), Some(loop_handle)) = (
+        seat.keyboard_state
+            .as_ref()
+            .and_then(|keyboard| keyboard.repeat_token),
+        state.loop_handle(),
+    ) {
+        state.timer_deadlines().remove(loop_handle, token);
+    }
+    if seat.current_surface.is_some() && *state.current_surface_mut() == seat.current_surface {
+        update_current_surface(state);
+    }
+    if let Some(id) = seat
+        .keyboard_surface
+        .as_ref()
+        .and_then(|surface| state.surface_id(surface))
+        .filter(|id| !state.is_keyboard_focused(*id))
+    {
+        state.push_seat_message(Some(id), None, SeatMessage::Unfocus);
+    }
+}
+
+/// NOTE: the pointer of another seat may be still on a surface
+fn update_current_surface<S: SeatHandler>(state: &mut S) {
+    let surface = state
+        .seats()
+        .iter()
+        .find_map(|seat| seat.current_surface.clone());
+    *state.current_surface_mut() = surface;
+}
+
+fn keyboard_state_mut<S: SeatHandler>(
+    state: &mut S,
+    seat_id: SeatId,
+) -> Option<&mut KeyboardState> {
+    state.seat_state_mut(seat_id)?.keyboard_state.as_mut()
+}
+
+fn cancel_repeat<S: SeatHandler>(
+    keyboard_state: &mut KeyboardState,
+    loop_handle: Option<&LoopHandle<'static, S>>,
+    timer_deadlines: &TimerDeadlines,
+) {
+    keyboard_state.current_repeat = None;
+    if let (Some(token), Some(loop_handle)) = (keyboard_state.repeat_token.take(), loop_handle) {
+        timer_deadlines.remove(loop_handle, token);
+    }
+}
+
+pub fn seat_event<S>(
+    state: &mut S,
+    seat: &WlSeat,
+    event: wl_seat::Event,
+    seat_id: SeatId,
+    qh: &QueueHandle<S>,
+) where
+    S: SeatHandler
+        + Dispatch<WlKeyboard, SeatId>
+        + Dispatch<WlPointer, SeatId>
+        + Dispatch<WlTouch, SeatId>,
+{
+    let Some(seat_state) = state.seat_state_mut(seat_id) else {
+        return;
+    };
+    if let wl_seat::Event::Capabilities {
+        capabilities: WEnum::Value(capabilities),
+    } = event
+    {
+        if capabilities.contains(wl_seat::Capability::Keyboard)
+            && seat_state.keyboard_state.is_none()
+        {
+            seat_state.keyboard_state = Some(KeyboardState::new(seat.get_keyboard(qh, seat_id)));
+        }
+        if capabilities.contains(wl_seat::Capability::Pointer) && seat_state.pointer.is_none() {
+            seat_state.pointer = Some(seat.get_pointer(qh, seat_id));
+        }
+        if capabilities.contains(wl_seat::Capability::Touch) && seat_state.touch.is_none() {
+            seat_state.touch = Some(seat.get_touch(qh, seat_id));
+        }
+    }
+}
+
+pub fn keyboard_event<S: SeatHandler>(state: &mut S, event: wl_keyboard::Event, seat_id: SeatId) {
+    // NOTE: the keys go to the surface with the keyboard focus of the seat
+    let surface_id = state
+        .seat_keyboard_surface_id(seat_id)
+        .or_else(|| state.seat_surface_id(seat_id));
+    let loop_handle = state.loop_handle().cloned();
+    let timer_deadlines = state.timer_deadlines().clone();
+    match event {
+        wl_keyboard::Event::Keymap { format, fd, size } => {
+            let Some(keyboard_state) = keyboard_state_mut(state, seat_id) else {
+                return;
+            };
+            match format {
+                WEnum::Value(KeymapFormat::XkbV1) => {
+                    let context = &mut keyboard_state.xkb_context;
+                    context.set_keymap_from_fd(fd, size as usize)
+                }
+                WEnum::Value(KeymapFormat::NoKeymap) => {
+                    log::warn!("non-xkb compatible keymap")
+                }
+                _ => unreachable!(),
+            }
+        }
+        wl_keyboard::Event::Enter { surface, .. } => {
+            if let Some(keyboard_state) = keyboard_state_mut(state, seat_id) {
+                cancel_repeat(keyboard_state, loop_handle.as_ref(), &timer_deadlines);
+            }
+            let id = state.surface_id(&surface);
+            // NOTE: the surface may be focused by the keyboard of another seat already
+            let was_focused = id.is_some_and(|id| state.is_keyboard_focused(id));
+            if let Some(seat_state) = state.seat_state_mut(seat_id) {
+                seat_state.keyboard_surface = Some(surface);
+            }
+            if let (Some(id), false) = (id, was_focused) {
+                state.push_seat_message(Some(id), Some(seat_id), SeatMessage::Focused(id));
+            }
+        }
+        wl_keyboard::Event::Leave { surface, .. } => {
+            if let Some(keyboard_state) = keyboard_state_mut(state, seat_id) {
+                cancel_repeat(keyboard_state, loop_handle.as_ref(), &timer_deadlines);
+            }
+            if let Some(seat_state) = state.seat_state_mut(seat_id) {
+                seat_state.keyboard_surface = None;
+            }
+            let id = state.surface_id(&surface);
+            state.push_seat_message(
+                id,
+                Some(seat_id),
+                SeatMessage::ModifiersChanged(ModifiersState::empty()),
+            );
+            if let Some(id) = id.filter(|id| !state.is_keyboard_focused(*id)) {
+                state.push_seat_message(Some(id), Some(seat_id), SeatMessage::Unfocus);
+            }
+        }
+        wl_keyboard::Event::Key {
+            state: keystate,
+            key,
+            ..
+        } => {
+            let pressed_state = match keystate {
+                WEnum::Value(KeyState::Pressed) => ElementState::Pressed,
+                WEnum::Value(KeyState::Released) => ElementState::Released,
+                _ => {
+                    return;
+                }
+            };
+            let key = key + 8;
+            let Some(keyboard_state) = keyboard_state_mut(state, seat_id) else {
+                return;
+            };
+            let event = keyboard_state
+                .xkb_context
+                .key_context()
+                .map(|mut key_context| key_context.process_key_event(key, pressed_state, false));
+
+            match pressed_state {
+                ElementState::Pressed => start_repeat(
+                    keyboard_state,
+                    loop_handle.as_ref(),
+                    &timer_deadlines,
+                    key,
+                    surface_id,
+                    seat_id,
+                ),
+                ElementState::Released => {
+                    if keyboard_state.repeat_info != RepeatInfo::Disable
+                        && keyboard_state
+                            .xkb_context
+                            .keymap_mut()
+                            .unwrap()
+                            .key_repeats(key)
+                        && Some(key) == keyboard_state.current_repeat
+                    {
+                        cancel_repeat(keyboard_state, loop_handle.as_ref(), &timer_deadlines);
+                    }
+                }
+            }
+
+            if let Some(event) = event {
+                state.push_seat_message(
+                    surface_id,
+                    Some(seat_id),
+                    SeatMessage::KeyboardInput {
+                        event,
+                        is_synthetic: false,
+                    },
+                );
+            }
+        }
+        wl_keyboard::Event::Modifiers {
+            mods_depressed,
+            mods_locked,
+            mods_latched,
+            group,
+            ..
+        } => {
+            let Some(keyboard_state) = keyboard_state_mut(state, seat_id) else {
+                return;
+            };
+            let Some(xkb_state) = keyboard_state.xkb_context.state_mut() else {
+                return;
+            };
+            xkb_state.update_modifiers(mods_depressed, mods_latched, mods_locked, 0, 0, group);
+            let modifiers = xkb_state.modifiers();
+
+            state.push_seat_message(
+                surface_id,
+                Some(seat_id),
+                SeatMessage::ModifiersChanged(modifiers.into()),
+            );
+        }
+        wl_keyboard::Event::RepeatInfo { rate, delay } => {
+            let Some(keyboard_state) = keyboard_state_mut(state, seat_id) else {
+                return;
+            };
+            keyboard_state.repeat_info = if rate == 0 {
+                // Stop the repeat once we get a disable event.
+                cancel_repeat(keyboard_state, loop_handle.as_ref(), &timer_deadlines);
+                RepeatInfo::Disable
+            } else {
+                let gap = Duration::from_micros(1_000_000 / rate as u64);
+                let delay = Duration::from_millis(delay as u64);
+                RepeatInfo::Repeat { gap, delay }
+            };
+        }
+        _ => {}
+    }
+}
+
+fn start_repeat<S: SeatHandler>(
+    keyboard_state: &mut KeyboardState,
+    loop_handle: Option<&LoopHandle<'static, S>>,
+    timer_deadlines: &TimerDeadlines,
+    key: u32,
+    surface_id: Option<S::Id>,
+    seat_id: SeatId,
+) {
+    let delay = match keyboard_state.repeat_info {
+        RepeatInfo::Repeat { delay, .. } => delay,
+        RepeatInfo::Disable => return,
+    };
+    if !keyboard_state
+        .xkb_context
+        .keymap_mut()
+        .unwrap()
+        .key_repeats(key)
+    {
+        return;
+    }
+
+    cancel_repeat(keyboard_state, loop_handle, timer_deadlines);
+    keyboard_state.current_repeat = Some(key);
+
+    let Some(loop_handle) = loop_handle else {
+        return;
+    };
+    keyboard_state.repeat_token = timer_deadlines
+        .insert(
+            loop_handle,
+            Timer::from_duration(delay),
+            move |_, _, state| repeat_key(state, key, surface_id, seat_id),
+        )
+        .ok();
+}
+
+fn repeat_key<S: SeatHandler>(
+    state: &mut S,
+    key: u32,
+    surface_id: Option<S::Id>,
+    seat_id: SeatId,
+) -> TimeoutAction {
+    let Some(keyboard_state) = keyboard_state_mut(state, seat_id) else {
+        return TimeoutAction::Drop;
+    };
+    // NOTE: not the same key
+    if keyboard_state.current_repeat != Some(key) {
+        return TimeoutAction::Drop;
+    }
+    let event = keyboard_state
+        .xkb_context
+        .key_context()
+        .map(|mut key_context| key_context.process_key_event(key, ElementState::Pressed, false));
+    let action = match keyboard_state.repeat_info {
+        RepeatInfo::Repeat { gap, .. } => TimeoutAction::ToDuration(gap),
+        RepeatInfo::Disable => TimeoutAction::Drop,
+    };
+    if let Some(event) = event {
+        state.push_seat_message(
+            surface_id,
+            Some(seat_id),
+            SeatMessage::KeyboardInput {
+                event,
+                is_synthetic: false,
+            },
+        );
+        state.wake();
+    }
+    action
+}
+
+pub fn touch_event<S: SeatHandler>(state: &mut S, event: wl_touch::Event, seat_id: SeatId) {
+    let Some(seat_state) = state.seat_state_mut(seat_id) else {
+        return;
+    };
+    match event {
+        wl_touch::Event::Down {
+            serial,
+            time,
+            surface,
+            id,
+            x,
+            y,
+        } => {
+            seat_state.last_touch_location = (x, y);
+            seat_state.last_touch_id = id;
+            let surface_id = state.surface_id(&surface);
+            state.push_seat_message(
+                surface_id,
+                Some(seat_id),
+                SeatMessage::TouchDown {
+                    serial,
+                    time,
+                    id,
+                    x,
+                    y,
+                },
+            );
+        }
+        wl_touch::Event::Cancel => {
+            let (x, y) = seat_state.last_touch_location;
+            let id = seat_state.last_touch_id;
+            state.push_seat_message(None, Some(seat_id), SeatMessage::TouchCancel { id, x, y });
+        }
+        wl_touch::Event::Up { serial, time, id } => {
+            let (x, y) = seat_state.last_touch_location;
+            state.push_seat_message(
+                None,
+                Some(seat_id),
+                SeatMessage::TouchUp {
+                    serial,
+                    time,
+                    id,
+                    x,
+                    y,
+                },
+            );
+        }
+        wl_touch::Event::Motion { time, id, x, y } => {
+            seat_state.last_touch_location = (x, y);
+            seat_state.last_touch_id = id;
+            state.push_seat_message(
+                None,
+                Some(seat_id),
+                SeatMessage::TouchMotion { time, id, x, y },
+            );
+        }
+        _ => {}
+    }
+}
+
+pub fn pointer_event<S: SeatHandler>(
+    state: &mut S,
+    pointer: &WlPointer,
+    event: wl_pointer::Event,
+    seat_id: SeatId,
+) {
+    let surface_id = state.seat_surface_id(seat_id);
+    let scale = surface_id.map(|id| state.surface_scale(id)).unwrap_or(1.0);
+    match event {
+        wl_pointer::Event::Axis { time, axis, value } => match axis {
+            WEnum::Value(axis) => {
+                let (mut horizontal, mut vertical) = <(AxisScroll, AxisScroll)>::default();
+                match axis {
+                    wl_pointer::Axis::VerticalScroll => {
+                        vertical.absolute = value;
+                    }
+                    wl_pointer::Axis::HorizontalScroll => {
+                        horizontal.absolute = value;
+                    }
+                    _ => unreachable!(),
+                };
+
+                state.push_seat_message(
+                    surface_id,
+                    Some(seat_id),
+                    SeatMessage::Axis {
+                        time,
+                        scale,
+                        horizontal,
+                        vertical,
+                        source: None,
+                    },
+                );
+            }
+            WEnum::Unknown(unknown) => {
+                log::warn!("{}: invalid pointer axis: {:x}", pointer.id(), unknown);
+            }
+        },
+        wl_pointer::Event::AxisStop { time, axis } => match axis {
+            WEnum::Value(axis) => {
+                let (mut horizontal, mut vertical) = <(AxisScroll, AxisScroll)>::default();
+                match axis {
+                    wl_pointer::Axis::VerticalScroll => vertical.stop = true,
+                    wl_pointer::Axis::HorizontalScroll => horizontal.stop = true,
+
+                    _ => unreachable!(),
+                }
+
+                state.push_seat_message(
+                    surface_id,
+                    Some(seat_id),
+                    SeatMessage::Axis {
+                        time,
+                        scale,
+                        horizontal,
+                        vertical,
+                        source: None,
+                    },
+                );
+            }
+
+            WEnum::Unknown(unknown) => {
+                log::warn!("{}: invalid pointer axis: {:x}", pointer.id(), unknown);
+            }
+        },
+        wl_pointer::Event::AxisSource { axis_source } => match axis_source {
+            WEnum::Value(source) => state.push_seat_message(
+                surface_id,
+                Some(seat_id),
+                SeatMessage::Axis {
+                    horizontal: AxisScroll::default(),
+                    vertical: AxisScroll::default(),
+                    scale,
+                    source: Some(source),
+                    time: 0,
+                },
+            ),
+            WEnum::Unknown(unknown) => {
+                log::warn!("unknown pointer axis source: {:x}", unknown);
+            }
+        },
+        wl_pointer::Event::AxisDiscrete { axis, discrete } => match axis {
+            WEnum::Value(axis) => {
+                let (mut horizontal, mut vertical) = <(AxisScroll, AxisScroll)>::default();
+                match axis {
+                    wl_pointer::Axis::VerticalScroll => {
+                        vertical.discrete = discrete;
+                    }
+
+                    wl_pointer::Axis::HorizontalScroll => {
+                        horizontal.discrete = discrete;
+                    }
+
+                    _ => unreachable!(),
+                };
+
+                state.push_seat_message(
+                    surface_id,
+                    Some(seat_id),
+                    SeatMessage::Axis {
+                        time: 0,
+                        scale,
+                        horizontal,
+                        vertical,
+                        source: None,
+                    },
+                );
+            }
+
+            WEnum::Unknown(unknown) => {
+                log::warn!("{}: invalid pointer axis: {:x}", pointer.id(), unknown);
+            }
+        },
+        wl_pointer::Event::Button {
+            state: btnstate,
+            serial,
+            button,
+            time,
+        } => {
+            state.push_seat_message(
+                surface_id,
+                Some(seat_id),
+                SeatMessage::MouseButton {
+                    state: btnstate,
+                    serial,
+                    button,
+                    time,
+                },
+            );
+        }
+        wl_pointer::Event::Leave { .. } => {
+            let Some(seat_state) = state.seat_state_mut(seat_id) else {
+                return;
+            };
+            let surface = seat_state.current_surface.take();
+            if let Some(keyboard_state) = seat_state.keyboard_state.as_mut() {
+                keyboard_state.current_repeat = None;
+            }
+            if *state.current_surface_mut() == surface {
+                update_current_surface(state);
+            }
+            state.push_seat_message(surface_id, Some(seat_id), SeatMessage::MouseLeave);
+        }
+        wl_pointer::Event::Enter {
+            serial,
+            surface,
+            surface_x,
+            surface_y,
+        } => {
+            *state.current_surface_mut() = Some(surface.clone());
+            if let Some(seat_state) = state.seat_state_mut(seat_id) {
+                seat_state.current_surface = Some(surface.clone());
+            }
+            let surface_id = state.surface_id(&surface);
+            state.pointer_entered(surface_id);
+
+            state.push_seat_message(
+                surface_id,
+                Some(seat_id),
+                SeatMessage::MouseEnter {
+                    pointer: pointer.clone(),
+                    serial,
+                    surface_x,
+                    surface_y,
+                },
+            );
+        }
+        wl_pointer::Event::Motion {
+            time,
+            surface_x,
+            surface_y,
+        } => {
+            state.push_seat_message(
+                surface_id,
+                Some(seat_id),
+                SeatMessage::MouseMotion {
+                    time,
+                    surface_x,
+                    surface_y,
+                },
+            );
+        }
+        _ => {
+            // TODO: not now
+        }
+    }
+}
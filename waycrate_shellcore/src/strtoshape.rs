@@ -1,6 +1,6 @@
 use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::Shape;
 
-pub(crate) fn str_to_shape(shape_name: &str) -> Option<Shape> {
+pub fn str_to_shape(shape_name: &str) -> Option<Shape> {
     match shape_name {
         "default" => Some(Shape::Default),
         "contenx_menu" => Some(Shape::ContextMenu),
@@ -0,0 +1,152 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Instant,
+};
+
+use calloop::{
+    timer::{TimeoutAction, Timer},
+    InsertError, LoopHandle, RegistrationToken,
+};
+
+/// The deadlines of the timers inserted with [TimerDeadlines::insert].
+///
+/// calloop timers have no fd, they only shorten the timeout of [calloop::EventLoop::dispatch].
+/// A main loop which only waits for the fd of the event loop has to wake up at
+/// [TimerDeadlines::next] too, else the timers never fire while the fd is quiet.
+#[derive(Debug, Clone, Default)]
+pub struct TimerDeadlines(Rc<RefCell<Vec<(RegistrationToken, Instant)>>>);
+
+impl TimerDeadlines {
+    /// the earliest deadline of the timers
+    pub fn next(&self) -> Option<Instant> {
+        self.0.borrow().iter().map(|(_, deadline)| *deadline).min()
+    }
+
+    /// forget the deadlines reached before a dispatch which started at `dispatch_start`, their
+    /// timers have fired during it
+    pub fn expire(&self, dispatch_start: Instant) {
+        self.0
+            .borrow_mut()
+            .retain(|(_, deadline)| *deadline > dispatch_start);
+    }
+
+    /// insert the timer into the loop, and keep its deadline until it is dropped or removed with
+    /// [TimerDeadlines::remove]
+    pub fn insert<Data, F>(
+        &self,
+        loop_handle: &LoopHandle<'static, Data>,
+        timer: Timer,
+        mut callback: F,
+    ) -> Result<RegistrationToken, InsertError<Timer>>
+    where
+        F: FnMut(Instant, &mut (), &mut Data) -> TimeoutAction + 'static,
+    {
+        let deadline = timer.current_deadline();
+        let deadlines = self.clone();
+        let registration: Rc<Cell<Option<RegistrationToken>>> = Rc::default();
+        let token = registration.clone();
+        let registration_token = loop_handle.insert_source(timer, move |now, metadata, data| {
+            let action = callback(now, metadata, data);
+            if let Some(token) = token.get() {
+                let deadline = match &action {
+                    TimeoutAction::Drop => None,
+                    TimeoutAction::ToInstant(deadline) => Some(*deadline),
+                    // NOTE: calloop computes the deadline before, so this one is never earlier
+                    TimeoutAction::ToDuration(duration) => Some(Instant::now() + *duration),
+                };
+                deadlines.set(token, deadline);
+            }
+            action
+        })?;
+        registration.set(Some(registration_token));
+        self.set(registration_token, deadline);
+        Ok(registration_token)
+    }
+
+    /// remove the timer from the loop, with its deadline
+    pub fn remove<Data>(&self, loop_handle: &LoopHandle<'static, Data>, token: RegistrationToken) {
+        loop_handle.remove(token);
+        self.set(token, None);
+    }
+
+    fn set(&self, token: RegistrationToken, deadline: Option<Instant>) {
+        let mut deadlines = self.0.borrow_mut();
+        deadlines.retain(|(registration_token, _)| *registration_token != token);
+        if let Some(deadline) = deadline {
+            deadlines.push((token, deadline));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use calloop::EventLoop;
+
+    use super::*;
+
+    #[test]
+    fn test_timer_deadlines_follow_the_timer() {
+        let mut event_loop: EventLoop<'static, u32> = EventLoop::try_new().unwrap();
+        let deadlines = TimerDeadlines::default();
+        let timer = Timer::from_duration(Duration::from_millis(5));
+        let deadline = timer.current_deadline();
+        deadlines
+            .insert(&event_loop.handle(), timer, |_, _, fired| {
+                *fired += 1;
+                if *fired < 2 {
+                    TimeoutAction::ToDuration(Duration::from_millis(5))
+                } else {
+                    TimeoutAction::Drop
+                }
+            })
+            .unwrap();
+        assert_eq!(deadlines.next(), deadline);
+
+        let mut fired = 0;
+        event_loop
+            .dispatch(Duration::from_millis(50), &mut fired)
+            .unwrap();
+        assert_eq!(fired, 1);
+        assert!(deadlines.next() > deadline);
+
+        event_loop
+            .dispatch(Duration::from_millis(50), &mut fired)
+            .unwrap();
+        assert_eq!(fired, 2);
+        assert_eq!(deadlines.next(), None);
+    }
+
+    #[test]
+    fn test_timer_deadlines_removed() {
+        let event_loop: EventLoop<'static, ()> = EventLoop::try_new().unwrap();
+        let deadlines = TimerDeadlines::default();
+        let token = deadlines
+            .insert(
+                &event_loop.handle(),
+                Timer::from_duration(Duration::from_secs(1)),
+                |_, _, _| TimeoutAction::Drop,
+            )
+            .unwrap();
+        assert!(deadlines.next().is_some());
+        deadlines.remove(&event_loop.handle(), token);
+        assert_eq!(deadlines.next(), None);
+    }
+
+    #[test]
+    fn test_timer_deadlines_expire() {
+        let event_loop: EventLoop<'static, ()> = EventLoop::try_new().unwrap();
+        let deadlines = TimerDeadlines::default();
+        let timer = Timer::from_duration(Duration::from_secs(1));
+        let deadline = timer.current_deadline().unwrap();
+        deadlines
+            .insert(&event_loop.handle(), timer, |_, _, _| TimeoutAction::Drop)
+            .unwrap();
+        deadlines.expire(deadline - Duration::from_millis(1));
+        assert_eq!(deadlines.next(), Some(deadline));
+        deadlines.expire(deadline);
+        assert_eq!(deadlines.next(), None);
+    }
+}
@@ -0,0 +1,80 @@
+use wayland_protocols::xdg::xdg_output::zv1::client::zxdg_output_v1::{self, ZxdgOutputV1};
+
+/// this tell the what kind of information is changed in [ZxdgOutputInfo]
+#[derive(Debug, Clone, Copy)]
+pub enum XdgInfoChangedType {
+    Position,
+    Size,
+    Name,
+    Description,
+}
+
+/// this struct store the xdg_output information
+#[derive(Debug, Clone)]
+pub struct ZxdgOutputInfo {
+    name: String,
+    description: String,
+    zxdgoutput: ZxdgOutputV1,
+    logical_size: (i32, i32),
+    position: (i32, i32),
+}
+
+impl ZxdgOutputInfo {
+    pub fn new(zxdgoutput: ZxdgOutputV1) -> Self {
+        Self {
+            zxdgoutput,
+            name: "".to_owned(),
+            description: "".to_owned(),
+            logical_size: (0, 0),
+            position: (0, 0),
+        }
+    }
+
+    /// the name of the output, like `DP-1`
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// the human readable description of the output
+    pub fn get_description(&self) -> &str {
+        &self.description
+    }
+
+    /// you can get the Logic position of the screen current surface in
+    pub fn get_position(&self) -> (i32, i32) {
+        self.position
+    }
+
+    /// you can get the LogicalPosition of the screen current surface in
+    pub fn get_logical_size(&self) -> (i32, i32) {
+        self.logical_size
+    }
+
+    /// the xdg_output the information comes from
+    pub fn zxdgoutput(&self) -> &ZxdgOutputV1 {
+        &self.zxdgoutput
+    }
+
+    /// store the information of the event, and return what is changed
+    pub fn update(&mut self, event: zxdg_output_v1::Event) -> Option<XdgInfoChangedType> {
+        match event {
+            zxdg_output_v1::Event::LogicalSize { width, height } => {
+                self.logical_size = (width, height);
+                Some(XdgInfoChangedType::Size)
+            }
+            zxdg_output_v1::Event::LogicalPosition { x, y } => {
+                self.position = (x, y);
+                Some(XdgInfoChangedType::Position)
+            }
+            zxdg_output_v1::Event::Name { name } => {
+                self.name = name;
+                Some(XdgInfoChangedType::Name)
+            }
+            zxdg_output_v1::Event::Description { description } => {
+                self.description = description;
+                Some(XdgInfoChangedType::Description)
+            }
+            _ => None,
+        }
+    }
+}